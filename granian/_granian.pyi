@@ -6,6 +6,8 @@ from .http import HTTP1Settings, HTTP2Settings
 
 __version__: str
 BUILD_GIL: bool
+TLS_CIPHER_SUITES: list[str]
+TLS_KX_GROUPS: list[str]
 
 class RSGIHeaders:
     def __contains__(self, key: str) -> bool: ...
@@ -66,6 +68,12 @@ class ASGIWorker:
         http2_opts: HTTP2Settings | None,
         websockets_enabled: bool,
         static_files: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], tuple[list[tuple[str, str]], str | None, str | None] | None, str]],
+        mounts: list[tuple[str, str]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
+        listeners: list[tuple[bool, tuple[ListenerSpec | None, SocketHolder | None]]],
+        affinity: tuple[list[int], bool],
         ssl_enabled: bool,
         ssl_cert: str | None,
         ssl_key: str | None,
@@ -74,6 +82,17 @@ class ASGIWorker:
         ssl_ca: str | None,
         ssl_crl: list[str],
         ssl_client_verify: bool,
+        ssl_reload_interval: int | None,
+        ssl_sni_certs: list[tuple[list[str], str, str, str | None, str | None]],
+        ssl_ocsp: str | None,
+        ssl_acme: str | None,
+        ssl_session_cache: int,
+        ssl_ciphers: list[str],
+        ssl_kx_groups: list[str],
+        ssl_pkcs12: bool,
+        ssl_ktls: bool,
+        ssl_client_rules: list[tuple[str, list[str], list[str], list[str], list[str]]],
+        ssl_tickets: TLSTicketKeys | None,
     ) -> ASGIWorker: ...
 
 class WSGIWorker:
@@ -90,6 +109,12 @@ class WSGIWorker:
         http1_opts: HTTP1Settings | None,
         http2_opts: HTTP2Settings | None,
         static_files: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], tuple[list[tuple[str, str]], str | None, str | None] | None, str]],
+        mounts: list[tuple[str, str]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
+        listeners: list[tuple[bool, tuple[ListenerSpec | None, SocketHolder | None]]],
+        affinity: tuple[list[int], bool],
         ssl_enabled: bool,
         ssl_cert: str | None,
         ssl_key: str | None,
@@ -98,6 +123,17 @@ class WSGIWorker:
         ssl_ca: str | None,
         ssl_crl: list[str],
         ssl_client_verify: bool,
+        ssl_reload_interval: int | None,
+        ssl_sni_certs: list[tuple[list[str], str, str, str | None, str | None]],
+        ssl_ocsp: str | None,
+        ssl_acme: str | None,
+        ssl_session_cache: int,
+        ssl_ciphers: list[str],
+        ssl_kx_groups: list[str],
+        ssl_pkcs12: bool,
+        ssl_ktls: bool,
+        ssl_client_rules: list[tuple[str, list[str], list[str], list[str], list[str]]],
+        ssl_tickets: TLSTicketKeys | None,
     ) -> WSGIWorker: ...
 
 class RSGIWorker:
@@ -115,6 +151,12 @@ class RSGIWorker:
         http2_opts: HTTP2Settings | None,
         websockets_enabled: bool,
         static_files: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], tuple[list[tuple[str, str]], str | None, str | None] | None, str]],
+        mounts: list[tuple[str, str]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
+        listeners: list[tuple[bool, tuple[ListenerSpec | None, SocketHolder | None]]],
+        affinity: tuple[list[int], bool],
         ssl_enabled: bool,
         ssl_cert: str | None,
         ssl_key: str | None,
@@ -123,6 +165,17 @@ class RSGIWorker:
         ssl_ca: str | None,
         ssl_crl: list[str],
         ssl_client_verify: bool,
+        ssl_reload_interval: int | None,
+        ssl_sni_certs: list[tuple[list[str], str, str, str | None, str | None]],
+        ssl_ocsp: str | None,
+        ssl_acme: str | None,
+        ssl_session_cache: int,
+        ssl_ciphers: list[str],
+        ssl_kx_groups: list[str],
+        ssl_pkcs12: bool,
+        ssl_ktls: bool,
+        ssl_client_rules: list[tuple[str, list[str], list[str], list[str], list[str]]],
+        ssl_tickets: TLSTicketKeys | None,
     ) -> RSGIWorker: ...
 
 class SocketHolder:
@@ -130,16 +183,42 @@ class SocketHolder:
     def is_uds(self) -> bool: ...
 
 class ListenerSpec:
-    def __new__(cls, host: str, port: int, backlog: int) -> ListenerSpec: ...
+    def __new__(
+        cls,
+        host: str,
+        port: int,
+        backlog: int,
+        opts: tuple[
+            int | None,
+            int | None,
+            int | None,
+            int | None,
+            int | None,
+            int | None,
+            int | None,
+            int | None,
+            bool | None,
+        ]
+        | None = None,
+    ) -> ListenerSpec: ...
     def build(self) -> SocketHolder: ...
     def is_uds(self) -> bool: ...
 
 if sys.platform != 'win32':
     class UnixListenerSpec:
-        def __new__(cls, bind: str, backlog: int, permissions: int | None) -> UnixListenerSpec: ...
+        def __new__(
+            cls,
+            bind: str,
+            backlog: int,
+            permissions: int | None = None,
+            owner: int | None = None,
+            group: int | None = None,
+        ) -> UnixListenerSpec: ...
         def build(self) -> SocketHolder: ...
         def is_uds(self) -> bool: ...
 
+    def listen_fds(backlog: int) -> list[tuple[str, SocketHolder]]: ...
+
 class CallbackScheduler:
     _loop: Any
     _ctx: Any
@@ -158,11 +237,36 @@ class IPCSenderHandle:
     def __init__(self, fd: int): ...
 
 class MetricsAggregator:
-    def __init__(self, size: int): ...
+    def __init__(self, size: int, cpus: list[str] = ..., interface: str = ..., buckets: list[float] = ...): ...
     def incr_spawn(self, val: int): ...
     def incr_respawn_err(self, val: int): ...
     def incr_respawn_ttl(self, val: int): ...
     def incr_respawn_rss(self, val: int): ...
+    def set_tls_certs(self, certs: list[tuple[str, int | None, bool]]): ...
+
+class ACMEManager:
+    def __init__(
+        self,
+        directory: str,
+        domains: list[str],
+        contact: list[str],
+        storage: str,
+        challenge: str,
+        ca_root: str | None = None,
+        terms_of_service_agreed: bool = False,
+    ): ...
+    def run(self, sig: WorkerSignal): ...
+
+class TLSTicketKeys:
+    def __init__(self, rotation: int, path: str | None = None): ...
+    @staticmethod
+    def from_keys(rotation: int, keys: list[bytes]) -> TLSTicketKeys: ...
+    def keys(self) -> list[bytes]: ...
+    def run(self, sig: WorkerSignal, senders: list[IPCSenderHandle]): ...
+    def listen(self, ipc: IPCReceiverHandle): ...
+
+def tls_validate_cert(cert: str, key: str, password: str | None = None, pkcs12: bool = False) -> tuple[str, int]: ...
+def tls_validate_client_auth(ca: str, crls: list[str]) -> None: ...
 
 class MetricsExporter:
     def __init__(self, aggregator: MetricsAggregator): ...
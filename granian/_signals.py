@@ -11,13 +11,16 @@ def _get_signals():
     return rv
 
 
-def set_main_signals(interrupt_handler, reload_handler=None):
+def set_main_signals(interrupt_handler, reload_handler=None, handoff_handler=None):
     for sig in _get_signals():
         signal.signal(sig, interrupt_handler)
 
     if reload_handler is not None and sys.platform != 'win32':
         signal.signal(signal.SIGHUP, reload_handler)
 
+    if handoff_handler is not None and sys.platform != 'win32':
+        signal.signal(signal.SIGUSR2, handoff_handler)
+
 
 def set_loop_signals(loop):
     signal_event = WorkerSignal()
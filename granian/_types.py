@@ -3,4 +3,23 @@ class WebsocketMessage:
     data: bytes | str
 
 
-SSLCtx = tuple[bool, str | None, str | None, str | None, str, str | None, list[str], bool]
+SSLCtx = tuple[
+    bool,
+    str | None,
+    str | None,
+    str | None,
+    str,
+    str | None,
+    list[str],
+    bool,
+    int | None,
+    list[tuple[list[str], str, str, str | None, str | None]],
+    str | None,
+    str | None,
+    int,
+    list[str],
+    list[str],
+    bool,
+    bool,
+    list[tuple[str, list[str], list[str], list[str], list[str]]],
+]
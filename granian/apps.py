@@ -0,0 +1,109 @@
+from collections.abc import Callable, Sequence
+from dataclasses import dataclass
+from functools import partial
+from pathlib import Path
+from typing import Any
+
+from ._futures import _future_watcher_wrapper
+from ._internal import load_target
+from .asgi import LifespanProtocol, _callback_wrapper as _asgi_call_wrap
+from .constants import Interfaces
+from .rsgi import _callback_wrapper as _rsgi_call_wrap, _callbacks_from_target as _rsgi_cbs_from_target
+from .wsgi import _callback_wrapper as _wsgi_call_wrap
+
+
+@dataclass
+class VirtualHost:
+    hosts: Sequence[str]
+    target: str | Callable[..., Any]
+    factory: bool = False
+    static_path_route: Sequence[str] | None = None
+    static_path_mount: Sequence[Path] | None = None
+    static_path_dir_to_file: str | None = None
+    static_path_expires: int = 86400
+    interface: Interfaces | None = None
+
+
+@dataclass
+class Mount:
+    prefix: str
+    target: str | Callable[..., Any]
+    factory: bool = False
+    interface: Interfaces | None = None
+
+
+def _load_callable(target, factory):
+    return target() if factory else target
+
+
+def build_target_loader(target: str | Callable[..., Any], factory: bool, wd: Path | None = None):
+    if isinstance(target, str):
+        return partial(load_target, target, wd=wd, factory=factory)
+    return partial(_load_callable, target, factory)
+
+
+def _apps_targets(vhosts, mounts, scope_opts):
+    #: vhosts first, then mounts: the order matters to the workers.
+    #  Mounted apps get their own prefix as root path.
+    return [
+        (f'virtual host {", ".join(hosts)}', interface, loader, scope_opts) for hosts, _, interface, loader in vhosts
+    ] + [
+        (f'mount {prefix}', interface, loader, {**scope_opts, 'url_path_prefix': prefix})
+        for prefix, interface, loader in mounts
+    ]
+
+
+def _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt):
+    #: every app is wrapped by its own interface; the worker runs
+    #  ASGI lifespans and RSGI init/del hooks alongside its main app ones.
+    callbacks, lifespans, inits, dels = [], [], [], []
+    for name, interface, loader, app_scope_opts in _apps_targets(vhosts, mounts, scope_opts):
+        target = loader()
+        if interface == Interfaces.WSGI:
+            callbacks.append(_wsgi_call_wrap(target, app_scope_opts, log_access_fmt))
+            continue
+        if interface == Interfaces.RSGI:
+            callback, callback_init, callback_del = _rsgi_cbs_from_target(target)
+            callbacks.append(_future_watcher_wrapper(_rsgi_call_wrap(callback, log_access_fmt)))
+            inits.append(callback_init)
+            dels.append(callback_del)
+            continue
+        state = {}
+        if interface == Interfaces.ASGI:
+            handler = LifespanProtocol(target, name)
+            lifespans.append(handler)
+            state = handler.state
+        callbacks.append(_future_watcher_wrapper(_asgi_call_wrap(target, app_scope_opts, state, log_access_fmt)))
+    return callbacks, lifespans, inits, dels
+
+
+def _apps_need_loop(vhosts, mounts):
+    return any(vhost[2] != Interfaces.WSGI for vhost in vhosts) or any(
+        mount[1] != Interfaces.WSGI for mount in mounts
+    )
+
+
+async def _apps_startup(loop, lifespans, inits):
+    #: returns the lifespan handler which failed its startup, if any
+    for cb_init in inits:
+        cb_init(loop)
+    for handler in lifespans:
+        await handler.startup()
+        if handler.interrupt:
+            return handler
+    return None
+
+
+def _apps_startup_error(handler):
+    #: the main app lifespan has no name, while the failing one might belong to a vhost or mount,
+    #  even when the main app is not an ASGI one.
+    if handler.name is None:
+        return 'ASGI lifespan startup failed'
+    return f'Application startup failed for {handler.name}'
+
+
+async def _apps_shutdown(loop, lifespans, dels):
+    for handler in lifespans:
+        await handler.shutdown()
+    for cb_del in dels:
+        cb_del(loop)
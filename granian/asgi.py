@@ -8,8 +8,9 @@ from .log import log_request_builder, logger
 class LifespanProtocol:
     error_transition = 'Invalid lifespan state transition'
 
-    def __init__(self, callable):
+    def __init__(self, callable, name: str | None = None):
         self.callable = callable
+        self.name = name
         self.event_queue = asyncio.Queue()
         self.event_startup = asyncio.Event()
         self.event_shutdown = asyncio.Event()
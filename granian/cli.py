@@ -7,15 +7,28 @@ from typing import Any, TypeVar
 
 import click
 
-from .constants import HTTPModes, Interfaces, Loops, RuntimeModes, SSLProtocols, TaskImpl
+from .apps import Mount, VirtualHost
+from .constants import (
+    ACMEChallenges,
+    CPUAffinity,
+    HTTPModes,
+    Interfaces,
+    Loops,
+    RuntimeModes,
+    SSLProfiles,
+    SSLProtocols,
+    TaskImpl,
+)
 from .errors import FatalError
 from .http import HTTP1Settings, HTTP2Settings
 from .log import LogLevels
+from .net import ACME_DIRECTORY, ACME_STORAGE, ClientCertRule, Listener, SNICertificate, TCPSettings
 from .server import Server
 
 
 _AnyCallable = Callable[..., Any]
 FC = TypeVar('FC', bound=_AnyCallable | click.Command)
+T = TypeVar('T')
 
 
 class Duration(click.IntRange):
@@ -91,6 +104,139 @@ class OctalIntType(click.ParamType):
             self.fail(str(e))
 
 
+def _split_interface(value: str) -> tuple[str, Interfaces | None | bool]:
+    #: `False` flags an unknown interface
+    head, sep, interface = value.rpartition('@')
+    if not sep:
+        return value, None
+    try:
+        return head, Interfaces(interface.strip())
+    except ValueError:
+        return head, False
+
+
+class OwnerType(click.ParamType):
+    name = 'NAME|ID'
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, int):
+            return value
+        return int(value) if value.isdigit() else value
+
+
+class VirtualHostType(click.ParamType):
+    name = 'HOST[,HOST...][@INTERFACE]=TARGET'
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, tuple):
+            return value
+
+        hosts, sep, target = value.partition('=')
+        hosts, interface = _split_interface(hosts)
+        hosts = [host.strip() for host in hosts.split(',') if host.strip()]
+        if not sep or not hosts or not target.strip() or interface is False:
+            self.fail(f'{value!r} is not a valid virtual host definition', param, ctx)
+        return (hosts, interface, target.strip())
+
+
+class MountType(click.ParamType):
+    name = 'PREFIX[@INTERFACE]=TARGET'
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, tuple):
+            return value
+
+        prefix, sep, target = value.partition('=')
+        prefix, interface = _split_interface(prefix)
+        if not sep or not prefix.startswith('/') or not target.strip() or interface is False:
+            self.fail(f'{value!r} is not a valid mount definition', param, ctx)
+        return (prefix.strip(), interface, target.strip())
+
+
+class ListenerType(click.ParamType):
+    name = '[tls:]HOST:PORT|[tls:]unix:PATH'
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, Listener):
+            return value
+
+        tls = value.startswith('tls:')
+        if tls:
+            value = value[4:]
+        if value.startswith('unix:'):
+            if not value[5:]:
+                self.fail(f'{value!r} is not a valid listener definition', param, ctx)
+            return Listener(uds=pathlib.Path(value[5:]), tls=tls)
+        host, sep, port = value.rpartition(':')
+        if not sep or not host or not port.isdigit():
+            self.fail(f'{value!r} is not a valid listener definition', param, ctx)
+        return Listener(address=host.strip('[]'), port=int(port), tls=tls)
+
+
+class SNICertificateType(click.ParamType):
+    name = 'HOST[,HOST...]=CERT:KEY[,OCSP]'
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, SNICertificate):
+            return value
+
+        hosts, sep, files = value.partition('=')
+        hosts = [host.strip() for host in hosts.split(',') if host.strip()]
+        files, osep, ocsp = files.partition(',')
+        cert, fsep, key = files.rpartition(':')
+        if not sep or not hosts or not fsep or not cert.strip() or not key.strip() or (osep and not ocsp.strip()):
+            self.fail(f'{value!r} is not a valid SNI certificate definition', param, ctx)
+        return SNICertificate(
+            hosts,
+            pathlib.Path(cert.strip()),
+            pathlib.Path(key.strip()),
+            ocsp_response=pathlib.Path(ocsp.strip()) if osep else None,
+        )
+
+
+class ClientCertRuleType(click.ParamType):
+    name = 'PREFIX[=KEY:VALUE[,KEY:VALUE...]]'
+    keys = {'cn': 'cn', 'ou': 'ou', 'san': 'san', 'sha256': 'fingerprints'}
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, ClientCertRule):
+            return value
+
+        prefix, _, matches = value.partition('=')
+        if not prefix.startswith('/'):
+            self.fail(f'{value!r} is not a valid client certificate rule definition', param, ctx)
+        rule = ClientCertRule(prefix.strip(), [], [], [], [])
+        for item in filter(None, (item.strip() for item in matches.split(','))):
+            key, sep, match = item.partition(':')
+            if not sep or key.strip() not in self.keys or not match.strip():
+                self.fail(f'{item!r} is not a valid client certificate rule match', param, ctx)
+            getattr(rule, self.keys[key.strip()]).append(match.strip())
+        return rule
+
+
+class ProxyRouteType(click.ParamType):
+    name = 'PREFIX=UPSTREAM'
+
+    def convert(self, value, param, ctx):
+        if value is None or isinstance(value, tuple):
+            return value
+
+        prefix, sep, upstream = value.partition('=')
+        if not sep or not prefix.startswith('/') or not upstream.strip():
+            self.fail(f'{value!r} is not a valid proxy route definition', param, ctx)
+        return (prefix.strip(), upstream.strip())
+
+
+def _exit_on_fatal(func: Callable[..., T]) -> Callable[..., T]:
+    def wrapped(*args: Any, **kwargs: Any) -> T:
+        try:
+            return func(*args, **kwargs)
+        except FatalError:
+            raise click.exceptions.Exit(1)
+
+    return wrapped
+
+
 def _pretty_print_default(value: bool | None) -> str | None:
     if isinstance(value, bool):
         return 'enabled' if value else 'disabled'
@@ -118,9 +264,23 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
 )
 @option('--port', type=int, default=8000, help='Port to bind to.')
 @option(
-    '--uds', type=click.Path(exists=False, writable=True, path_type=pathlib.Path), help='Unix Domain Socket to bind to.'
+    '--uds',
+    type=click.Path(exists=False, writable=True, path_type=pathlib.Path),
+    help='Unix Domain Socket to bind to (use a leading @ for abstract sockets on Linux).',
 )
 @option('--uds-permissions', type=OctalIntType(), default=None, help='Unix Domain Socket file permissions')
+@option('--uds-owner', type=OwnerType(), default=None, help='Unix Domain Socket file owner (user name or id)')
+@option('--uds-group', type=OwnerType(), default=None, help='Unix Domain Socket file group (group name or id)')
+@option(
+    '--listen',
+    'listeners',
+    type=ListenerType(),
+    multiple=True,
+    help=(
+        'Additional address to listen on, in the [tls:]HOST:PORT or [tls:]unix:PATH format. '
+        'The tls: prefix requires SSL to be configured. Can be specified multiple times.'
+    ),
+)
 @option(
     '--interface',
     type=EnumType(Interfaces),
@@ -153,6 +313,20 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
     default=RuntimeModes.auto,
     help='Runtime mode to use (single/multi threaded)',
 )
+@option(
+    '--cpu-affinity',
+    type=EnumType(CPUAffinity),
+    default=CPUAffinity.none,
+    help='CPU affinity policy for workers and runtime threads (Linux only)',
+)
+@option(
+    '--cpu-list',
+    multiple=True,
+    help=(
+        "CPUs to use for affinity, as a list of ranges (eg: '0-3,8') or a NUMA node (eg: 'node:0'). "
+        'Can be specified multiple times: with the list policy, each worker gets pinned to the sets in order.'
+    ),
+)
 @option('--loop', type=EnumType(Loops), default=Loops.auto, help='Event loop implementation')
 @option(
     '--task-impl',
@@ -172,6 +346,60 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
     show_default='backlog/workers',
     help='Maximum number of requests to process concurrently (per worker)',
 )
+@option(
+    '--tcp-keepalive-idle',
+    type=Duration(1),
+    help='Idle time (in seconds or a human-readable duration) before sending TCP keepalive probes',
+    show_default='system',
+)
+@option(
+    '--tcp-keepalive-interval',
+    type=Duration(1),
+    help='Interval (in seconds or a human-readable duration) between TCP keepalive probes',
+    show_default='system',
+)
+@option(
+    '--tcp-keepalive-count',
+    type=click.IntRange(1),
+    help='Number of unacknowledged TCP keepalive probes before dropping the connection',
+    show_default='system',
+)
+@option(
+    '--tcp-defer-accept',
+    type=Duration(1),
+    help='Time (in seconds or a human-readable duration) to wait for data before accepting connections (Linux only)',
+    show_default='disabled',
+)
+@option(
+    '--tcp-fastopen',
+    type=click.IntRange(1),
+    help='Enable TCP Fast Open with the given queue length (Linux only)',
+    show_default='disabled',
+)
+@option(
+    '--tcp-recv-buffer-size',
+    type=click.IntRange(1),
+    help='Sets the socket receive buffer size (SO_RCVBUF)',
+    show_default='system',
+)
+@option(
+    '--tcp-send-buffer-size',
+    type=click.IntRange(1),
+    help='Sets the socket send buffer size (SO_SNDBUF)',
+    show_default='system',
+)
+@option(
+    '--tcp-user-timeout',
+    type=click.IntRange(1),
+    help='Sets the maximum time (in milliseconds) transmitted data may remain unacknowledged (Linux only)',
+    show_default='system',
+)
+@option(
+    '--ipv6-only/--ipv6-dual-stack',
+    default=None,
+    help='Restrict IPv6 sockets to IPv6 connections only, or accept IPv4 connections too (dual-stack)',
+    show_default='system',
+)
 @option(
     '--http1-buffer-size',
     type=click.IntRange(8192),
@@ -267,11 +495,41 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
     help='SSL key file (PKCS#8 format only)',
 )
 @option('--ssl-keyfile-password', help='SSL key password')
+@option(
+    '--ssl-pkcs12',
+    type=click.Path(exists=True, file_okay=True, dir_okay=False, readable=True, path_type=pathlib.Path),
+    help=(
+        'SSL PKCS#12 bundle containing the certificate and key, as an alternative to the --ssl-certificate '
+        'and --ssl-keyfile options. Uses the --ssl-keyfile-password option as password.'
+    ),
+)
 @option(
     '--ssl-protocol-min',
     type=EnumType(SSLProtocols),
-    default=SSLProtocols.tls13,
-    help='Set the minimum supported protocol for SSL connections.',
+    help=(
+        'Set the minimum supported protocol for SSL connections. Defaults to tls1.3, '
+        'or to the --ssl-profile one, which can only be made stricter.'
+    ),
+)
+@option(
+    '--ssl-profile',
+    type=EnumType(SSLProfiles),
+    help=(
+        'SSL configuration preset, following Mozilla guidelines. Sets the minimum protocol, '
+        'and the default cipher suites and key exchange groups.'
+    ),
+)
+@option(
+    '--ssl-cipher',
+    'ssl_ciphers',
+    multiple=True,
+    help='SSL cipher suite to enable (IANA name), in order of preference. Can be specified multiple times.',
+)
+@option(
+    '--ssl-kx-group',
+    'ssl_kx_groups',
+    multiple=True,
+    help='SSL key exchange group to enable, in order of preference. Can be specified multiple times.',
 )
 @option(
     '--ssl-ca',
@@ -289,6 +547,116 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
     default=False,
     help='Verify clients SSL certificates',
 )
+@option(
+    '--ssl-client-rule',
+    'ssl_client_rules',
+    type=ClientCertRuleType(),
+    multiple=True,
+    help=(
+        'Require a valid client SSL certificate for requests under the given path prefix, optionally matching '
+        'subject CN, OU, SAN values or SHA-256 fingerprints (eg: /admin=cn:ops,sha256:AB:CD:...). '
+        'Can be specified multiple times.'
+    ),
+)
+@option(
+    '--ssl-sni-cert',
+    'ssl_sni_certs',
+    type=SNICertificateType(),
+    multiple=True,
+    help=(
+        'Additional SSL certificate to use for the given SNI host name(s), in the HOST[,HOST...]=CERT:KEY[,OCSP] '
+        'format. Hosts support a leading wildcard (eg: *.example.com). Can be specified multiple times.'
+    ),
+)
+@option(
+    '--ssl-ocsp-response',
+    type=click.Path(exists=True, file_okay=True, dir_okay=False, readable=True, path_type=pathlib.Path),
+    help=(
+        'DER encoded OCSP response file to staple to the SSL certificate. '
+        'The file gets checked for changes on the SSL reload interval (hourly if disabled)'
+    ),
+)
+@option(
+    '--ssl-reload-interval',
+    type=Duration(1),
+    help=(
+        'Interval (in seconds or a human-readable duration) to check the SSL certificate and keyfile for changes, '
+        'and reload them without restarting workers'
+    ),
+    show_default='disabled',
+)
+@option(
+    '--ssl-session-cache',
+    type=click.IntRange(0),
+    default=256,
+    help='Maximum number of SSL sessions to keep in memory for each worker for resumption (0 to disable)',
+)
+@option(
+    '--ssl-tickets/--no-ssl-tickets',
+    default=True,
+    help='Enable SSL session tickets, with keys shared across workers',
+)
+@option(
+    '--ssl-ticket-keys',
+    type=click.Path(exists=True, file_okay=True, dir_okay=False, readable=True, path_type=pathlib.Path),
+    help=(
+        'File containing the SSL session ticket keys (48 bytes each, the first one is used to issue tickets). '
+        'The file gets checked for changes on the rotation interval. If not set, keys are randomly generated'
+    ),
+)
+@option(
+    '--ssl-ticket-rotation',
+    type=Duration(60),
+    default=21600,
+    help='Interval (in seconds or a human-readable duration) to rotate the SSL session ticket keys',
+)
+@option(
+    '--ssl-ktls/--no-ssl-ktls',
+    default=False,
+    help='Offload TLS 1.3 encryption to the kernel (kTLS, Linux only), falling back to userspace when unavailable',
+)
+@option(
+    '--acme-domain',
+    'acme_domains',
+    multiple=True,
+    help=(
+        'Domain to obtain the SSL certificate for from an ACME certificate authority. '
+        'Can be specified multiple times, and excludes the --ssl-certificate and --ssl-keyfile options.'
+    ),
+)
+@option(
+    '--acme-directory',
+    default=ACME_DIRECTORY,
+    help='ACME directory URL of the certificate authority',
+)
+@option(
+    '--acme-contact',
+    'acme_contact',
+    multiple=True,
+    help='Contact email for the ACME account. Can be specified multiple times.',
+)
+@option(
+    '--acme-storage',
+    type=click.Path(file_okay=False, dir_okay=True, writable=True, path_type=pathlib.Path),
+    default=ACME_STORAGE,
+    help='Directory to store the ACME account and certificates into',
+)
+@option(
+    '--acme-challenge',
+    type=EnumType(ACMEChallenges),
+    default=ACMEChallenges.tls_alpn01,
+    help='ACME challenge type to answer',
+)
+@option(
+    '--acme-ca-root',
+    type=click.Path(exists=True, file_okay=True, dir_okay=False, readable=True, path_type=pathlib.Path),
+    help='Root certificate to trust when connecting to the ACME directory (eg: for testing with Pebble)',
+)
+@option(
+    '--acme-agree-tos/--no-acme-agree-tos',
+    default=False,
+    help='Agree to the terms of service of the ACME certificate authority (required to use ACME)',
+)
 @option('--url-path-prefix', help='URL path prefix the app is mounted on')
 @option(
     '--respawn-failed-workers/--no-respawn-failed-workers',
@@ -367,6 +735,54 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
     default=86400,
     help='Cache headers expiration (in seconds or a human-readable duration) for static file serving. 0 to disable.',
 )
+@option(
+    '--error-pages-path',
+    type=click.Path(exists=True, file_okay=False, dir_okay=True, readable=True, path_type=pathlib.Path),
+    help='Directory containing custom error pages (`<status>.html` and/or `<status>.json` files)',
+)
+@option(
+    '--vhost',
+    'vhosts',
+    type=VirtualHostType(),
+    multiple=True,
+    help=(
+        'Serve an additional application for the given host name(s), in the HOST[,HOST...][@INTERFACE]=TARGET '
+        'format. Hosts support a leading wildcard (eg: *.example.com). The interface defaults to the --interface '
+        'one. Can be specified multiple times.'
+    ),
+)
+@option(
+    '--mount',
+    'mounts',
+    type=MountType(),
+    multiple=True,
+    help=(
+        'Serve an additional application under the given path prefix, in the PREFIX[@INTERFACE]=TARGET format. '
+        'The interface defaults to the --interface one. Can be specified multiple times.'
+    ),
+)
+@option(
+    '--proxy-route',
+    'proxy_routes',
+    type=ProxyRouteType(),
+    multiple=True,
+    help=(
+        'Forward requests under the given path prefix to an upstream HTTP server, in the PREFIX=UPSTREAM format '
+        '(upstream should be either http://HOST:PORT or unix:PATH). Can be specified multiple times.'
+    ),
+)
+@option(
+    '--proxy-connect-timeout',
+    type=Duration(1),
+    default=5,
+    help='Timeout (in seconds or a human-readable duration) for connecting to proxy upstreams',
+)
+@option(
+    '--proxy-timeout',
+    type=Duration(1),
+    default=60,
+    help='Timeout (in seconds or a human-readable duration) for proxy upstreams responses',
+)
 @option('--metrics/--no-metrics', 'metrics_enabled', default=False, help='Enable the prometheus metrics exporter.')
 @option(
     '--metrics-scrape-interval', default=15, type=Duration(1, 60), help='Configure the interval for metrics collection.'
@@ -377,6 +793,16 @@ def option(*param_decls: str, cls: type[click.Option] | None = None, **attrs: An
     help='Metrics exporter host address to bind to',
 )
 @option('--metrics-port', type=int, default=9090, help='Metrics exporter port to bind to.')
+@option(
+    '--metrics-bucket',
+    'metrics_buckets',
+    type=click.FloatRange(min=0, min_open=True),
+    multiple=True,
+    help=(
+        'Upper bound (in seconds) of a bucket for the requests latency histograms, replacing the default ones. '
+        'Can be specified multiple times.'
+    ),
+)
 @option(
     '--reload/--no-reload',
     default=False,
@@ -438,6 +864,9 @@ def cli(
     port: int,
     uds: pathlib.Path | None,
     uds_permissions: int | None,
+    uds_owner: str | int | None,
+    uds_group: str | int | None,
+    listeners: list[Listener],
     interface: Interfaces,
     http: HTTPModes,
     websockets: bool,
@@ -447,10 +876,21 @@ def cli(
     runtime_threads: int,
     runtime_blocking_threads: int | None,
     runtime_mode: RuntimeModes,
+    cpu_affinity: CPUAffinity,
+    cpu_list: list[str],
     loop: Loops,
     task_impl: TaskImpl,
     backlog: int,
     backpressure: int | None,
+    tcp_keepalive_idle: int | None,
+    tcp_keepalive_interval: int | None,
+    tcp_keepalive_count: int | None,
+    tcp_defer_accept: int | None,
+    tcp_fastopen: int | None,
+    tcp_recv_buffer_size: int | None,
+    tcp_send_buffer_size: int | None,
+    tcp_user_timeout: int | None,
+    ipv6_only: bool | None,
     http1_buffer_size: int,
     http1_header_read_timeout: int,
     http1_keep_alive: bool,
@@ -472,10 +912,30 @@ def cli(
     ssl_certificate: pathlib.Path | None,
     ssl_keyfile: pathlib.Path | None,
     ssl_keyfile_password: str | None,
-    ssl_protocol_min: SSLProtocols,
+    ssl_pkcs12: pathlib.Path | None,
+    ssl_protocol_min: SSLProtocols | None,
+    ssl_profile: SSLProfiles | None,
+    ssl_ciphers: list[str],
+    ssl_kx_groups: list[str],
     ssl_ca: pathlib.Path | None,
     ssl_crl: list[pathlib.Path] | None,
     ssl_client_verify: bool,
+    ssl_client_rules: list[ClientCertRule],
+    ssl_sni_certs: list[SNICertificate],
+    ssl_ocsp_response: pathlib.Path | None,
+    ssl_reload_interval: int | None,
+    ssl_session_cache: int,
+    ssl_tickets: bool,
+    ssl_ticket_keys: pathlib.Path | None,
+    ssl_ticket_rotation: int,
+    ssl_ktls: bool,
+    acme_domains: list[str],
+    acme_directory: str,
+    acme_contact: list[str],
+    acme_storage: pathlib.Path,
+    acme_challenge: ACMEChallenges,
+    acme_ca_root: pathlib.Path | None,
+    acme_agree_tos: bool,
     url_path_prefix: str | None,
     respawn_failed_workers: bool,
     respawn_interval: float,
@@ -491,10 +951,17 @@ def cli(
     static_path_mount: list[pathlib.Path],
     static_path_dir_to_file: str | None,
     static_path_expires: int,
+    error_pages_path: pathlib.Path | None,
+    vhosts: list[tuple[list[str], str]],
+    mounts: list[tuple[str, str]],
+    proxy_routes: list[tuple[str, str]],
+    proxy_connect_timeout: int,
+    proxy_timeout: int,
     metrics_enabled: bool,
     metrics_scrape_interval: int,
     metrics_address: str,
     metrics_port: int,
+    metrics_buckets: list[float],
     reload: bool,
     reload_paths: list[pathlib.Path] | None,
     reload_ignore_dirs: list[str] | None,
@@ -518,7 +985,7 @@ def cli(
 
     patch_pypath(working_dir)
 
-    server = Server(
+    server = _exit_on_fatal(Server)(
         app,
         address=host,
         port=port,
@@ -562,10 +1029,30 @@ def cli(
         ssl_cert=ssl_certificate,
         ssl_key=ssl_keyfile,
         ssl_key_password=ssl_keyfile_password,
+        ssl_pkcs12=ssl_pkcs12,
         ssl_protocol_min=ssl_protocol_min,
+        ssl_profile=ssl_profile,
+        ssl_ciphers=ssl_ciphers,
+        ssl_kx_groups=ssl_kx_groups,
         ssl_ca=ssl_ca,
         ssl_crl=ssl_crl,
         ssl_client_verify=ssl_client_verify,
+        ssl_client_rules=ssl_client_rules,
+        ssl_reload_interval=ssl_reload_interval,
+        ssl_sni_certs=ssl_sni_certs,
+        ssl_ocsp_response=ssl_ocsp_response,
+        ssl_session_cache=ssl_session_cache,
+        ssl_tickets=ssl_tickets,
+        ssl_ticket_keys=ssl_ticket_keys,
+        ssl_ticket_rotation=ssl_ticket_rotation,
+        ssl_ktls=ssl_ktls,
+        acme_domains=acme_domains,
+        acme_directory=acme_directory,
+        acme_contact=acme_contact,
+        acme_storage=acme_storage,
+        acme_challenge=acme_challenge,
+        acme_ca_root=acme_ca_root,
+        acme_agree_tos=acme_agree_tos,
         url_path_prefix=url_path_prefix,
         respawn_failed_workers=respawn_failed_workers,
         respawn_interval=respawn_interval,
@@ -581,10 +1068,37 @@ def cli(
         static_path_mount=static_path_mount,
         static_path_dir_to_file=static_path_dir_to_file,
         static_path_expires=static_path_expires,
+        error_pages_path=error_pages_path,
+        vhosts=[
+            VirtualHost(hosts, target, factory=factory, interface=interface) for hosts, interface, target in vhosts
+        ],
+        mounts=[
+            Mount(prefix, target, factory=factory, interface=interface) for prefix, interface, target in mounts
+        ],
+        proxy_routes=proxy_routes,
+        proxy_connect_timeout=proxy_connect_timeout,
+        proxy_timeout=proxy_timeout,
+        listeners=listeners,
+        uds_owner=uds_owner,
+        uds_group=uds_group,
+        cpu_affinity=cpu_affinity,
+        cpu_list=cpu_list,
+        tcp_settings=TCPSettings(
+            keepalive_idle=tcp_keepalive_idle,
+            keepalive_interval=tcp_keepalive_interval,
+            keepalive_count=tcp_keepalive_count,
+            defer_accept=tcp_defer_accept,
+            fastopen=tcp_fastopen,
+            recv_buffer_size=tcp_recv_buffer_size,
+            send_buffer_size=tcp_send_buffer_size,
+            user_timeout=tcp_user_timeout,
+            ipv6_only=ipv6_only,
+        ),
         metrics_enabled=metrics_enabled,
         metrics_scrape_interval=metrics_scrape_interval,
         metrics_address=metrics_address,
         metrics_port=metrics_port,
+        metrics_buckets=metrics_buckets,
         reload=reload,
         reload_paths=reload_paths,
         reload_ignore_paths=reload_ignore_paths,
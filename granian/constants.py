@@ -38,6 +38,23 @@ class TaskImpl(StrEnum):
     rust = 'rust'
 
 
+class CPUAffinity(StrEnum):
+    none = 'none'
+    worker = 'worker'
+    thread = 'thread'
+    list = 'list'
+
+
 class SSLProtocols(StrEnum):
     tls12 = 'tls1.2'
     tls13 = 'tls1.3'
+
+
+class SSLProfiles(StrEnum):
+    modern = 'modern'
+    intermediate = 'intermediate'
+
+
+class ACMEChallenges(StrEnum):
+    http01 = 'http-01'
+    tls_alpn01 = 'tls-alpn-01'
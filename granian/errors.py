@@ -4,4 +4,14 @@ class FatalError(Exception): ...
 class ConfigurationError(FatalError): ...
 
 
+class SSLConfigurationError(ConfigurationError):
+    def __init__(self, param: str, message: str):
+        super().__init__(param, message)
+        self.param = param
+        self.message = message
+
+    def __str__(self):
+        return f'{self.param}: {self.message}'
+
+
 class PidFileError(FatalError): ...
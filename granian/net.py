@@ -1,15 +1,160 @@
 import copyreg
+import pickle
+import socket
 import sys
+from collections.abc import Sequence
+from dataclasses import astuple, dataclass
+from pathlib import Path
 
 from ._granian import ListenerSpec as SocketSpec, SocketHolder
+from .constants import SSLProfiles, SSLProtocols
 
 
 copyreg.pickle(SocketHolder, lambda v: (SocketHolder, v.__getstate__()))
 copyreg.pickle(SocketSpec, lambda v: (SocketSpec, v.__getstate__()))
 
 if sys.platform != 'win32':
-    from ._granian import UnixListenerSpec as UnixSocketSpec
+    from ._granian import UnixListenerSpec as UnixSocketSpec, listen_fds
 
     copyreg.pickle(UnixSocketSpec, lambda v: (UnixSocketSpec, v.__getstate__()))
 else:
     UnixSocketSpec = None
+    listen_fds = None
+
+
+#: environment variable carrying the handoff socket fd to a new main process
+HANDOFF_ENV = 'GRANIAN_HANDOFF_FD'
+HANDOFF_ACK = b'1'
+HANDOFF_MAXFDS = 1024
+
+#: ACME defaults and storage layout (the latter should match the one of the Rust ACME manager)
+ACME_DIRECTORY = 'https://acme-v02.api.letsencrypt.org/directory'
+ACME_STORAGE = '.acme'
+ACME_CERT_FILE = 'certificate.pem'
+ACME_HTTP_DIR = 'http-01'
+ACME_HTTP_ROUTE = '/.well-known/acme-challenge'
+
+#: TLS session ticket keys are made of a 16 bytes name and a 32 bytes AES-256 key
+TLS_TICKET_KEY_SIZE = 48
+
+#: warn about certificates expiring within 30 days
+TLS_CERT_EXPIRY_WARN = 86400 * 30
+
+#: TLS presets, following the Mozilla server side TLS guidelines
+TLS_PROFILES = {
+    SSLProfiles.modern: (
+        SSLProtocols.tls13,
+        ['TLS_AES_128_GCM_SHA256', 'TLS_AES_256_GCM_SHA384', 'TLS_CHACHA20_POLY1305_SHA256'],
+        ['X25519', 'secp256r1', 'secp384r1'],
+    ),
+    SSLProfiles.intermediate: (
+        SSLProtocols.tls12,
+        [
+            'TLS_AES_128_GCM_SHA256',
+            'TLS_AES_256_GCM_SHA384',
+            'TLS_CHACHA20_POLY1305_SHA256',
+            'TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256',
+            'TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256',
+            'TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384',
+            'TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384',
+            'TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256',
+            'TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256',
+        ],
+        ['X25519', 'secp256r1', 'secp384r1'],
+    ),
+}
+
+
+def handoff_send(conn: socket.socket, sockets):
+    fds, slots = [], []
+    for tls, (spec, holder) in sockets:
+        #: TCP sockets on Linux are bound by every worker, so we bind one more for the new main process:
+        #  the fd gets passed along as any other, and the new workers will share it.
+        if holder is None:
+            holder = spec.build()
+        #: fds numbers change on the receiving side, so we only send the rest of the holder state
+        fds.append(holder.get_fd())
+        slots.append((tls, holder.__getstate__()[1:]))
+    socket.send_fds(conn, [pickle.dumps(slots)], fds)
+
+
+def handoff_recv(conn: socket.socket):
+    msg, fds, _, _ = socket.recv_fds(conn, 65536, HANDOFF_MAXFDS)
+    return [
+        (tls, (None, SocketHolder(fd, *state)))
+        for fd, (tls, state) in zip(fds, pickle.loads(msg))  # noqa: S301
+    ]
+
+
+def uds_is_abstract(path: Path | None) -> bool:
+    return path is not None and str(path).startswith('@')
+
+
+def uds_resolve(path: Path | None) -> Path | None:
+    if path is None or uds_is_abstract(path):
+        return path
+    return path.resolve()
+
+
+def uds_unlink(path: Path | None):
+    if path is None or uds_is_abstract(path):
+        return
+    if path.exists():
+        path.unlink()
+
+
+def uds_owner_ids(owner: str | int | None, group: str | int | None) -> tuple[int | None, int | None]:
+    import grp
+    import pwd
+
+    uid = gid = None
+    if owner is not None:
+        uid = owner if isinstance(owner, int) else pwd.getpwnam(owner).pw_uid
+    if group is not None:
+        gid = group if isinstance(group, int) else grp.getgrnam(group).gr_gid
+    return uid, gid
+
+
+@dataclass
+class Listener:
+    address: str = '127.0.0.1'
+    port: int = 8000
+    uds: Path | None = None
+    uds_permissions: int | None = None
+    uds_owner: str | int | None = None
+    uds_group: str | int | None = None
+    tls: bool = False
+
+
+@dataclass
+class SNICertificate:
+    hostnames: Sequence[str]
+    cert: Path
+    key: Path
+    key_password: str | None = None
+    ocsp_response: Path | None = None
+
+
+@dataclass
+class ClientCertRule:
+    path: str
+    cn: Sequence[str] = ()
+    ou: Sequence[str] = ()
+    san: Sequence[str] = ()
+    fingerprints: Sequence[str] = ()
+
+
+@dataclass
+class TCPSettings:
+    keepalive_idle: int | None = None
+    keepalive_interval: int | None = None
+    keepalive_count: int | None = None
+    defer_accept: int | None = None
+    fastopen: int | None = None
+    recv_buffer_size: int | None = None
+    send_buffer_size: int | None = None
+    user_timeout: int | None = None
+    ipv6_only: bool | None = None
+
+    def as_opts(self):
+        return astuple(self)
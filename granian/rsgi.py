@@ -1,6 +1,7 @@
 import time
 from enum import Enum
 from functools import wraps
+from typing import Any
 
 from ._granian import (
     RSGIHeaders as Headers,
@@ -23,6 +24,8 @@ class Scope:
     path: str
     query_string: str
     authority: str | None
+    peer_cred: tuple[int | None, int, int] | None
+    tls: dict[str, Any] | None
 
     @property
     def headers(self) -> Headers: ...
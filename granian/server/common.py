@@ -3,32 +3,84 @@ from __future__ import annotations
 import errno
 import multiprocessing
 import os
-import ssl
+import re
+import socket
+import subprocess
 import sys
 import threading
 import time
 from collections.abc import Callable, Sequence
+from dataclasses import replace
 from functools import partial
 from pathlib import Path
 from typing import Any, Generic, TypeVar
+from urllib.parse import urlsplit
 
 from .._compat import _PY_312, _PYV
-from .._granian import MetricsAggregator, MetricsExporter, WorkerSignal
+from .._granian import (
+    TLS_CIPHER_SUITES,
+    TLS_KX_GROUPS,
+    ACMEManager,
+    MetricsAggregator,
+    MetricsExporter,
+    TLSTicketKeys,
+    WorkerSignal,
+    tls_validate_cert,
+    tls_validate_client_auth,
+)
 from .._imports import dotenv, setproctitle, watchfiles
 from .._internal import build_env_loader, load_target
 from .._signals import set_main_signals
-from ..constants import HTTPModes, Interfaces, Loops, RuntimeModes, SSLProtocols, TaskImpl
-from ..errors import ConfigurationError, PidFileError
+from ..apps import Mount, VirtualHost, build_target_loader
+from ..constants import (
+    ACMEChallenges,
+    CPUAffinity,
+    HTTPModes,
+    Interfaces,
+    Loops,
+    RuntimeModes,
+    SSLProfiles,
+    SSLProtocols,
+    TaskImpl,
+)
+from ..errors import ConfigurationError, PidFileError, SSLConfigurationError
 from ..http import HTTP1Settings, HTTP2Settings
 from ..log import DEFAULT_ACCESSLOG_FMT, LogLevels, configure_logging, logger
-from ..net import SocketSpec, UnixSocketSpec
+from ..net import (
+    ACME_CERT_FILE,
+    ACME_DIRECTORY,
+    ACME_HTTP_DIR,
+    ACME_HTTP_ROUTE,
+    ACME_STORAGE,
+    HANDOFF_ACK,
+    HANDOFF_ENV,
+    TLS_CERT_EXPIRY_WARN,
+    TLS_PROFILES,
+    TLS_TICKET_KEY_SIZE,
+    ClientCertRule,
+    Listener,
+    SNICertificate,
+    SocketSpec,
+    TCPSettings,
+    UnixSocketSpec,
+    handoff_recv,
+    handoff_send,
+    listen_fds,
+    uds_owner_ids,
+    uds_resolve,
+    uds_unlink,
+)
 
 
 WT = TypeVar('WT')
 
+HANDOFF_TIMEOUT = 30
+
+METRICS_BUCKETS = (0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0)
+
 WORKERS_METHODS = {
-    RuntimeModes.mt: {False: 'serve_mtr', True: 'serve_mtr_uds'},
-    RuntimeModes.st: {False: 'serve_str', True: 'serve_str_uds'},
+    RuntimeModes.mt: {False: 'serve_mtr', True: 'serve_mtr_uds', 'multi': 'serve_mtr_multi'},
+    RuntimeModes.st: {False: 'serve_str', True: 'serve_str_uds', 'multi': 'serve_str_multi'},
 }
 
 
@@ -39,6 +91,20 @@ def _tcp_migrate_req_enabled():
         return False
 
 
+def _proxy_upstream_authority(upstream: str) -> str | None:
+    #: anything after HOST:PORT (paths, queries, credentials) is not supported
+    try:
+        url = urlsplit(upstream)
+        port = url.port
+    except ValueError:
+        return None
+    if url.scheme != 'http' or not url.hostname or not port or url.username is not None:
+        return None
+    if url.path.strip('/') or url.query or url.fragment:
+        return None
+    return url.netloc
+
+
 class AbstractWorker:
     _idl = 'id'
 
@@ -117,10 +183,30 @@ class AbstractServer(Generic[WT]):
         ssl_cert: Path | None = None,
         ssl_key: Path | None = None,
         ssl_key_password: str | None = None,
-        ssl_protocol_min: SSLProtocols = SSLProtocols.tls13,
+        ssl_pkcs12: Path | None = None,
+        ssl_protocol_min: SSLProtocols | None = None,
+        ssl_profile: SSLProfiles | None = None,
+        ssl_ciphers: Sequence[str] | None = None,
+        ssl_kx_groups: Sequence[str] | None = None,
         ssl_ca: Path | None = None,
         ssl_crl: list[Path] | None = None,
         ssl_client_verify: bool = False,
+        ssl_client_rules: Sequence[ClientCertRule] | None = None,
+        ssl_reload_interval: int | None = None,
+        ssl_sni_certs: Sequence[SNICertificate] | None = None,
+        ssl_ocsp_response: Path | None = None,
+        ssl_session_cache: int = 256,
+        ssl_tickets: bool = True,
+        ssl_ticket_keys: Path | None = None,
+        ssl_ticket_rotation: int = 21600,
+        ssl_ktls: bool = False,
+        acme_domains: Sequence[str] | None = None,
+        acme_directory: str = ACME_DIRECTORY,
+        acme_contact: Sequence[str] | None = None,
+        acme_storage: Path | None = None,
+        acme_challenge: ACMEChallenges = ACMEChallenges.tls_alpn01,
+        acme_ca_root: Path | None = None,
+        acme_agree_tos: bool = False,
         url_path_prefix: str | None = None,
         respawn_failed_workers: bool = False,
         respawn_interval: float = 3.5,
@@ -136,10 +222,23 @@ class AbstractServer(Generic[WT]):
         static_path_mount: Sequence[Path] | None = None,
         static_path_dir_to_file: str | None = None,
         static_path_expires: int = 86400,
+        error_pages_path: Path | None = None,
+        vhosts: Sequence[VirtualHost] | None = None,
+        mounts: Sequence[Mount] | None = None,
+        proxy_routes: Sequence[tuple[str, str]] | None = None,
+        proxy_connect_timeout: int = 5,
+        proxy_timeout: int = 60,
+        listeners: Sequence[Listener] | None = None,
+        tcp_settings: TCPSettings | None = None,
+        uds_owner: str | int | None = None,
+        uds_group: str | int | None = None,
+        cpu_affinity: CPUAffinity = CPUAffinity.none,
+        cpu_list: Sequence[str] | None = None,
         metrics_enabled: bool = False,
         metrics_scrape_interval: int = 15,
         metrics_address: str = '127.0.0.1',
         metrics_port: int = 9090,
+        metrics_buckets: Sequence[float] | None = None,
         reload: bool = False,
         reload_paths: Sequence[Path] | None = None,
         reload_ignore_dirs: Sequence[str] | None = None,
@@ -154,8 +253,10 @@ class AbstractServer(Generic[WT]):
         self.target = target
         self.bind_addr = address
         self.bind_port = port
-        self.bind_uds = uds.resolve() if uds else None
+        self.bind_uds = uds_resolve(uds)
         self.uds_permissions = uds_permissions
+        self.uds_owner = uds_owner
+        self.uds_group = uds_group
         self.interface = interface
         self.workers = max(1, workers)
         self.runtime_threads = max(1, runtime_threads)
@@ -175,6 +276,7 @@ class AbstractServer(Generic[WT]):
         self.blocking_threads_idle_timeout = blocking_threads_idle_timeout
         self.http1_settings = http1_settings
         self.http2_settings = http2_settings
+        self.tcp_settings = tcp_settings
         self.log_enabled = log_enabled
         self.log_level = log_level
         self.log_config = log_dictconfig
@@ -194,10 +296,12 @@ class AbstractServer(Generic[WT]):
         self.working_dir = working_dir
         self.env_files = env_files or ()
         self.static_path = None
+        self.error_pages = str(error_pages_path.resolve()) if error_pages_path else None
         self.metrics_enabled = metrics_enabled
         self.metrics_scrape_interval = metrics_scrape_interval
         self.metrics_address = metrics_address
         self.metrics_port = metrics_port
+        self.metrics_buckets = sorted(set(metrics_buckets or METRICS_BUCKETS))
         self.reload_paths = reload_paths or [Path.cwd()]
         self.reload_ignore_paths = reload_ignore_paths or ()
         self.reload_ignore_dirs = reload_ignore_dirs or ()
@@ -215,19 +319,90 @@ class AbstractServer(Generic[WT]):
         configure_logging(self.log_level, self.log_config, self.log_enabled)
 
         if static_path_mount:
-            self._init_static_mounts(
+            self.static_path = self._build_static_mounts(
                 static_path_route or [],
                 static_path_mount,
                 static_path_dir_to_file,
                 (str(static_path_expires) if static_path_expires else None),
             )
+        self._vhosts = [
+            (
+                list(vhost.hosts),
+                self._build_static_mounts(
+                    vhost.static_path_route or [],
+                    vhost.static_path_mount or [],
+                    vhost.static_path_dir_to_file,
+                    (str(vhost.static_path_expires) if vhost.static_path_expires else None),
+                ),
+                Interfaces(vhost.interface or self.interface),
+                build_target_loader(vhost.target, vhost.factory, self.working_dir),
+            )
+            for vhost in (vhosts or ())
+        ]
+        self._mounts = self._build_mounts(mounts or ())
+        self.proxy = self._build_proxy(proxy_routes or (), proxy_connect_timeout, proxy_timeout)
+        self.listeners = [
+            replace(listener, uds=uds_resolve(listener.uds)) if listener.uds else listener
+            for listener in (listeners or ())
+        ]
+        self.acme = self._build_acme(
+            acme_domains or (),
+            acme_directory,
+            acme_contact or (),
+            acme_storage,
+            acme_challenge,
+            acme_ca_root,
+            acme_agree_tos,
+            bool(ssl_cert or ssl_key or ssl_pkcs12),
+        )
         self.build_ssl_context(
-            ssl_cert, ssl_key, ssl_key_password, ssl_protocol_min, ssl_ca, ssl_crl or [], ssl_client_verify
+            ssl_cert,
+            ssl_key,
+            ssl_key_password,
+            ssl_protocol_min,
+            ssl_ca,
+            ssl_crl or [],
+            ssl_client_verify,
+            ssl_reload_interval,
+            ssl_sni_certs or (),
+            ssl_ocsp_response,
+            Path(self.acme[3]) if self.acme else None,
+            ssl_session_cache,
+            ssl_ciphers or (),
+            ssl_kx_groups or (),
+            ssl_pkcs12,
+            ssl_profile,
+            ssl_ktls,
+            ssl_client_rules or (),
         )
+        self.ssl_tickets = self._build_ssl_tickets(ssl_tickets, ssl_ticket_keys, ssl_ticket_rotation)
+        if self.acme and acme_challenge == ACMEChallenges.http01:
+            #: http-01 challenges are answered by the static files handler
+            mounts, dir_to_file, expires = self.static_path or ([], None, None)
+            self.static_path = (
+                [(ACME_HTTP_ROUTE, str(Path(self.acme[3]) / ACME_HTTP_DIR)), *mounts],
+                dir_to_file,
+                expires,
+            )
         self._ssp = None
         self._shd = None
         self._sfd = None
-        self._metrics = MetricsAggregator(self.workers)
+        self._lss = []
+        self._lfd = []
+        self._handoff_conn = None
+        self._handoff_ppid = None
+        self._tls_tickets = None
+        self._tls_tickets_ipc = {}
+        self._handoff_done = False
+        self._affinity = self._build_affinity(cpu_affinity, cpu_list or ())
+        self._metrics = MetricsAggregator(
+            self.workers,
+            [','.join(map(str, sorted(set(cpus)))) for cpus, _ in self._affinity],
+            str(self.interface),
+            self.metrics_buckets,
+        )
+        if self.ssl_ctx[0]:
+            self._metrics.set_tls_certs(self._ssl_certs_expiry)
         self._metrics_exporter = MetricsExporter(self._metrics)
         self.wrks: list[WT] = []
         self.main_loop_interrupt = threading.Event()
@@ -235,56 +410,274 @@ class AbstractServer(Generic[WT]):
         self.interrupt_children = []
         self.respawned_wrks = {}
         self.reload_signal = False
+        self.handoff_signal = False
         self.lifetime_signal = False
         self.rss_signal = False
         self.pid = None
         self._env_loader = build_env_loader()
 
-    def _init_static_mounts(
-        self,
+    @staticmethod
+    def _build_static_mounts(
         routes: Sequence[str],
         paths: Sequence[Path],
         dir_to_file: str | None,
         expires: str | None,
     ):
         if not paths:
-            return
+            return None
         if len(paths) == 1 and not routes:
-            self.static_path = (
+            return (
                 [('/static', str(paths[0].resolve()))],
                 dir_to_file,
                 expires,
             )
-            return
         if len(paths) != len(routes):
             logger.error('Static path routes and mounts should have the same length')
             raise ConfigurationError('static_path')
-        self.static_path = (
+        return (
             [(routes[idx], str(path.resolve())) for idx, path in enumerate(paths)],
             dir_to_file,
             expires,
         )
 
+    def _build_mounts(self, mounts: Sequence[Mount]):
+        rv = []
+        for mount in mounts:
+            prefix = '/' + mount.prefix.strip('/')
+            if prefix == '/':
+                logger.error('Mount prefix cannot be the root path, use the main target instead')
+                raise ConfigurationError('mounts')
+            if prefix in (item[0] for item in rv):
+                logger.error(f'Duplicated mount prefix: {prefix}')
+                raise ConfigurationError('mounts')
+            rv.append(
+                (
+                    prefix,
+                    Interfaces(mount.interface or self.interface),
+                    build_target_loader(mount.target, mount.factory, self.working_dir),
+                )
+            )
+        return rv
+
+    def _check_listeners(self):
+        for listener in self.listeners:
+            if listener.uds and sys.platform == 'win32':
+                logger.error('Unix Domain sockets are not available on Windows')
+                raise ConfigurationError('listeners')
+            if listener.tls and not self.ssl_ctx[0]:
+                logger.error('TLS listeners require an SSL certificate and key to be configured')
+                raise ConfigurationError('listeners')
+
+    @staticmethod
+    def _parse_cpus(value: str) -> list[int]:
+        if value.startswith('node:'):
+            value = Path(f'/sys/devices/system/node/node{value[5:]}/cpulist').read_text().strip()
+        rv = []
+        for item in value.split(','):
+            start, _, end = item.partition('-')
+            rv.extend(range(int(start), int(end or start) + 1))
+        return rv
+
+    def _build_affinity(self, mode: CPUAffinity, cpu_list: Sequence[str]) -> list[tuple[list[int], bool]]:
+        if mode == CPUAffinity.none:
+            return [([], False)] * self.workers
+        if sys.platform != 'linux':
+            logger.warning('CPU affinity is only supported on Linux, ignoring')
+            return [([], False)] * self.workers
+
+        try:
+            cpu_sets = [self._parse_cpus(item) for item in cpu_list]
+        except (OSError, ValueError):
+            logger.error(f'Invalid CPU list: {", ".join(cpu_list)}')
+            raise ConfigurationError('cpu_list')
+
+        available = os.sched_getaffinity(0)
+        if invalid := sorted({cpu for cpu_set in cpu_sets for cpu in cpu_set} - available):
+            logger.error(f'CPUs not available to this process: {", ".join(map(str, invalid))}')
+            raise ConfigurationError('cpu_list')
+
+        if mode == CPUAffinity.list:
+            if not cpu_sets:
+                logger.error('The `list` CPU affinity policy requires a CPU list')
+                raise ConfigurationError('cpu_list')
+            return [(cpu_sets[idx % len(cpu_sets)], False) for idx in range(self.workers)]
+
+        cpus = [cpu for cpu_set in cpu_sets for cpu in cpu_set] or sorted(available)
+        if mode == CPUAffinity.worker:
+            return [([cpus[idx % len(cpus)]], False) for idx in range(self.workers)]
+        #: runtime threads get pinned round-robin across all the workers
+        return [
+            ([cpus[(idx * self.runtime_threads + thread) % len(cpus)] for thread in range(self.runtime_threads)], True)
+            for idx in range(self.workers)
+        ]
+
+    def _check_tcp_settings(self):
+        if not self.tcp_settings or sys.platform == 'linux':
+            return
+        for key in ('defer_accept', 'fastopen', 'user_timeout'):
+            if getattr(self.tcp_settings, key) is not None:
+                logger.info(f'TCP {key} setting is only available on Linux, ignoring')
+
+    @staticmethod
+    def _build_proxy(routes: Sequence[tuple[str, str]], connect_timeout: int, timeout: int):
+        if not routes:
+            return None
+        rv = []
+        for prefix, upstream in routes:
+            prefix = '/' + prefix.strip('/')
+            if upstream.startswith('unix:'):
+                if sys.platform == 'win32':
+                    logger.error('Unix socket proxy upstreams are not supported on Windows')
+                    raise ConfigurationError('proxy_routes')
+                upstream = 'unix:' + str(Path(upstream[5:]).resolve())
+            elif authority := _proxy_upstream_authority(upstream):
+                upstream = f'http://{authority}'
+            else:
+                logger.error(f'Unsupported proxy upstream: {upstream} (should be http://HOST:PORT or unix:PATH)')
+                raise ConfigurationError('proxy_routes')
+            rv.append((prefix, upstream))
+        return (rv, max(1, connect_timeout), max(1, timeout))
+
+    @staticmethod
+    def _build_acme(
+        domains: Sequence[str],
+        directory: str,
+        contact: Sequence[str],
+        storage: Path | None,
+        challenge: ACMEChallenges,
+        ca_root: Path | None,
+        agree_tos: bool,
+        ssl_configured: bool,
+    ):
+        if not domains:
+            return None
+        if ssl_configured:
+            logger.error('ACME certificates cannot be used together with a configured SSL certificate and key')
+            raise ConfigurationError('acme_domains')
+        if not agree_tos:
+            logger.error('ACME certificates require to agree to the certificate authority terms of service')
+            raise ConfigurationError('acme_agree_tos')
+        return (
+            directory,
+            list(domains),
+            list(contact),
+            str((storage or Path(ACME_STORAGE)).resolve()),
+            str(challenge),
+            str(ca_root.resolve()) if ca_root else None,
+            agree_tos,
+        )
+
     def build_ssl_context(
         self,
         cert: Path | None,
         key: Path | None,
         password: str | None,
-        proto: SSLProtocols,
+        proto: SSLProtocols | None,
         ca: Path | None,
         crl: list[Path],
         client_verify: bool,
+        reload_interval: int | None = None,
+        sni_certs: Sequence[SNICertificate] = (),
+        ocsp_response: Path | None = None,
+        acme_storage: Path | None = None,
+        session_cache: int = 256,
+        ciphers: Sequence[str] = (),
+        kx_groups: Sequence[str] = (),
+        pkcs12: Path | None = None,
+        profile: SSLProfiles | None = None,
+        ktls: bool = False,
+        client_rules: Sequence[ClientCertRule] = (),
     ):
-        if not (cert and key):
-            self.ssl_ctx = (False, None, None, None, str(proto), None, [], False)
+        #: profiles set the minimum protocol, an explicit one can only make it stricter
+        if profile:
+            profile_proto = TLS_PROFILES[profile][0]
+            proto = max(proto or profile_proto, profile_proto, key=list(SSLProtocols).index)
+        proto = proto or SSLProtocols.tls13
+        if pkcs12 and (cert or key or acme_storage):
+            logger.error('PKCS#12 bundles cannot be used together with SSL certificate and key files')
+            raise ConfigurationError('ssl_pkcs12')
+        if acme_storage:
+            if sni_certs:
+                logger.error('SNI certificates cannot be used together with ACME certificates')
+                raise ConfigurationError('ssl_sni_certs')
+            #: the certificate might not be there yet, workers will wait for the ACME manager to store it
+            cert = key = acme_storage / ACME_CERT_FILE
+            expiry = None
+        elif pkcs12:
+            cert = key = pkcs12
+            expiry = self._validate_ssl_cert(cert, key, password, True, 'ssl_pkcs12')
+        elif cert and key:
+            expiry = self._validate_ssl_cert(cert, key, password, False, 'ssl_certificate')
+        else:
+            if sni_certs:
+                logger.error('SNI certificates require a default SSL certificate and key to be configured')
+                raise ConfigurationError('ssl_sni_certs')
+            if client_rules:
+                logger.error('SSL client certificate rules require SSL to be configured')
+                raise ConfigurationError('ssl_client_rules')
+            self.ssl_ctx = (
+                False,
+                None,
+                None,
+                None,
+                str(proto),
+                None,
+                [],
+                False,
+                None,
+                [],
+                None,
+                None,
+                0,
+                [],
+                [],
+                False,
+                False,
+                [],
+            )
             return
-        # uneeded?
-        ctx = ssl.SSLContext(ssl.PROTOCOL_TLS_SERVER)
-        ctx.load_cert_chain(str(cert.resolve()), str(key.resolve()), password)
+        #: metrics expose the certificates expiry, without the need of keeping passwords around
+        self._ssl_certs_expiry = [(str(cert.resolve()), expiry, bool(pkcs12))]
+        for item in sni_certs:
+            if not item.hostnames:
+                logger.error(f'SNI certificate {item.cert} requires at least one hostname')
+                raise ConfigurationError('ssl_sni_certs')
+            expiry = self._validate_ssl_cert(item.cert, item.key, item.key_password, False, 'ssl_sni_certs')
+            self._ssl_certs_expiry.append((str(item.cert.resolve()), expiry, False))
+        if profile:
+            _, profile_ciphers, profile_kx_groups = TLS_PROFILES[profile]
+            ciphers = ciphers or profile_ciphers
+            kx_groups = kx_groups or profile_kx_groups
+        ciphers = self._resolve_tls_names(ciphers, TLS_CIPHER_SUITES, 'cipher suite', 'ssl_ciphers')
+        kx_groups = self._resolve_tls_names(kx_groups, TLS_KX_GROUPS, 'key exchange group', 'ssl_kx_groups')
+        #: TLS 1.2 suites are the only ones specifying the key exchange and authentication
+        if ciphers and proto == SSLProtocols.tls13 and all('_WITH_' in item for item in ciphers):
+            logger.error('SSL cipher suites should include at least one TLS 1.3 suite when TLS 1.2 is disabled')
+            raise ConfigurationError('ssl_ciphers')
         #: build ctx
         if client_verify and not ca:
             logger.warning('SSL client verification requires a CA certificate, ignoring')
             client_verify = False
+        if client_rules and not ca:
+            logger.error('SSL client certificate rules require a CA certificate to verify clients')
+            raise ConfigurationError('ssl_client_rules')
+        for rule in client_rules:
+            if not rule.path.startswith('/'):
+                logger.error(f'SSL client certificate rule path {rule.path!r} should start with /')
+                raise ConfigurationError('ssl_client_rules')
+            for fingerprint in rule.fingerprints:
+                if not re.fullmatch(r'[0-9a-fA-F]{64}', fingerprint.replace(':', '')):
+                    logger.error(f'Invalid SHA-256 fingerprint {fingerprint!r} in SSL client certificate rule')
+                    raise ConfigurationError('ssl_client_rules')
+        if ktls and sys.platform != 'linux':
+            logger.warning('kTLS is only available on Linux, ignoring')
+            ktls = False
+        if ca:
+            try:
+                tls_validate_client_auth(str(ca.resolve()), [str(item.resolve()) for item in crl])
+            except ValueError as exc:
+                logger.error(f'Invalid SSL client verification settings: {exc}')
+                raise SSLConfigurationError('ssl_crl' if crl and 'CRL' in str(exc) else 'ssl_ca', str(exc))
         self.ssl_ctx = (
             True,
             str(cert.resolve()),
@@ -294,12 +687,101 @@ class AbstractServer(Generic[WT]):
             str(ca.resolve()) if ca else None,
             [str(item.resolve()) for item in crl],
             client_verify,
+            reload_interval,
+            [
+                (
+                    list(item.hostnames),
+                    str(item.cert.resolve()),
+                    str(item.key.resolve()),
+                    item.key_password,
+                    str(item.ocsp_response.resolve()) if item.ocsp_response else None,
+                )
+                for item in sni_certs
+            ],
+            str(ocsp_response.resolve()) if ocsp_response else None,
+            str(acme_storage) if acme_storage else None,
+            max(0, session_cache),
+            ciphers,
+            kx_groups,
+            bool(pkcs12),
+            ktls,
+            [
+                (rule.path, list(rule.cn), list(rule.ou), list(rule.san), list(rule.fingerprints))
+                for rule in client_rules
+            ],
         )
 
+    @staticmethod
+    def _validate_ssl_cert(cert: Path, key: Path, password: str | None, pkcs12: bool, param: str) -> int:
+        try:
+            subject, expiry = tls_validate_cert(str(cert.resolve()), str(key.resolve()), password, pkcs12)
+        except ValueError as exc:
+            logger.error(f'Invalid SSL certificate {cert}: {exc}')
+            raise SSLConfigurationError(param, f'{cert}: {exc}')
+        expires_in = expiry - time.time()
+        if expires_in < TLS_CERT_EXPIRY_WARN:
+            logger.warning(f'SSL certificate {cert} ({subject}) expires in {int(expires_in // 86400)} days')
+        return expiry
+
+    def _revalidate_ssl_files(self) -> bool:
+        #: files might have changed since startup, and new workers would fail to load broken ones
+        enabled, cert, key, password, _, ca, crl, _, _, sni_certs, _, acme_storage, *_ = self.ssl_ctx
+        if not enabled or acme_storage:
+            return True
+        try:
+            param = 'ssl_pkcs12' if self.ssl_ctx[15] else 'ssl_certificate'
+            self._validate_ssl_cert(Path(cert), Path(key), password, self.ssl_ctx[15], param)
+            for _, sni_cert, sni_key, sni_password, _ in sni_certs:
+                self._validate_ssl_cert(Path(sni_cert), Path(sni_key), sni_password, False, 'ssl_sni_certs')
+            if ca:
+                tls_validate_client_auth(ca, crl)
+        except SSLConfigurationError:
+            return False
+        except ValueError as exc:
+            logger.error(f'Invalid SSL client verification settings: {exc}')
+            return False
+        return True
+
+    @staticmethod
+    def _resolve_tls_names(names: Sequence[str], supported: list[str], kind: str, param: str) -> list[str]:
+        supported_map = {item.lower(): item for item in supported}
+        rv = []
+        for name in names:
+            if name.lower() not in supported_map:
+                logger.error(f'Unsupported SSL {kind} {name}, available ones are: {", ".join(supported)}')
+                raise ConfigurationError(param)
+            rv.append(supported_map[name.lower()])
+        return rv
+
+    def _build_ssl_tickets(self, enabled: bool, keys: Path | None, rotation: int):
+        if not enabled or not self.ssl_ctx[0]:
+            return None
+        if rotation < 1:
+            logger.error('TLS session ticket keys rotation interval should be a positive number of seconds')
+            raise ConfigurationError('ssl_ticket_rotation')
+        if keys:
+            try:
+                size = keys.stat().st_size
+            except OSError as exc:
+                logger.error(f'Unable to read TLS session ticket keys file {keys}: {exc}')
+                raise ConfigurationError('ssl_ticket_keys')
+            if not size or size % TLS_TICKET_KEY_SIZE:
+                logger.error(
+                    f'TLS session ticket keys file {keys} should contain one or more {TLS_TICKET_KEY_SIZE} bytes keys'
+                )
+                raise ConfigurationError('ssl_ticket_keys')
+        return (rotation, str(keys.resolve()) if keys else None)
+
     @property
     def _bind_addr_fmt(self):
         return f'unix:{self.bind_uds}' if self.bind_uds else f'{self.bind_addr}:{self.bind_port}'
 
+    @staticmethod
+    def _listener_fmt(listener: Listener):
+        proto = 'https' if listener.tls else 'http'
+        addr = f'unix:{listener.uds}' if listener.uds else f'{listener.address}:{listener.port}'
+        return f'{proto}://{addr}'
+
     @staticmethod
     def _call_hooks(hooks):
         for hook in hooks:
@@ -317,17 +799,99 @@ class AbstractServer(Generic[WT]):
         self.hooks_shutdown.append(hook)
         return hook
 
-    def _init_shared_socket(self):
-        if self.bind_uds:
-            self._ssp = UnixSocketSpec(str(self.bind_uds), self.backlog, self.uds_permissions)
+    def _build_shared_socket(self, address, port, uds, uds_permissions, uds_owner=None, uds_group=None):
+        if uds:
+            spec = UnixSocketSpec(str(uds), self.backlog, uds_permissions, *self._uds_owner_ids(uds_owner, uds_group))
         else:
-            self._ssp = SocketSpec(self.bind_addr, self.bind_port, self.backlog)
+            spec = SocketSpec(address, port, self.backlog, self.tcp_settings.as_opts() if self.tcp_settings else None)
             if sys.platform == 'linux':
-                self._ssp.build()
-                return
-        self._shd = self._ssp.build()
+                spec.build()
+                return spec, None
+        return None, spec.build()
+
+    @staticmethod
+    def _uds_owner_ids(owner, group):
+        try:
+            return uds_owner_ids(owner, group)
+        except KeyError:
+            logger.error(f'Unknown Unix Domain Socket owner or group: {owner}:{group}')
+            raise ConfigurationError('uds_owner')
+
+    def _log_listeners(self):
+        if self._lfd:
+            logger.info(f'Listening on sockets inherited from systemd: {", ".join(self._lfd)}')
+        else:
+            proto = 'https' if self.ssl_ctx[0] else 'http'
+            logger.info(f'Listening at: {proto}://{self._bind_addr_fmt}')
+        for listener in self.listeners:
+            logger.info(f'Listening at: {self._listener_fmt(listener)}')
+
+    def _init_inherited_sockets(self):
+        inherited = listen_fds(self.backlog) if listen_fds is not None else []
+        if not inherited:
+            return False
+        #: avoid leaking the activation environment to applications and child processes
+        for key in ('LISTEN_PID', 'LISTEN_FDS', 'LISTEN_FDNAMES'):
+            os.environ.pop(key, None)
+        (_, self._shd), *extras = inherited
         self._sfd = self._shd.get_fd()
-        self._ssp = None
+        self._lfd = [name for name, _ in inherited]
+        for name, holder in extras:
+            if name.startswith('tls') and not self.ssl_ctx[0]:
+                logger.error(f'Inherited socket {name} requires an SSL certificate and key to be configured')
+                raise ConfigurationError('listeners')
+            self._lss.append((name.startswith('tls'), (None, holder)))
+        return True
+
+    def _init_handoff_sockets(self):
+        fd = os.environ.pop(HANDOFF_ENV, None)
+        if fd is None:
+            return False
+        self._handoff_conn = socket.socket(fileno=int(fd))
+        (_, (self._ssp, self._shd)), *self._lss = handoff_recv(self._handoff_conn)
+        if any(tls for tls, _ in self._lss) and not self.ssl_ctx[0]:
+            logger.error('Handed off TLS listeners require an SSL certificate and key to be configured')
+            raise ConfigurationError('listeners')
+        self._sfd = self._shd.get_fd()
+        logger.info(f'Took over listening sockets from main PID {self._handoff_ppid}')
+        return True
+
+    def _handoff_ack(self):
+        if self._handoff_conn is None:
+            return
+        with self._handoff_conn:
+            self._handoff_conn.sendall(HANDOFF_ACK)
+        self._handoff_conn = None
+
+    def _init_shared_socket(self):
+        self._lss = []
+        if self._init_handoff_sockets():
+            return
+        if not self._init_inherited_sockets():
+            self._ssp, self._shd = self._build_shared_socket(
+                self.bind_addr,
+                self.bind_port,
+                self.bind_uds,
+                self.uds_permissions,
+                self.uds_owner,
+                self.uds_group,
+            )
+            if self._shd is not None:
+                self._sfd = self._shd.get_fd()
+        self._lss.extend(
+            (
+                listener.tls,
+                self._build_shared_socket(
+                    listener.address,
+                    listener.port,
+                    listener.uds,
+                    listener.uds_permissions,
+                    listener.uds_owner,
+                    listener.uds_group,
+                ),
+            )
+            for listener in self.listeners
+        )
 
     def signal_handler_interrupt(self, *args, **kwargs):
         self.interrupt_signal = True
@@ -337,6 +901,10 @@ class AbstractServer(Generic[WT]):
         self.reload_signal = True
         self.main_loop_interrupt.set()
 
+    def signal_handler_handoff(self, *args, **kwargs):
+        self.handoff_signal = True
+        self.main_loop_interrupt.set()
+
     def _spawn_worker(self, idx, target, callback_loader, socket_loader) -> WT:
         raise NotImplementedError
 
@@ -347,7 +915,11 @@ class AbstractServer(Generic[WT]):
             self.wrks.append(wrk)
         self._metrics.incr_spawn(self.workers)
 
-    def _respawn_workers(self, workers, spawn_target, target_loader, delay: float = 0):
+    def _respawn_workers(self, workers, spawn_target, target_loader, delay: float = 0) -> bool:
+        if not self._revalidate_ssl_files():
+            logger.error('Invalid SSL configuration, keeping current workers')
+            return False
+
         for idx in workers:
             self.respawned_wrks[idx] = time.monotonic()
             logger.info(f'Respawning worker-{idx + 1}')
@@ -371,6 +943,7 @@ class AbstractServer(Generic[WT]):
             logger.info(f'Stopped old worker-{idx + 1}')
 
         self._metrics.incr_spawn(len(workers))
+        return True
 
     def _stop_workers(self):
         for wrk in self.wrks:
@@ -432,7 +1005,7 @@ class AbstractServer(Generic[WT]):
                 logger.error(f'Unable to read existing PID file {self.pid_file}')
                 raise PidFileError
 
-        if existing_pid is not None and existing_pid != self.pid:
+        if existing_pid is not None and existing_pid not in (self.pid, self._handoff_ppid):
             existing_process = True
             try:
                 os.kill(existing_pid, 0)
@@ -447,8 +1020,11 @@ class AbstractServer(Generic[WT]):
         self._write_pid()
 
     def _unlink_pidfile(self):
-        if self.bind_uds and self.bind_uds.exists():
-            self.bind_uds.unlink()
+        #: the new main process keeps serving on the same socket files
+        if not self._handoff_done:
+            uds_unlink(self.bind_uds)
+            for listener in self.listeners:
+                uds_unlink(listener.uds)
 
         if not (self.pid_file and self.pid_file.exists()):
             return
@@ -479,19 +1055,41 @@ class AbstractServer(Generic[WT]):
     def _stop_metrics(self):
         self._metrics_sig.set()
 
+    def _start_acme(self):
+        self._acme_sig = WorkerSignal()
+        ACMEManager(*self.acme).run(self._acme_sig)
+
+    def _stop_acme(self):
+        self._acme_sig.set()
+
+    def _start_tls_tickets(self):
+        self._tls_tickets_sig = WorkerSignal()
+        self._tls_tickets.run(self._tls_tickets_sig, [pipe[0] for pipe in self._tls_tickets_ipc.values()])
+
+    def _stop_tls_tickets(self):
+        self._tls_tickets_sig.set()
+
     def startup(self, spawn_target, target_loader):
         self.pid = os.getpid()
+        self._handoff_ppid = os.getppid() if HANDOFF_ENV in os.environ else None
         logger.info(f'Starting granian (main PID: {self.pid})')
         self._write_pidfile()
-        set_main_signals(self.signal_handler_interrupt, self.signal_handler_reload)
+        set_main_signals(
+            self.signal_handler_interrupt,
+            self.signal_handler_reload,
+            None if self.reload_on_changes else self.signal_handler_handoff,
+        )
         self._init_shared_socket()
+        if self.ssl_tickets:
+            #: keys are generated (or loaded) by the main process and shared with workers
+            self._tls_tickets = TLSTicketKeys(*self.ssl_tickets)
         self._start_ipc()
-        proto = 'https' if self.ssl_ctx[0] else 'http'
-        logger.info(f'Listening at: {proto}://{self._bind_addr_fmt}')
+        self._log_listeners()
 
         self._env_loader(self.env_files)
         self._call_hooks(self.hooks_startup)
         self._spawn_workers(spawn_target, target_loader)
+        self._handoff_ack()
 
         if self.workers_lifetime is not None:
             self._watch_workers_lifetime(self.workers_lifetime)
@@ -499,12 +1097,20 @@ class AbstractServer(Generic[WT]):
             self._watch_workers_rss()
         if self.metrics_enabled:
             self._start_metrics()
+        if self.acme:
+            self._start_acme()
+        if self._tls_tickets:
+            self._start_tls_tickets()
 
     def shutdown(self, exit_code=0):
         logger.info('Shutting down granian')
 
         if self.metrics_enabled:
             self._stop_metrics()
+        if self.acme:
+            self._stop_acme()
+        if self._tls_tickets:
+            self._stop_tls_tickets()
         self._stop_workers()
         self._stop_ipc()
         self._call_hooks(self.hooks_shutdown)
@@ -529,6 +1135,40 @@ class AbstractServer(Generic[WT]):
         self._call_hooks(self.hooks_reload)
         return self._respawn_workers(workers, spawn_target, target_loader, delay=self.respawn_interval)
 
+    def _handoff(self):
+        logger.info('USR2 signal received, handing listening sockets off to a new main process..')
+
+        self.handoff_signal = False
+        self.main_loop_interrupt.clear()
+
+        #: prefer the original executable, so its shebang can point to a different Python
+        cmd = sys.argv if os.access(sys.argv[0], os.X_OK) else [sys.executable, *sys.orig_argv[1:]]
+        proc, ack = None, b''
+        conn, child_conn = socket.socketpair()
+        try:
+            with conn:
+                with child_conn:
+                    proc = subprocess.Popen(  # noqa: S603
+                        cmd,
+                        env={**os.environ, HANDOFF_ENV: str(child_conn.fileno())},
+                        pass_fds=(child_conn.fileno(),),
+                    )
+                handoff_send(conn, [(False, (self._ssp, self._shd)), *self._lss])
+                conn.settimeout(HANDOFF_TIMEOUT)
+                ack = conn.recv(len(HANDOFF_ACK))
+        except Exception as exc:
+            logger.error(f'Unable to hand off listening sockets: {exc}')
+
+        if ack != HANDOFF_ACK:
+            logger.error('The new main process failed to take over, keep serving')
+            if proc is not None and proc.poll() is None:
+                proc.kill()
+            return False
+
+        logger.info(f'Listening sockets handed off to main PID {proc.pid}, draining..')
+        self._handoff_done = True
+        return True
+
     def _handle_rss_signal(self, spawn_target, target_loader):
         raise NotImplementedError
 
@@ -551,12 +1191,15 @@ class AbstractServer(Generic[WT]):
                 self.interrupt_children.clear()
                 self.respawned_wrks.clear()
                 self.main_loop_interrupt.clear()
-                self._respawn_workers(workers, spawn_target, target_loader)
-                self._metrics.incr_respawn_err(1)
+                if self._respawn_workers(workers, spawn_target, target_loader):
+                    self._metrics.incr_respawn_err(1)
 
             if self.reload_signal:
                 self._reload(spawn_target, target_loader)
 
+            if self.handoff_signal and self._handoff():
+                break
+
             if self.lifetime_signal or self.rss_signal:
                 self.main_loop_interrupt.clear()
 
@@ -568,10 +1211,13 @@ class AbstractServer(Generic[WT]):
                     for worker in list(self.wrks):
                         if (now - worker.birth) >= ttl:
                             logger.info(f'worker-{worker.idx + 1} lifetime expired, gracefully respawning..')
-                            self._respawn_workers(
+                            if self._respawn_workers(
                                 [worker.idx], spawn_target, target_loader, delay=self.respawn_interval
-                            )
-                            self._metrics.incr_respawn_ttl(1)
+                            ):
+                                self._metrics.incr_respawn_ttl(1)
+                            else:
+                                #: retry once the SSL files get fixed
+                                etas.append(60)
                         else:
                             elapsed = now - worker.birth
                             remaining = self.workers_lifetime - elapsed
@@ -620,6 +1266,9 @@ class AbstractServer(Generic[WT]):
                     logger.info('Changes detected, reloading workers..')
                     for change, file in changes:
                         logger.info(f'{change.raw_str().capitalize()}: {file}')
+                    if not self._revalidate_ssl_files():
+                        logger.error('Invalid SSL configuration, keeping current workers')
+                        continue
                     self._env_loader(self.env_files)
                     self._call_hooks(self.hooks_reload)
                     self._stop_workers()
@@ -666,6 +1315,9 @@ class AbstractServer(Generic[WT]):
             logger.error('Unix Domain sockets are not available on Windows')
             raise ConfigurationError('uds')
 
+        self._check_listeners()
+        self._check_tcp_settings()
+
         if self.interface != Interfaces.WSGI and self.blocking_threads > 1:
             logger.error('Blocking threads > 1 is not supported on ASGI and RSGI')
             raise ConfigurationError('blocking_threads')
@@ -705,6 +1357,9 @@ class AbstractServer(Generic[WT]):
             if self.reload_on_changes:
                 self.metrics_enabled = False
                 logger.info('Metrics are not available in combination with changes reloader, ignoring')
+            if self.metrics_buckets[0] <= 0:
+                logger.error('Metrics histogram buckets must be positive numbers')
+                raise ConfigurationError('metrics_buckets')
 
         if self.blocking_threads_idle_timeout < 5 or self.blocking_threads_idle_timeout > 600:
             logger.error('Blocking threads idle timeout must be between 5 and 600 seconds')
@@ -8,12 +8,14 @@ from pathlib import Path
 from typing import Any
 
 from .._futures import _future_watcher_wrapper, _new_cbscheduler
-from .._granian import ASGIWorker, RSGIWorker, WorkerSignal
+from .._granian import ASGIWorker, RSGIWorker, TLSTicketKeys, WorkerSignal
 from .._imports import dotenv
 from .._internal import load_env
 from .._types import SSLCtx
+from ..apps import Mount, VirtualHost, _apps_callbacks, _apps_shutdown, _apps_startup, _apps_startup_error
 from ..asgi import LifespanProtocol, _callback_wrapper as _asgi_call_wrap
 from ..errors import ConfigurationError, FatalError
+from ..net import ClientCertRule, Listener, SNICertificate, TCPSettings, uds_unlink
 from ..rsgi import _callback_wrapper as _rsgi_call_wrap, _callbacks_from_target as _rsgi_cbs_from_target
 from .common import (
     _PY_312,
@@ -25,6 +27,7 @@ from .common import (
     HTTPModes,
     Interfaces,
     LogLevels,
+    SSLProfiles,
     SSLProtocols,
     TaskImpl,
     logger,
@@ -42,9 +45,9 @@ class AsyncWorker(AbstractWorker):
     @staticmethod
     def wrap_target(target):
         @wraps(target)
-        def wrapped(worker_id, sig, callback, sock, *args, **kwargs):
+        def wrapped(worker_id, sig, callback, sock, listeners, ssl_tickets, *args, **kwargs):
             loop = asyncio.get_event_loop()
-            return target(worker_id, sig, callback, sock, loop, *args, **kwargs)
+            return target(worker_id, sig, callback, sock, listeners, ssl_tickets, loop, *args, **kwargs)
 
         return wrapped
 
@@ -118,16 +121,37 @@ class Server(AbstractServer[AsyncWorker]):
         ssl_cert: Path | None = None,
         ssl_key: Path | None = None,
         ssl_key_password: str | None = None,
-        ssl_protocol_min: SSLProtocols = SSLProtocols.tls13,
+        ssl_pkcs12: Path | None = None,
+        ssl_protocol_min: SSLProtocols | None = None,
+        ssl_profile: SSLProfiles | None = None,
+        ssl_ciphers: Sequence[str] | None = None,
+        ssl_kx_groups: Sequence[str] | None = None,
         ssl_ca: Path | None = None,
         ssl_crl: list[Path] | None = None,
         ssl_client_verify: bool = False,
+        ssl_client_rules: Sequence[ClientCertRule] | None = None,
+        ssl_reload_interval: int | None = None,
+        ssl_sni_certs: Sequence[SNICertificate] | None = None,
+        ssl_ocsp_response: Path | None = None,
+        ssl_session_cache: int = 256,
+        ssl_tickets: bool = True,
+        ssl_ticket_keys: Path | None = None,
+        ssl_ticket_rotation: int = 21600,
+        ssl_ktls: bool = False,
         url_path_prefix: str | None = None,
         factory: bool = False,
         static_path_route: Sequence[str] | None = None,
         static_path_mount: Sequence[Path] | None = None,
         static_path_dir_to_file: str | None = None,
         static_path_expires: int = 86400,
+        error_pages_path: Path | None = None,
+        vhosts: Sequence[VirtualHost] | None = None,
+        mounts: Sequence[Mount] | None = None,
+        proxy_routes: Sequence[tuple[str, str]] | None = None,
+        proxy_connect_timeout: int = 5,
+        proxy_timeout: int = 60,
+        listeners: Sequence[Listener] | None = None,
+        tcp_settings: TCPSettings | None = None,
     ):
         super().__init__(
             target=target,
@@ -154,16 +178,37 @@ class Server(AbstractServer[AsyncWorker]):
             ssl_cert=ssl_cert,
             ssl_key=ssl_key,
             ssl_key_password=ssl_key_password,
+            ssl_pkcs12=ssl_pkcs12,
             ssl_protocol_min=ssl_protocol_min,
+            ssl_profile=ssl_profile,
+            ssl_ciphers=ssl_ciphers,
+            ssl_kx_groups=ssl_kx_groups,
             ssl_ca=ssl_ca,
             ssl_crl=ssl_crl,
             ssl_client_verify=ssl_client_verify,
+            ssl_client_rules=ssl_client_rules,
+            ssl_reload_interval=ssl_reload_interval,
+            ssl_sni_certs=ssl_sni_certs,
+            ssl_ocsp_response=ssl_ocsp_response,
+            ssl_session_cache=ssl_session_cache,
+            ssl_tickets=ssl_tickets,
+            ssl_ticket_keys=ssl_ticket_keys,
+            ssl_ticket_rotation=ssl_ticket_rotation,
+            ssl_ktls=ssl_ktls,
             url_path_prefix=url_path_prefix,
             factory=factory,
             static_path_route=static_path_route,
             static_path_mount=static_path_mount,
             static_path_dir_to_file=static_path_dir_to_file,
             static_path_expires=static_path_expires,
+            error_pages_path=error_pages_path,
+            vhosts=vhosts,
+            mounts=mounts,
+            proxy_routes=proxy_routes,
+            proxy_connect_timeout=proxy_connect_timeout,
+            proxy_timeout=proxy_timeout,
+            listeners=listeners,
+            tcp_settings=tcp_settings,
         )
         self.main_loop_interrupt = asyncio.Event()
 
@@ -179,8 +224,11 @@ class Server(AbstractServer[AsyncWorker]):
                 sig,
                 callback_loader,
                 (self._ssp, self._shd),
+                self._lss,
+                self._tls_tickets,
                 self.runtime_threads,
                 self.runtime_blocking_threads,
+                self._affinity[idx],
                 self.blocking_threads,
                 self.blocking_threads_idle_timeout,
                 self.backpressure,
@@ -190,6 +238,10 @@ class Server(AbstractServer[AsyncWorker]):
                 self.http2_settings,
                 self.websockets,
                 self.static_path,
+                self.error_pages,
+                self._vhosts,
+                self._mounts,
+                self.proxy,
                 self.log_access_format if self.log_access else None,
                 self.ssl_ctx,
                 {'url_path_prefix': self.url_path_prefix},
@@ -204,9 +256,12 @@ class Server(AbstractServer[AsyncWorker]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -216,11 +271,16 @@ class Server(AbstractServer[AsyncWorker]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
     ):
         wcallback = _future_watcher_wrapper(_asgi_call_wrap(callback, scope_opts, {}, log_access_fmt))
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         fut = loop.create_future()
 
         def shutdown_glue():
@@ -231,6 +291,10 @@ class Server(AbstractServer[AsyncWorker]):
 
         shutdown_event.add_cb(shutdown_glue)
 
+        if failed := await _apps_startup(loop, app_lifespans, app_inits):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            raise FatalError('ASGI lifespan startup')
+
         worker = ASGIWorker(
             worker_id,
             sock,
@@ -245,13 +309,28 @@ class Server(AbstractServer[AsyncWorker]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
-            (None, None),
+            ssl_tickets,
+            (None, None, []),
         )
-        serve = worker.serve_async_uds if (sock[0] or sock[1]).is_uds() else worker.serve_async
+        if listeners:
+            serve = worker.serve_async_multi
+        else:
+            serve = worker.serve_async_uds if (sock[0] or sock[1]).is_uds() else worker.serve_async
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         await fut
+        await _apps_shutdown(loop, app_lifespans, app_dels)
 
     @staticmethod
     @AsyncWorker.wrap_target
@@ -260,9 +339,12 @@ class Server(AbstractServer[AsyncWorker]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -272,6 +354,10 @@ class Server(AbstractServer[AsyncWorker]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -280,10 +366,10 @@ class Server(AbstractServer[AsyncWorker]):
         wcallback = _future_watcher_wrapper(
             _asgi_call_wrap(callback, scope_opts, lifespan_handler.state, log_access_fmt)
         )
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
 
-        await lifespan_handler.startup()
-        if lifespan_handler.interrupt:
-            logger.error('ASGI lifespan startup failed', exc_info=lifespan_handler.exc)
+        if failed := await _apps_startup(loop, [lifespan_handler, *app_lifespans], app_inits):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
             raise FatalError('ASGI lifespan startup')
 
         fut = loop.create_future()
@@ -310,14 +396,28 @@ class Server(AbstractServer[AsyncWorker]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
-            (None, None),
+            ssl_tickets,
+            (None, None, []),
         )
-        serve = worker.serve_async_uds if (sock[0] or sock[1]).is_uds() else worker.serve_async
+        if listeners:
+            serve = worker.serve_async_multi
+        else:
+            serve = worker.serve_async_uds if (sock[0] or sock[1]).is_uds() else worker.serve_async
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         await fut
-        await lifespan_handler.shutdown()
+        await _apps_shutdown(loop, [lifespan_handler, *app_lifespans], app_dels)
 
     @staticmethod
     @AsyncWorker.wrap_target
@@ -326,9 +426,12 @@ class Server(AbstractServer[AsyncWorker]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -338,12 +441,17 @@ class Server(AbstractServer[AsyncWorker]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
     ):
         callback, callback_init, callback_del = _rsgi_cbs_from_target(callback)
         wcallback = _future_watcher_wrapper(_rsgi_call_wrap(callback, log_access_fmt))
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         fut = loop.create_future()
 
         def shutdown_glue():
@@ -354,7 +462,9 @@ class Server(AbstractServer[AsyncWorker]):
 
         shutdown_event.add_cb(shutdown_glue)
 
-        callback_init(loop)
+        if failed := await _apps_startup(loop, app_lifespans, [callback_init, *app_inits]):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            raise FatalError('ASGI lifespan startup')
 
         worker = RSGIWorker(
             worker_id,
@@ -370,14 +480,28 @@ class Server(AbstractServer[AsyncWorker]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
-            (None, None),
+            ssl_tickets,
+            (None, None, []),
         )
-        serve = worker.serve_async_uds if (sock[0] or sock[1]).is_uds() else worker.serve_async
+        if listeners:
+            serve = worker.serve_async_multi
+        else:
+            serve = worker.serve_async_uds if (sock[0] or sock[1]).is_uds() else worker.serve_async
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         await fut
-        callback_del(loop)
+        await _apps_shutdown(loop, app_lifespans, [callback_del, *app_dels])
 
     async def _respawn_workers(self, workers, spawn_target, target_loader, delay: float = 0):
         for idx in workers:
@@ -416,12 +540,16 @@ class Server(AbstractServer[AsyncWorker]):
     def startup(self, spawn_target, target_loader):
         logger.info('Starting granian (embedded)')
         self._init_shared_socket()
-        proto = 'https' if self.ssl_ctx[0] else 'http'
-        logger.info(f'Listening at: {proto}://{self._bind_addr_fmt}')
+        if self.ssl_tickets:
+            self._tls_tickets = TLSTicketKeys(*self.ssl_tickets)
+        self._log_listeners()
 
         load_env(self.env_files)
         self._call_hooks(self.hooks_startup)
         self._spawn_workers(spawn_target, target_loader)
+        self._handoff_ack()
+        if self._tls_tickets:
+            self._start_tls_tickets()
 
     async def _serve_loop(self, spawn_target, target_loader):
         while True:
@@ -437,10 +565,13 @@ class Server(AbstractServer[AsyncWorker]):
 
     async def shutdown(self, exit_code=0):
         logger.info('Shutting down granian')
+        if self._tls_tickets:
+            self._stop_tls_tickets()
         await self._stop_workers()
         self._call_hooks(self.hooks_shutdown)
-        if self.bind_uds and self.bind_uds.exists():
-            self.bind_uds.unlink()
+        uds_unlink(self.bind_uds)
+        for listener in self.listeners:
+            uds_unlink(listener.uds)
 
     async def _serve(self, spawn_target, target_loader):
         target = target_loader()
@@ -466,6 +597,14 @@ class Server(AbstractServer[AsyncWorker]):
             logger.error('WSGI is not supported in embedded mode')
             raise ConfigurationError('interface')
 
+        if any(vhost[2] == Interfaces.WSGI for vhost in self._vhosts):
+            logger.error('WSGI is not supported in embedded mode')
+            raise ConfigurationError('vhosts')
+
+        if any(interface == Interfaces.WSGI for _, interface, _ in self._mounts):
+            logger.error('WSGI is not supported in embedded mode')
+            raise ConfigurationError('mounts')
+
         if self.reload_on_changes:
             logger.error('The changes reloader is not supported in embedded mode')
             raise ConfigurationError('reload')
@@ -485,6 +624,9 @@ class Server(AbstractServer[AsyncWorker]):
             logger.error('Unix Domain sockets are not available on Windows')
             raise ConfigurationError('uds')
 
+        self._check_listeners()
+        self._check_tcp_settings()
+
         if self.blocking_threads > 1:
             logger.error('Blocking threads > 1 is not supported on ASGI and RSGI')
             raise ConfigurationError('blocking_threads')
@@ -16,11 +16,13 @@ from .._granian import (
     ProcInfoCollector,
     RSGIWorker,
     SocketHolder,
+    TLSTicketKeys,
     WorkerSignal,
     WSGIWorker,
 )
 from .._internal import load_env
 from .._types import SSLCtx
+from ..apps import _apps_callbacks, _apps_need_loop, _apps_shutdown, _apps_startup, _apps_startup_error
 from ..asgi import LifespanProtocol, _callback_wrapper as _asgi_call_wrap
 from ..rsgi import _callback_wrapper as _rsgi_call_wrap, _callbacks_from_target as _rsgi_cbs_from_target
 from ..wsgi import _callback_wrapper as _wsgi_call_wrap
@@ -50,7 +52,7 @@ class WorkerProcess(AbstractWorker):
         # NOTE: Python 3.14 defaults mp spawn method to 'forkserver' on Linux,
         #       which doesn't really play well with shared sockets.
         self._spawn_method = multiprocessing.get_start_method()
-        if self._spawn_method not in {'fork', 'spawn'} and parent._sso is not None:
+        if self._spawn_method not in {'fork', 'spawn'} and (parent._sso is not None or parent._lso):
             self._spawn_method = 'spawn'
         super().__init__(parent, idx, target, args)
 
@@ -62,7 +64,9 @@ class WorkerProcess(AbstractWorker):
             process_name,
             callback_loader,
             sock,
+            listeners,
             ipc,
+            ssl_tickets,
             loop_impl,
             log_enabled,
             log_level,
@@ -80,17 +84,28 @@ class WorkerProcess(AbstractWorker):
             load_env(env_files)
 
             _ipc_handle = None
+            _tls_tickets = None
             sock, _sso = sock
+            listeners, _lso = listeners
             if sys.platform == 'win32':
                 sock = (None, SocketHolder(_sso.fileno()))
+                listeners = [(tls, (None, SocketHolder(lso.fileno()))) for (tls, _), lso in zip(listeners, _lso)]
             elif ipc:
                 _ipc_fd = os.dup(ipc.fileno())
                 os.set_blocking(_ipc_fd, False)
                 _ipc_handle = IPCSenderHandle(_ipc_fd)
+            if ssl_tickets:
+                rotation, keys, keys_ipc = ssl_tickets
+                _tls_tickets = TLSTicketKeys.from_keys(rotation, keys)
+                #: rotated keys are pushed by the main process
+                if keys_ipc:
+                    _keys_fd = os.dup(keys_ipc.fileno())
+                    os.set_blocking(_keys_fd, False)
+                    _tls_tickets.listen(IPCReceiverHandle(worker_id, _keys_fd))
 
             loop = loops.get(loop_impl)
             callback = callback_loader()
-            return target(worker_id, callback, sock, _ipc_handle, loop, *args, **kwargs)
+            return target(worker_id, callback, sock, listeners, _ipc_handle, _tls_tickets, loop, *args, **kwargs)
 
         return wrapped
 
@@ -118,11 +133,14 @@ class MPServer(AbstractServer[WorkerProcess]):
         worker_id: int,
         callback: Any,
         sock: Any,
+        listeners: Any,
         ipc: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -132,6 +150,10 @@ class MPServer(AbstractServer[WorkerProcess]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -140,6 +162,7 @@ class MPServer(AbstractServer[WorkerProcess]):
         from granian._signals import set_loop_signals
 
         wcallback = _future_watcher_wrapper(_asgi_call_wrap(callback, scope_opts, {}, log_access_fmt))
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         shutdown_event = set_loop_signals(loop)
         evp = asyncio.Event()
 
@@ -154,6 +177,10 @@ class MPServer(AbstractServer[WorkerProcess]):
 
         shutdown_event.add_cb(shutdown_glue)
 
+        if failed := loop.run_until_complete(_apps_startup(loop, app_lifespans, app_inits)):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            sys.exit(1)
+
         worker = ASGIWorker(
             worker_id,
             sock,
@@ -168,13 +195,25 @@ class MPServer(AbstractServer[WorkerProcess]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         loop.run_until_complete(_main())
+        loop.run_until_complete(_apps_shutdown(loop, app_lifespans, app_dels))
 
     @staticmethod
     @WorkerProcess.wrap_target
@@ -182,11 +221,14 @@ class MPServer(AbstractServer[WorkerProcess]):
         worker_id: int,
         callback: Any,
         sock: Any,
+        listeners: Any,
         ipc: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -196,6 +238,10 @@ class MPServer(AbstractServer[WorkerProcess]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -207,6 +253,7 @@ class MPServer(AbstractServer[WorkerProcess]):
         wcallback = _future_watcher_wrapper(
             _asgi_call_wrap(callback, scope_opts, lifespan_handler.state, log_access_fmt)
         )
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         shutdown_event = set_loop_signals(loop)
         evp = asyncio.Event()
 
@@ -221,9 +268,8 @@ class MPServer(AbstractServer[WorkerProcess]):
 
         shutdown_event.add_cb(shutdown_glue)
 
-        loop.run_until_complete(lifespan_handler.startup())
-        if lifespan_handler.interrupt:
-            logger.error('ASGI lifespan startup failed', exc_info=lifespan_handler.exc)
+        if failed := loop.run_until_complete(_apps_startup(loop, [lifespan_handler, *app_lifespans], app_inits)):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
             sys.exit(1)
 
         worker = ASGIWorker(
@@ -240,14 +286,25 @@ class MPServer(AbstractServer[WorkerProcess]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         loop.run_until_complete(_main())
-        loop.run_until_complete(lifespan_handler.shutdown())
+        loop.run_until_complete(_apps_shutdown(loop, [lifespan_handler, *app_lifespans], app_dels))
 
     @staticmethod
     @WorkerProcess.wrap_target
@@ -255,11 +312,14 @@ class MPServer(AbstractServer[WorkerProcess]):
         worker_id: int,
         callback: Any,
         sock: Any,
+        listeners: Any,
         ipc: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -269,6 +329,10 @@ class MPServer(AbstractServer[WorkerProcess]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -278,6 +342,7 @@ class MPServer(AbstractServer[WorkerProcess]):
 
         callback, callback_init, callback_del = _rsgi_cbs_from_target(callback)
         wcallback = _future_watcher_wrapper(_rsgi_call_wrap(callback, log_access_fmt))
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         shutdown_event = set_loop_signals(loop)
         evp = asyncio.Event()
 
@@ -292,7 +357,9 @@ class MPServer(AbstractServer[WorkerProcess]):
 
         shutdown_event.add_cb(shutdown_glue)
 
-        callback_init(loop)
+        if failed := loop.run_until_complete(_apps_startup(loop, app_lifespans, [callback_init, *app_inits])):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            sys.exit(1)
 
         worker = RSGIWorker(
             worker_id,
@@ -308,14 +375,25 @@ class MPServer(AbstractServer[WorkerProcess]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         loop.run_until_complete(_main())
-        callback_del(loop)
+        loop.run_until_complete(_apps_shutdown(loop, app_lifespans, [callback_del, *app_dels]))
 
     @staticmethod
     @WorkerProcess.wrap_target
@@ -323,11 +401,14 @@ class MPServer(AbstractServer[WorkerProcess]):
         worker_id: int,
         callback: Any,
         sock: Any,
+        listeners: Any,
         ipc: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -337,6 +418,10 @@ class MPServer(AbstractServer[WorkerProcess]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -345,10 +430,15 @@ class MPServer(AbstractServer[WorkerProcess]):
         from granian._signals import set_sync_signals
 
         wcallback = _wsgi_call_wrap(callback, scope_opts, log_access_fmt)
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         shutdown_event = set_sync_signals()
         evp = threading.Event()
 
         def _main():
+            if _apps_need_loop(vhosts, mounts):
+                #: apps with async interfaces need the loop running alongside the WSGI worker
+                loop.run_until_complete(loop.run_in_executor(None, evp.wait))
+                return
             evp.wait()
 
         def shutdown_glue():
@@ -356,6 +446,10 @@ class MPServer(AbstractServer[WorkerProcess]):
 
         shutdown_event.add_cb(shutdown_glue)
 
+        if failed := loop.run_until_complete(_apps_startup(loop, app_lifespans, app_inits)):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            sys.exit(1)
+
         worker = WSGIWorker(
             worker_id,
             sock,
@@ -369,13 +463,25 @@ class MPServer(AbstractServer[WorkerProcess]):
             http1_settings,
             http2_settings,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         _main()
+        loop.run_until_complete(_apps_shutdown(loop, app_lifespans, app_dels))
 
     def _init_shared_socket(self):
         super()._init_shared_socket()
@@ -384,6 +490,14 @@ class MPServer(AbstractServer[WorkerProcess]):
             sock = socket.socket(fileno=self._sfd)
             sock.set_inheritable(True)
             self._sso = sock
+        self._lso = []
+        for _, (_, lshd) in self._lss:
+            if lshd is None:
+                self._lso.append(None)
+                continue
+            sock = socket.socket(fileno=lshd.get_fd())
+            sock.set_inheritable(True)
+            self._lso.append(sock)
 
     def _write_pidfile(self):
         super()._write_pidfile()
@@ -392,6 +506,9 @@ class MPServer(AbstractServer[WorkerProcess]):
     def _unlink_pidfile(self):
         if self._sso is not None:
             self._sso.detach()
+        for sock in self._lso:
+            if sock is not None:
+                sock.detach()
         super()._unlink_pidfile()
 
     def _start_ipc(self):
@@ -416,6 +533,12 @@ class MPServer(AbstractServer[WorkerProcess]):
             #       Doesn't really matter, given the call fails when available U.U
             os.set_blocking(rxd, False)
             self._ipc[idx] = (IPCReceiverHandle(idx, rxd), tx, rx)
+            if self._tls_tickets:
+                #: downstream pipe, used to send rotated TLS session ticket keys to workers
+                rx, tx = multiprocessing.Pipe(False)
+                txd = os.dup(tx.fileno())
+                os.set_blocking(txd, False)
+                self._tls_tickets_ipc[idx] = (IPCSenderHandle(txd), rx, tx)
 
         # NOTE: given we use IPC only for metrics right now, let's run the receivers
         #       only if metrics collection is actually enabled.
@@ -426,6 +549,26 @@ class MPServer(AbstractServer[WorkerProcess]):
     def _stop_ipc(self):
         self._ipc_sig.set()
 
+    def _start_tls_tickets(self):
+        # NOTE: without IPC (see `_start_ipc`) workers would never receive the rotated keys,
+        #       while respawned ones would: thus on Windows we keep the same keys for the server lifetime.
+        if sys.platform == 'win32':
+            logger.warning('SSL session ticket keys rotation is not available in Windows, ignoring.')
+            return
+        super()._start_tls_tickets()
+
+    def _stop_tls_tickets(self):
+        if sys.platform == 'win32':
+            return
+        super()._stop_tls_tickets()
+
+    def _tls_tickets_spec(self, idx):
+        if not self._tls_tickets:
+            return None
+        pipe = self._tls_tickets_ipc.get(idx)
+        #: workers get the current keys on spawn, and the rotated ones through the pipe (if any)
+        return (self.ssl_tickets[0], self._tls_tickets.keys(), pipe[1] if pipe else None)
+
     def _handle_rss_signal(self, spawn_target, target_loader):
         wpids = {wrk._id(): wrk for wrk in self.wrks}
         try:
@@ -449,8 +592,7 @@ class MPServer(AbstractServer[WorkerProcess]):
                 cycle_samples[wpid] = 0
         self._rss_wrk_samples.clear()
         self._rss_wrk_samples.update(cycle_samples)
-        if to_restart:
-            self._respawn_workers(to_restart, spawn_target, target_loader, delay=self.respawn_interval)
+        if to_restart and self._respawn_workers(to_restart, spawn_target, target_loader, delay=self.respawn_interval):
             self._metrics.incr_respawn_rss(len(to_restart))
 
     def _spawn_worker(self, idx, target, callback_loader) -> WorkerProcess:
@@ -463,9 +605,11 @@ class MPServer(AbstractServer[WorkerProcess]):
                 self.process_name,
                 callback_loader,
                 ((self._ssp, self._shd), self._sso),
+                (self._lss, self._lso),
                 # NOTE: given we use IPC only for metrics right now, let's share the pipe
                 #       only if metrics collection is actually enabled.
                 self._ipc[idx][1] if self.metrics_enabled else None,
+                self._tls_tickets_spec(idx),
                 self.loop,
                 self.log_enabled,
                 self.log_level,
@@ -474,6 +618,7 @@ class MPServer(AbstractServer[WorkerProcess]):
                 self.runtime_mode,
                 self.runtime_threads,
                 self.runtime_blocking_threads,
+                self._affinity[idx],
                 self.blocking_threads,
                 self.blocking_threads_idle_timeout,
                 self.backpressure,
@@ -483,10 +628,14 @@ class MPServer(AbstractServer[WorkerProcess]):
                 self.http2_settings,
                 self.websockets,
                 self.static_path,
+                self.error_pages,
+                self._vhosts,
+                self._mounts,
+                self.proxy,
                 self.log_access_format if self.log_access else None,
                 self.ssl_ctx,
                 {'url_path_prefix': self.url_path_prefix},
-                (self.metrics_scrape_interval if self.metrics_enabled else None, None),
+                (self.metrics_scrape_interval if self.metrics_enabled else None, None, self.metrics_buckets),
             ),
         )
 
@@ -9,6 +9,7 @@ from .._futures import _future_watcher_wrapper, _new_cbscheduler
 from .._granian import ASGIWorker, RSGIWorker, WorkerSignal, WSGIWorker
 from .._loops import loops
 from .._types import SSLCtx
+from ..apps import _apps_callbacks, _apps_need_loop, _apps_shutdown, _apps_startup, _apps_startup_error
 from ..asgi import LifespanProtocol, _callback_wrapper as _asgi_call_wrap
 from ..errors import ConfigurationError, FatalError
 from ..rsgi import _callback_wrapper as _rsgi_call_wrap, _callbacks_from_target as _rsgi_cbs_from_target
@@ -20,6 +21,7 @@ from .common import (
     HTTP1Settings,
     HTTP2Settings,
     HTTPModes,
+    Interfaces,
     RuntimeModes,
     TaskImpl,
     logger,
@@ -36,9 +38,9 @@ class WorkerThread(AbstractWorker):
     @staticmethod
     def wrap_target(target):
         @wraps(target)
-        def wrapped(worker_id, sig, callback, sock, loop_impl, *args, **kwargs):
+        def wrapped(worker_id, sig, callback, sock, listeners, ssl_tickets, loop_impl, *args, **kwargs):
             loop = loops.get(loop_impl)
-            return target(worker_id, sig, callback, sock, loop, *args, **kwargs)
+            return target(worker_id, sig, callback, sock, listeners, ssl_tickets, loop, *args, **kwargs)
 
         return wrapped
 
@@ -76,10 +78,13 @@ class MTServer(AbstractServer[WorkerThread]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -89,12 +94,17 @@ class MTServer(AbstractServer[WorkerThread]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
         metrics: Any,
     ):
         wcallback = _future_watcher_wrapper(_asgi_call_wrap(callback, scope_opts, {}, log_access_fmt))
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         evp = asyncio.Event()
 
         async def _main():
@@ -108,6 +118,10 @@ class MTServer(AbstractServer[WorkerThread]):
 
         shutdown_event.add_cb(shutdown_glue)
 
+        if failed := loop.run_until_complete(_apps_startup(loop, app_lifespans, app_inits)):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            sys.exit(1)
+
         worker = ASGIWorker(
             worker_id,
             sock,
@@ -122,13 +136,25 @@ class MTServer(AbstractServer[WorkerThread]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         loop.run_until_complete(_main())
+        loop.run_until_complete(_apps_shutdown(loop, app_lifespans, app_dels))
 
     @staticmethod
     @WorkerThread.wrap_target
@@ -137,10 +163,13 @@ class MTServer(AbstractServer[WorkerThread]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -150,6 +179,10 @@ class MTServer(AbstractServer[WorkerThread]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -159,6 +192,7 @@ class MTServer(AbstractServer[WorkerThread]):
         wcallback = _future_watcher_wrapper(
             _asgi_call_wrap(callback, scope_opts, lifespan_handler.state, log_access_fmt)
         )
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         evp = asyncio.Event()
 
         async def _main():
@@ -172,9 +206,8 @@ class MTServer(AbstractServer[WorkerThread]):
 
         shutdown_event.add_cb(shutdown_glue)
 
-        loop.run_until_complete(lifespan_handler.startup())
-        if lifespan_handler.interrupt:
-            logger.error('ASGI lifespan startup failed', exc_info=lifespan_handler.exc)
+        if failed := loop.run_until_complete(_apps_startup(loop, [lifespan_handler, *app_lifespans], app_inits)):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
             sys.exit(1)
 
         worker = ASGIWorker(
@@ -191,14 +224,25 @@ class MTServer(AbstractServer[WorkerThread]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         loop.run_until_complete(_main())
-        loop.run_until_complete(lifespan_handler.shutdown())
+        loop.run_until_complete(_apps_shutdown(loop, [lifespan_handler, *app_lifespans], app_dels))
 
     @staticmethod
     @WorkerThread.wrap_target
@@ -207,10 +251,13 @@ class MTServer(AbstractServer[WorkerThread]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -220,6 +267,10 @@ class MTServer(AbstractServer[WorkerThread]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
@@ -227,6 +278,7 @@ class MTServer(AbstractServer[WorkerThread]):
     ):
         callback, callback_init, callback_del = _rsgi_cbs_from_target(callback)
         wcallback = _future_watcher_wrapper(_rsgi_call_wrap(callback, log_access_fmt))
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         evp = asyncio.Event()
 
         async def _main():
@@ -240,7 +292,9 @@ class MTServer(AbstractServer[WorkerThread]):
 
         shutdown_event.add_cb(shutdown_glue)
 
-        callback_init(loop)
+        if failed := loop.run_until_complete(_apps_startup(loop, app_lifespans, [callback_init, *app_inits])):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            sys.exit(1)
 
         worker = RSGIWorker(
             worker_id,
@@ -256,14 +310,25 @@ class MTServer(AbstractServer[WorkerThread]):
             http2_settings,
             websockets,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         loop.run_until_complete(_main())
-        callback_del(loop)
+        loop.run_until_complete(_apps_shutdown(loop, app_lifespans, [callback_del, *app_dels]))
 
     @staticmethod
     @WorkerThread.wrap_target
@@ -272,10 +337,13 @@ class MTServer(AbstractServer[WorkerThread]):
         shutdown_event: Any,
         callback: Any,
         sock: Any,
+        listeners: Any,
+        ssl_tickets: Any,
         loop: Any,
         runtime_mode: RuntimeModes,
         runtime_threads: int,
         runtime_blocking_threads: int | None,
+        affinity: tuple[list[int], bool],
         blocking_threads: int,
         blocking_threads_idle_timeout: int,
         backpressure: int,
@@ -285,15 +353,24 @@ class MTServer(AbstractServer[WorkerThread]):
         http2_settings: HTTP2Settings | None,
         websockets: bool,
         static_path: tuple[str, str, str | None] | None,
+        error_pages: str | None,
+        vhosts: list[tuple[list[str], Any, Interfaces, Any]],
+        mounts: list[tuple[str, Interfaces, Any]],
+        proxy: tuple[list[tuple[str, str]], int, int] | None,
         log_access_fmt: str | None,
         ssl_ctx: SSLCtx,
         scope_opts: dict[str, Any],
         metrics: Any,
     ):
         wcallback = _wsgi_call_wrap(callback, scope_opts, log_access_fmt)
+        app_callbacks, app_lifespans, app_inits, app_dels = _apps_callbacks(vhosts, mounts, scope_opts, log_access_fmt)
         evp = threading.Event()
 
         def _main():
+            if _apps_need_loop(vhosts, mounts):
+                #: apps with async interfaces need the loop running alongside the WSGI worker
+                loop.run_until_complete(loop.run_in_executor(None, evp.wait))
+                return
             evp.wait()
 
         def shutdown_glue():
@@ -301,6 +378,10 @@ class MTServer(AbstractServer[WorkerThread]):
 
         shutdown_event.add_cb(shutdown_glue)
 
+        if failed := loop.run_until_complete(_apps_startup(loop, app_lifespans, app_inits)):
+            logger.error(_apps_startup_error(failed), exc_info=failed.exc)
+            sys.exit(1)
+
         worker = WSGIWorker(
             worker_id,
             sock,
@@ -314,13 +395,25 @@ class MTServer(AbstractServer[WorkerThread]):
             http1_settings,
             http2_settings,
             static_path,
+            error_pages,
+            [vhost[:3] for vhost in vhosts],
+            [mount[:2] for mount in mounts],
+            proxy,
+            listeners,
+            affinity,
             *ssl_ctx,
+            ssl_tickets,
             metrics,
         )
-        serve = getattr(worker, WORKERS_METHODS[runtime_mode][(sock[0] or sock[1]).is_uds()])
+        serve = getattr(worker, WORKERS_METHODS[runtime_mode]['multi' if listeners else (sock[0] or sock[1]).is_uds()])
         scheduler = _new_cbscheduler(loop, wcallback, impl_asyncio=task_impl == TaskImpl.asyncio)
-        serve(scheduler, loop, shutdown_event)
+        app_schedulers = [
+            _new_cbscheduler(loop, app_callback, impl_asyncio=task_impl == TaskImpl.asyncio)
+            for app_callback in app_callbacks
+        ]
+        serve(scheduler, loop, shutdown_event, app_schedulers)
         _main()
+        loop.run_until_complete(_apps_shutdown(loop, app_lifespans, app_dels))
 
     def _spawn_worker(self, idx, target, callback_loader) -> WorkerThread:
         sig = WorkerSignal()
@@ -334,10 +427,14 @@ class MTServer(AbstractServer[WorkerThread]):
                 sig,
                 callback_loader,
                 (self._ssp, self._shd),
+                self._lss,
+                #: workers share the same keys of the main thread, so no distribution is needed
+                self._tls_tickets,
                 self.loop,
                 self.runtime_mode,
                 self.runtime_threads,
                 self.runtime_blocking_threads,
+                self._affinity[idx],
                 self.blocking_threads,
                 self.blocking_threads_idle_timeout,
                 self.backpressure,
@@ -347,10 +444,14 @@ class MTServer(AbstractServer[WorkerThread]):
                 self.http2_settings,
                 self.websockets,
                 self.static_path,
+                self.error_pages,
+                self._vhosts,
+                self._mounts,
+                self.proxy,
                 self.log_access_format if self.log_access else None,
                 self.ssl_ctx,
                 {'url_path_prefix': self.url_path_prefix},
-                (self.metrics_scrape_interval if self.metrics_enabled else None, self._metrics),
+                (self.metrics_scrape_interval if self.metrics_enabled else None, self._metrics, self.metrics_buckets),
             ),
             sig=sig,
         )
@@ -0,0 +1,133 @@
+use anyhow::Result;
+use percent_encoding::percent_decode_str;
+use std::{fmt::Write, sync::Arc};
+
+use crate::{
+    der::{OID_COMMON_NAME, OID_ORGANIZATIONAL_UNIT, cert_alt_names, cert_names, name_attributes},
+    tls::TlsInfo,
+};
+
+pub(crate) type ClientCertRuleConfig = (String, Vec<String>, Vec<String>, Vec<String>, Vec<String>);
+
+// The attributes of a client certificate access rules can match on.
+// Names are stored lowercased, as rules match them case-insensitively (like X.500 names and hostnames).
+pub(crate) struct ClientCertIdentity {
+    cn: Vec<String>,
+    ou: Vec<String>,
+    san: Vec<String>,
+    fingerprint: String,
+}
+
+impl ClientCertIdentity {
+    pub fn new(cert: &[u8]) -> Result<Self> {
+        let subject = cert_names(cert)?.1;
+        Ok(Self {
+            cn: lowercase(&name_attributes(subject, OID_COMMON_NAME)?),
+            ou: lowercase(&name_attributes(subject, OID_ORGANIZATIONAL_UNIT)?),
+            san: lowercase(&cert_alt_names(cert)?),
+            fingerprint: fingerprint(cert),
+        })
+    }
+}
+
+fn lowercase(values: &[String]) -> Vec<String> {
+    values.iter().map(|v| v.to_lowercase()).collect()
+}
+
+// SHA-256 digest of the DER encoded certificate, as lowercase hex.
+fn fingerprint(cert: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, cert).as_ref().iter().fold(
+        String::with_capacity(64),
+        |mut acc, byte| {
+            _ = write!(acc, "{byte:02x}");
+            acc
+        },
+    )
+}
+
+// Decodes the path like the protocols do before handing it to applications, collapsing empty and
+// current directory segments, so encoded or redundant characters can't skip a rule.
+// Paths with parent directory segments have no normalised form, and get no match at all.
+fn normalize_path(uri_path: &str) -> Option<String> {
+    let decoded = percent_decode_str(uri_path).decode_utf8_lossy();
+    let mut path = String::with_capacity(decoded.len());
+    for segment in decoded.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => return None,
+            _ => {
+                path.push('/');
+                path.push_str(segment);
+            }
+        }
+    }
+    Some(path)
+}
+
+struct ClientCertRule {
+    prefix: String,
+    cn: Vec<String>,
+    ou: Vec<String>,
+    san: Vec<String>,
+    fingerprints: Vec<String>,
+}
+
+impl ClientCertRule {
+    fn new((prefix, cn, ou, san, fingerprints): &ClientCertRuleConfig) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            cn: lowercase(cn),
+            ou: lowercase(ou),
+            san: lowercase(san),
+            fingerprints: fingerprints
+                .iter()
+                .map(|v| v.replace(':', "").to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    // Every configured attribute should match one of its allowed values.
+    fn allows(&self, identity: &ClientCertIdentity) -> bool {
+        let any = |allowed: &[String], values: &[String]| {
+            allowed.is_empty() || values.iter().any(|value| allowed.contains(value))
+        };
+        any(&self.cn, &identity.cn)
+            && any(&self.ou, &identity.ou)
+            && any(&self.san, &identity.san)
+            && (self.fingerprints.is_empty() || self.fingerprints.contains(&identity.fingerprint))
+    }
+}
+
+pub(crate) struct ClientCertRules {
+    rules: Vec<ClientCertRule>,
+}
+
+impl ClientCertRules {
+    pub fn new(config: &[ClientCertRuleConfig]) -> Option<Arc<Self>> {
+        if config.is_empty() {
+            return None;
+        }
+        let mut rules: Vec<ClientCertRule> = config.iter().map(ClientCertRule::new).collect();
+        // longest prefix first, so the first match is also the most specific one
+        rules.sort_by(|a, b| b.prefix.len().cmp(&a.prefix.len()));
+        Some(Arc::new(Self { rules }))
+    }
+
+    // Checks the client certificate of the connection against the most specific rule matching the path.
+    // Requests on paths with no rules are always allowed, while connections with no (valid) client
+    // certificate are denied on paths with rules. Paths which can't be normalised are always denied.
+    #[inline]
+    pub fn allows(&self, uri_path: &str, tls: Option<&TlsInfo>) -> bool {
+        let Some(path) = normalize_path(uri_path) else {
+            return false;
+        };
+        let Some(rule) = self.rules.iter().find(|rule| {
+            path.strip_prefix(rule.prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        }) else {
+            return true;
+        };
+        tls.and_then(TlsInfo::client_identity)
+            .is_some_and(|identity| rule.allows(identity))
+    }
+}
@@ -0,0 +1,290 @@
+use anyhow::{Result, anyhow, bail};
+use instant_acme::{
+    Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, NewAccount, NewOrder, OrderStatus,
+    RetryPolicy,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+pub(crate) const ACME_TLS_ALPN_PROTOCOL: &[u8] = b"acme-tls/1";
+
+// Storage layout, relative to the storage directory.
+const ACME_ACCOUNT_FILE: &str = "account.cbor";
+const ACME_CERT_FILE: &str = "certificate.pem";
+const ACME_DOMAINS_FILE: &str = "domains";
+const ACME_HTTP_DIR: &str = "http-01";
+pub(crate) const ACME_TLS_ALPN_DIR: &str = "tls-alpn-01";
+
+// seconds between certificate checks, and before retrying a failed order
+const ACME_CHECK_INTERVAL: u64 = 3600;
+const ACME_RETRY_INTERVAL: u64 = 60;
+
+#[derive(Clone, Copy, Debug)]
+enum ACMEChallenge {
+    Http01,
+    TlsAlpn01,
+}
+
+// Challenge files get removed once the order is completed, whatever the outcome.
+struct ChallengeFiles(Vec<PathBuf>);
+
+impl Drop for ChallengeFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            _ = fs::remove_file(path);
+        }
+    }
+}
+
+// Writes files atomically, so workers never read partial contents.
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut opts = fs::OpenOptions::new();
+    opts.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut file = opts.open(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+// Self-signed certificate carrying the `acmeIdentifier` extension, as per RFC 8737.
+fn tls_alpn_cert(domain: &str, digest: &[u8]) -> Result<String> {
+    let mut params = rcgen::CertificateParams::new(vec![domain.to_owned()])?;
+    params.custom_extensions = vec![rcgen::CustomExtension::new_acme_identifier(digest)];
+    let key = rcgen::KeyPair::generate()?;
+    let cert = params.self_signed(&key)?;
+    Ok(format!("{}{}", cert.pem(), key.serialize_pem()))
+}
+
+#[pyclass(frozen, module = "granian._granian")]
+pub(crate) struct ACMEManager {
+    directory: String,
+    domains: Vec<String>,
+    contact: Vec<String>,
+    storage: PathBuf,
+    challenge: ACMEChallenge,
+    ca_root: Option<String>,
+    terms_of_service_agreed: bool,
+}
+
+impl ACMEManager {
+    // Renewal is due when a third of the certificate validity is left, or the domains changed.
+    fn renewal_due(&self) -> bool {
+        let domains = fs::read_to_string(self.storage.join(ACME_DOMAINS_FILE)).unwrap_or_default();
+        if domains != self.domains.join("\n") {
+            return true;
+        }
+        let validity = fs::read(self.storage.join(ACME_CERT_FILE))
+            .ok()
+            .and_then(|content| pem::parse_many(content).ok())
+            .and_then(|sections| sections.into_iter().find(|v| v.tag() == "CERTIFICATE"))
+            .and_then(|cert| crate::der::cert_validity(cert.contents()).ok());
+        match validity {
+            Some((not_before, not_after)) => {
+                let lifetime = not_after.duration_since(not_before).unwrap_or_default();
+                SystemTime::now() >= not_before + lifetime * 2 / 3
+            }
+            None => true,
+        }
+    }
+
+    async fn account(&self) -> Result<Account> {
+        let builder = match &self.ca_root {
+            Some(root) => Account::builder_with_root(root)?,
+            None => Account::builder()?,
+        };
+        let path = self.storage.join(ACME_ACCOUNT_FILE);
+        if let Ok(file) = fs::File::open(&path) {
+            let credentials: AccountCredentials = ciborium::from_reader(file)?;
+            return Ok(builder.from_credentials(credentials).await?);
+        }
+
+        let contact: Vec<String> = self
+            .contact
+            .iter()
+            .map(|v| {
+                if v.contains(':') {
+                    v.clone()
+                } else {
+                    format!("mailto:{v}")
+                }
+            })
+            .collect();
+        let contact: Vec<&str> = contact.iter().map(String::as_str).collect();
+        let (account, credentials) = builder
+            .create(
+                &NewAccount {
+                    contact: &contact,
+                    terms_of_service_agreed: self.terms_of_service_agreed,
+                    only_return_existing: false,
+                },
+                self.directory.clone(),
+                None,
+            )
+            .await?;
+        let mut data = Vec::new();
+        ciborium::into_writer(&credentials, &mut data)?;
+        write_file(&path, &data)?;
+        log::info!("Registered ACME account on {}", self.directory);
+        Ok(account)
+    }
+
+    async fn order(&self) -> Result<()> {
+        let account = self.account().await?;
+        let identifiers: Vec<Identifier> = self.domains.iter().map(|v| Identifier::Dns(v.clone())).collect();
+        let mut order = account.new_order(&NewOrder::new(&identifiers)).await?;
+
+        let mut challenge_files = ChallengeFiles(vec![]);
+        let mut authorizations = order.authorizations();
+        while let Some(authz) = authorizations.next().await {
+            let mut authz = authz?;
+            match authz.status {
+                AuthorizationStatus::Pending => {}
+                AuthorizationStatus::Valid => continue,
+                status => bail!("unexpected authorization status {status:?}"),
+            }
+            let Identifier::Dns(domain) = authz.identifier.clone() else {
+                bail!("unsupported identifier {:?}", authz.identifier);
+            };
+            let challenge_type = match self.challenge {
+                ACMEChallenge::Http01 => ChallengeType::Http01,
+                ACMEChallenge::TlsAlpn01 => ChallengeType::TlsAlpn01,
+            };
+            let mut challenge = authz
+                .challenge(challenge_type.clone())
+                .ok_or_else(|| anyhow!("{challenge_type:?} challenge not available for {domain}"))?;
+            let key_auth = challenge.key_authorization();
+            let path = match self.challenge {
+                ACMEChallenge::Http01 => {
+                    let path = self.storage.join(ACME_HTTP_DIR).join(&challenge.token);
+                    write_file(&path, key_auth.as_str().as_bytes())?;
+                    path
+                }
+                ACMEChallenge::TlsAlpn01 => {
+                    let path = self.storage.join(ACME_TLS_ALPN_DIR).join(format!("{domain}.pem"));
+                    write_file(&path, tls_alpn_cert(&domain, key_auth.digest().as_ref())?.as_bytes())?;
+                    path
+                }
+            };
+            challenge_files.0.push(path);
+            challenge.set_ready().await?;
+        }
+
+        let status = order.poll_ready(&RetryPolicy::default()).await?;
+        if status != OrderStatus::Ready {
+            bail!("order failed with status {status:?}");
+        }
+        let private_key = order.finalize().await?;
+        let chain = order.poll_certificate(&RetryPolicy::default()).await?;
+        drop(challenge_files);
+
+        // certificates and key share the same file, so workers never load a mismatching pair
+        let pem = format!("{}\n{private_key}", chain.trim_end());
+        write_file(&self.storage.join(ACME_CERT_FILE), pem.as_bytes())?;
+        let domains = self.domains.join("\n");
+        write_file(&self.storage.join(ACME_DOMAINS_FILE), domains.as_bytes())?;
+        Ok(())
+    }
+
+    async fn ensure_certificate(&self) -> Result<()> {
+        if !self.renewal_due() {
+            return Ok(());
+        }
+        fs::create_dir_all(self.storage.join(ACME_HTTP_DIR))?;
+        fs::create_dir_all(self.storage.join(ACME_TLS_ALPN_DIR))?;
+
+        log::info!("Requesting ACME certificate for {}", self.domains.join(", "));
+        self.order().await?;
+        log::info!("Obtained ACME certificate for {}", self.domains.join(", "));
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ACMEManager {
+    #[new]
+    #[pyo3(
+        signature = (
+            directory,
+            domains,
+            contact,
+            storage,
+            challenge,
+            ca_root=None,
+            terms_of_service_agreed=false,
+        )
+    )]
+    fn new(
+        directory: String,
+        domains: Vec<String>,
+        contact: Vec<String>,
+        storage: String,
+        challenge: &str,
+        ca_root: Option<String>,
+        terms_of_service_agreed: bool,
+    ) -> PyResult<Self> {
+        let challenge = match challenge {
+            "http-01" => ACMEChallenge::Http01,
+            "tls-alpn-01" => ACMEChallenge::TlsAlpn01,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported ACME challenge: {challenge}"
+                )));
+            }
+        };
+        Ok(Self {
+            directory,
+            domains,
+            contact,
+            storage: storage.into(),
+            challenge,
+            ca_root,
+            terms_of_service_agreed,
+        })
+    }
+
+    fn run(pyself: Py<Self>, sig: Py<crate::workers::WorkerSignal>) {
+        let mut sig = sig.get().arx.lock().unwrap().take().unwrap();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            rt.block_on(async {
+                let manager = pyself.get();
+                loop {
+                    let interval = match manager.ensure_certificate().await {
+                        Ok(()) => ACME_CHECK_INTERVAL,
+                        Err(err) => {
+                            log::error!("ACME certificate order failed: {err}");
+                            ACME_RETRY_INTERVAL
+                        }
+                    };
+                    tokio::select! {
+                        () = tokio::time::sleep(Duration::from_secs(interval)) => {}
+                        _ = sig.changed() => break,
+                    }
+                }
+            });
+            Python::attach(|_| drop(pyself));
+        });
+    }
+}
+
+pub(crate) fn init_pymodule(module: &Bound<PyModule>) -> PyResult<()> {
+    module.add_class::<ACMEManager>()?;
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use anyhow::Result;
+use hyper::header::HOST;
+use pyo3::prelude::*;
+use std::{pin::Pin, sync::Arc};
+use tokio::sync::Notify;
+
+use crate::{
+    callbacks::{ArcCBScheduler, CallbackScheduler},
+    http::{HTTPProto, HTTPRequest, HTTPResponse},
+    net::SockAddr,
+    runtime::RuntimeRef,
+};
+
+pub(crate) type StaticFilesConfig = (Vec<(String, String)>, Option<String>, Option<String>);
+
+pub(crate) type AppHandler = fn(
+    RuntimeRef,
+    Arc<Notify>,
+    ArcCBScheduler,
+    SockAddr,
+    SockAddr,
+    HTTPRequest,
+    HTTPProto,
+) -> Pin<Box<dyn Future<Output = HTTPResponse> + Send>>;
+
+macro_rules! app_handler {
+    ($handler:path) => {{
+        fn handle(
+            rt: RuntimeRef,
+            disconnect_guard: Arc<Notify>,
+            callback: ArcCBScheduler,
+            server_addr: SockAddr,
+            client_addr: SockAddr,
+            req: HTTPRequest,
+            scheme: HTTPProto,
+        ) -> Pin<Box<dyn Future<Output = HTTPResponse> + Send>> {
+            Box::pin($handler(
+                rt,
+                disconnect_guard,
+                callback,
+                server_addr,
+                client_addr,
+                req,
+                scheme,
+            ))
+        }
+        handle as AppHandler
+    }};
+}
+
+pub(crate) struct WorkerApp {
+    pub callback: ArcCBScheduler,
+    pub handler: AppHandler,
+    static_mounts: Vec<(String, String)>,
+    static_dir_to_file: Option<String>,
+    pub static_expires: Option<String>,
+}
+
+impl WorkerApp {
+    fn new(
+        callback: Py<CallbackScheduler>,
+        interface: &str,
+        websockets: bool,
+        static_files: Option<StaticFilesConfig>,
+    ) -> Self {
+        let (static_mounts, static_dir_to_file, static_expires) = static_files.unwrap_or_default();
+        // apps might use a different interface than the worker's one,
+        // while websockets support depends on the worker connections.
+        let handler = match (interface, websockets) {
+            ("rsgi", true) => app_handler!(crate::rsgi::http::handle_ws),
+            ("rsgi", false) => app_handler!(crate::rsgi::http::handle),
+            ("wsgi", _) => app_handler!(crate::wsgi::http::handle),
+            (_, true) => app_handler!(crate::asgi::http::handle_ws),
+            (_, false) => app_handler!(crate::asgi::http::handle),
+        };
+        Self {
+            callback: Arc::new(callback),
+            handler,
+            static_mounts,
+            static_dir_to_file,
+            static_expires,
+        }
+    }
+
+    #[inline]
+    pub fn match_static_file(&self, uri_path: &str) -> Option<Result<String>> {
+        if self.static_mounts.is_empty() {
+            return None;
+        }
+        crate::files::match_static_file(uri_path, &self.static_mounts, self.static_dir_to_file.as_ref())
+    }
+}
+
+pub(crate) enum HostPattern {
+    Exact(String),
+    Wildcard(String),
+}
+
+impl HostPattern {
+    pub fn new(value: &str) -> Self {
+        let value = value.trim().to_ascii_lowercase();
+        match value.strip_prefix("*.") {
+            Some(suffix) => Self::Wildcard(format!(".{suffix}")),
+            None => Self::Exact(value),
+        }
+    }
+
+    #[inline]
+    pub fn matches(&self, host: &str) -> bool {
+        match self {
+            Self::Exact(name) => name.eq_ignore_ascii_case(host),
+            Self::Wildcard(suffix) => {
+                host.len() > suffix.len()
+                    && host.is_char_boundary(host.len() - suffix.len())
+                    && host[host.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+            }
+        }
+    }
+}
+
+pub(crate) struct WorkerApps {
+    vhosts: Vec<(Vec<HostPattern>, WorkerApp)>,
+    mounts: Vec<(String, WorkerApp)>,
+}
+
+impl WorkerApps {
+    // Callbacks are expected in the same order of the configuration: vhosts first, then mounts.
+    pub fn new(
+        vhosts_cfg: &[(Vec<String>, Option<StaticFilesConfig>, String)],
+        mounts_cfg: &[(String, String)],
+        websockets: bool,
+        callbacks: Vec<Py<CallbackScheduler>>,
+    ) -> Option<Arc<Self>> {
+        if callbacks.is_empty() {
+            return None;
+        }
+
+        let mut callbacks = callbacks.into_iter();
+        let vhosts = vhosts_cfg
+            .iter()
+            .zip(callbacks.by_ref())
+            .map(|((hosts, static_files, interface), callback)| {
+                (
+                    hosts.iter().map(|v| HostPattern::new(v)).collect(),
+                    WorkerApp::new(callback, interface, websockets, static_files.clone()),
+                )
+            })
+            .collect();
+        let mut mounts: Vec<(String, WorkerApp)> = mounts_cfg
+            .iter()
+            .zip(callbacks)
+            .map(|((prefix, interface), callback)| {
+                (
+                    prefix.trim_end_matches('/').to_string(),
+                    WorkerApp::new(callback, interface, websockets, None),
+                )
+            })
+            .collect();
+        // longest prefix first, so the first match is also the most specific one
+        mounts.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+        Some(Arc::new(Self { vhosts, mounts }))
+    }
+
+    // Picks the app for the given request, using the `Host` header (or `:authority`)
+    // and falling back to the TLS SNI, then the path prefix mounts.
+    // `None` means the default app should be used.
+    #[inline]
+    pub fn resolve(&self, req: &HTTPRequest, sni: Option<&str>) -> Option<&WorkerApp> {
+        if !self.vhosts.is_empty()
+            && let Some(host) = request_host(req).or(sni)
+            && let Some((_, app)) = self
+                .vhosts
+                .iter()
+                .find(|(patterns, _)| patterns.iter().any(|pattern| pattern.matches(host)))
+        {
+            return Some(app);
+        }
+
+        let path = req.uri().path();
+        self.mounts
+            .iter()
+            .find(|(prefix, _)| {
+                path.strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .map(|(_, app)| app)
+    }
+}
+
+#[inline]
+fn request_host(req: &HTTPRequest) -> Option<&str> {
+    if let Some(host) = req.uri().host() {
+        return Some(host.trim_start_matches('[').trim_end_matches(']'));
+    }
+    let host = req.headers().get(HOST)?.to_str().ok()?;
+    if let Some(ipv6) = host.strip_prefix('[') {
+        return ipv6.split(']').next();
+    }
+    host.split(':').next()
+}
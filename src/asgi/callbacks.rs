@@ -10,6 +10,7 @@ use super::{
 use crate::{
     callbacks::ArcCBScheduler,
     http::{HTTPProto, HTTPResponse, response_500},
+    metrics::{RequestMetrics, WebsocketMetrics},
     net::SockAddr,
     runtime::{Runtime, RuntimeRef},
     utils::log_application_callable_exception,
@@ -150,13 +151,17 @@ pub(crate) fn call_http(
     server_addr: SockAddr,
     client_addr: SockAddr,
     scheme: HTTPProto,
-    req: hyper::http::request::Parts,
+    mut req: hyper::http::request::Parts,
     body: hyper::body::Incoming,
 ) -> oneshot::Receiver<HTTPResponse> {
     let (tx, rx) = oneshot::channel();
     let protocol = HTTPProtocol::new(rt.clone(), body, tx, disconnect_guard);
+    let metrics = req.extensions.remove::<RequestMetrics>();
 
     rt.spawn_blocking(move |py| {
+        if let Some(metrics) = metrics {
+            metrics.observe_queue();
+        }
         if let Ok(scope) = build_scope_http(py, req, server_addr, client_addr, scheme)
             && let Ok(watcher) = CallbackWatcherHTTP::new(py, protocol, scope)
         {
@@ -180,7 +185,8 @@ pub(crate) fn call_ws(
     upgrade: UpgradeData,
 ) -> oneshot::Receiver<WebsocketDetachedTransport> {
     let (tx, rx) = oneshot::channel();
-    let protocol = WebsocketProtocol::new(rt.clone(), tx, ws, upgrade, disconnect_guard);
+    let metrics = WebsocketMetrics::new(req.extensions.get::<RequestMetrics>());
+    let protocol = WebsocketProtocol::new(rt.clone(), tx, ws, upgrade, disconnect_guard, metrics);
 
     rt.spawn_blocking(move |py| {
         if let Ok(scope) = build_scope_ws(py, req, server_addr, client_addr, scheme)
@@ -1,12 +1,10 @@
-use http_body_util::BodyExt;
-use hyper::{StatusCode, header::SERVER as HK_SERVER, http::response::Builder as ResponseBuilder};
 use std::sync::Arc;
 use tokio::sync::{Notify, mpsc};
 
 use super::callbacks::{call_http, call_ws};
 use crate::{
     callbacks::ArcCBScheduler,
-    http::{HTTPProto, HTTPRequest, HTTPResponse, HV_SERVER, empty_body, response_500},
+    http::{HTTPProto, HTTPRequest, HTTPResponse, response_400, response_500, response_empty},
     net::SockAddr,
     runtime::{Runtime, RuntimeRef},
     ws::{UpgradeData, is_upgrade_request as is_ws_upgrade, upgrade_intent as ws_upgrade},
@@ -102,15 +100,7 @@ macro_rules! handle_request_with_ws {
                                 Ok(mut detached) => {
                                     match detached.consumed {
                                         false => {
-                                            let _ = tx_ref
-                                                .send(
-                                                    ResponseBuilder::new()
-                                                        .status(StatusCode::FORBIDDEN)
-                                                        .header(HK_SERVER, HV_SERVER)
-                                                        .body(empty_body())
-                                                        .unwrap(),
-                                                )
-                                                .await;
+                                            let _ = tx_ref.send(response_empty(403)).await;
                                         }
                                         true => {
                                             detached.close().await;
@@ -134,15 +124,7 @@ macro_rules! handle_request_with_ws {
                     }
                     Err(err) => {
                         log::info!("Websocket handshake failed with {:?}", err);
-                        return ResponseBuilder::new()
-                            .status(StatusCode::BAD_REQUEST)
-                            .header(HK_SERVER, HV_SERVER)
-                            .body(
-                                http_body_util::Full::new(format!("{}", err).into())
-                                    .map_err(|e| match e {})
-                                    .boxed(),
-                            )
-                            .unwrap();
+                        return response_400(format!("{}", err));
                     }
                 };
             }
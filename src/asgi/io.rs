@@ -24,6 +24,7 @@ use super::{
 use crate::{
     conversion::FutureResultToPy,
     http::{HTTPResponse, HTTPResponseBody, HV_SERVER, response_404},
+    metrics::WebsocketMetrics,
     runtime::{
         Runtime, RuntimeRef, done_future_into_py, empty_future_into_py, err_future_into_py, future_into_py_futlike,
     },
@@ -294,10 +295,9 @@ impl ASGIHTTPProtocol {
             ) {
                 (true, Some(tx)) => {
                     let (status, headers) = self.response_intent.lock().unwrap().take().unwrap();
-                    // FIXME: to store the actual status in case of 404 this should be re-implemented taking
-                    //        into account the following async flow (we return empty future to avoid waiting)
-                    self.sent_response_code.store(status, atomic::Ordering::Relaxed);
-                    self.rt.spawn(async move {
+                    let sent_response_code = self.sent_response_code.clone();
+                    // we wait for the file to be opened, so the actual status is stored before the app returns
+                    future_into_py_futlike(self.rt.clone(), py, async move {
                         let res = match File::open(&file_path).await {
                             Ok(file) => {
                                 let stream = ReaderStream::with_capacity(file, 131_072);
@@ -313,9 +313,10 @@ impl ASGIHTTPProtocol {
                                 response_404()
                             }
                         };
+                        sent_response_code.store(res.status().as_u16(), atomic::Ordering::Relaxed);
                         let _ = tx.send(res);
-                    });
-                    empty_future_into_py(py)
+                        FutureResultToPy::None
+                    })
                 }
                 _ => error_flow!("Response not started"),
             },
@@ -379,6 +380,7 @@ pub(crate) struct ASGIWebsocketProtocol {
     init_tx: Arc<atomic::AtomicBool>,
     init_event: Arc<Notify>,
     closed: Arc<atomic::AtomicBool>,
+    metrics: WebsocketMetrics,
 }
 
 impl ASGIWebsocketProtocol {
@@ -388,6 +390,7 @@ impl ASGIWebsocketProtocol {
         websocket: HyperWebsocket,
         upgrade: UpgradeData,
         disconnect_guard: Arc<Notify>,
+        metrics: WebsocketMetrics,
     ) -> Self {
         Self {
             rt,
@@ -402,6 +405,7 @@ impl ASGIWebsocketProtocol {
             init_tx: Arc::new(false.into()),
             init_event: Arc::new(Notify::new()),
             closed: Arc::new(false.into()),
+            metrics,
         }
     }
 
@@ -414,6 +418,7 @@ impl ASGIWebsocketProtocol {
         let closed = self.closed.clone();
         let rx = self.ws_rx.clone();
         let tx = self.ws_tx.clone();
+        let metrics = self.metrics.clone();
 
         future_into_py_futlike(self.rt.clone(), py, async move {
             if let Some(mut upgrade) = upgrade {
@@ -431,6 +436,7 @@ impl ASGIWebsocketProtocol {
                     *wtx = Some(tx);
                     *wrx = Some(rx);
                     drop(wrx);
+                    metrics.accepted();
                     accepted.store(true, atomic::Ordering::Release);
                     accept_notify.notify_one();
                     return FutureResultToPy::None;
@@ -473,6 +479,7 @@ impl ASGIWebsocketProtocol {
         }
 
         if let Some(mut upgrade) = self.upgrade.lock().unwrap().take() {
+            self.metrics.rejected();
             return future_into_py_futlike(self.rt.clone(), py, async move {
                 let (status, headers) = intent.unwrap();
                 if (upgrade.send(Some(status), Some(headers), Some(body.into())).await).is_ok() {
@@ -492,6 +499,7 @@ impl ASGIWebsocketProtocol {
 
         let transport = self.ws_tx.clone();
         let closed = self.closed.clone();
+        self.metrics.sent(&data);
         future_into_py_futlike(self.rt.clone(), py, async move {
             if let Some(ws) = &mut *(transport.lock().await) {
                 match ws.send(data).await {
@@ -513,10 +521,12 @@ impl ASGIWebsocketProtocol {
         let init_ev = self.init_event.clone();
         let ws_rx = self.ws_rx.clone();
         let ws_tx = self.ws_tx.clone();
+        let metrics = self.metrics.clone();
         self.closed.store(true, atomic::Ordering::Release);
 
         future_into_py_futlike(self.rt.clone(), py, async move {
             if let Some(tx) = ws_tx.lock().await.take() {
+                metrics.sent(&Message::Close(frame.clone()));
                 WebsocketDetachedTransport::new(true, ws_rx.lock().await.take(), Some(tx), frame)
                     .close()
                     .await;
@@ -540,6 +550,11 @@ impl ASGIWebsocketProtocol {
         self.closed.store(true, atomic::Ordering::Release);
         let ws_rx = self.ws_rx.try_lock().map_or(None, |mut guard| guard.take());
         let ws_tx = self.ws_tx.try_lock().map_or(None, |mut guard| guard.take());
+        if self.consumed() {
+            self.metrics.finished();
+        } else {
+            self.metrics.rejected();
+        }
         (
             self.tx.lock().unwrap().take(),
             WebsocketDetachedTransport::new(self.consumed(), ws_rx, ws_tx, None),
@@ -574,6 +589,7 @@ impl ASGIWebsocketProtocol {
         let closed = self.closed.clone();
         let transport = self.ws_rx.clone();
         let guard_disconnect = self.disconnect_guard.clone();
+        let metrics = self.metrics.clone();
 
         future_into_py_futlike(self.rt.clone(), py, async move {
             if !accepted.load(atomic::Ordering::Acquire) {
@@ -590,10 +606,14 @@ impl ASGIWebsocketProtocol {
                     match recv {
                         Ok(Message::Ping(_) | Message::Pong(_)) => {}
                         Ok(message @ Message::Close(_)) => {
+                            metrics.received(&message);
                             closed.store(true, atomic::Ordering::Release);
                             return FutureResultToPy::ASGIWSMessage(message);
                         }
-                        Ok(message) => return FutureResultToPy::ASGIWSMessage(message),
+                        Ok(message) => {
+                            metrics.received(&message);
+                            return FutureResultToPy::ASGIWSMessage(message);
+                        }
                         _ => {
                             // treat any recv error as a disconnection
                             closed.store(true, atomic::Ordering::Release);
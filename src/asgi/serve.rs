@@ -31,6 +31,12 @@ impl ASGIWorker {
             http2_opts=None,
             websockets_enabled=false,
             static_files=None,
+            error_pages=None,
+            vhosts=vec![],
+            mounts=vec![],
+            proxy=None,
+            listeners=vec![],
+            affinity=(vec![], false),
             ssl_enabled=false,
             ssl_cert=None,
             ssl_key=None,
@@ -39,7 +45,18 @@ impl ASGIWorker {
             ssl_ca=None,
             ssl_crl=vec![],
             ssl_client_verify=false,
-            metrics=(None, None),
+            ssl_reload_interval=None,
+            ssl_sni_certs=vec![],
+            ssl_ocsp=None,
+            ssl_acme=None,
+            ssl_session_cache=256,
+            ssl_ciphers=vec![],
+            ssl_kx_groups=vec![],
+            ssl_pkcs12=false,
+            ssl_ktls=false,
+            ssl_client_rules=vec![],
+            ssl_tickets=None,
+            metrics=(None, None, vec![]),
         )
     )]
     fn new(
@@ -57,6 +74,18 @@ impl ASGIWorker {
         http2_opts: Option<Py<PyAny>>,
         websockets_enabled: bool,
         static_files: Option<(Vec<(String, String)>, Option<String>, Option<String>)>,
+        error_pages: Option<String>,
+        vhosts: Vec<(Vec<String>, Option<crate::apps::StaticFilesConfig>, String)>,
+        mounts: Vec<(String, String)>,
+        proxy: Option<crate::proxy::ProxyConfig>,
+        listeners: Vec<(
+            bool,
+            (
+                Option<Py<crate::net::ListenerSpec>>,
+                Option<Py<crate::net::SocketHolder>>,
+            ),
+        )>,
+        affinity: (Vec<usize>, bool),
         ssl_enabled: bool,
         ssl_cert: Option<String>,
         ssl_key: Option<String>,
@@ -65,7 +94,18 @@ impl ASGIWorker {
         ssl_ca: Option<String>,
         ssl_crl: Vec<String>,
         ssl_client_verify: bool,
-        metrics: (Option<u64>, Option<Py<crate::metrics::MetricsAggregator>>),
+        ssl_reload_interval: Option<u64>,
+        ssl_sni_certs: Vec<(Vec<String>, String, String, Option<String>, Option<String>)>,
+        ssl_ocsp: Option<String>,
+        ssl_acme: Option<String>,
+        ssl_session_cache: usize,
+        ssl_ciphers: Vec<String>,
+        ssl_kx_groups: Vec<String>,
+        ssl_pkcs12: bool,
+        ssl_ktls: bool,
+        ssl_client_rules: Vec<crate::access::ClientCertRuleConfig>,
+        ssl_tickets: Option<Py<crate::tickets::TLSTicketKeys>>,
+        metrics: (Option<u64>, Option<Py<crate::metrics::MetricsAggregator>>, Vec<f64>),
     ) -> PyResult<Self> {
         Ok(Self {
             config: WorkerConfig::new(
@@ -82,6 +122,12 @@ impl ASGIWorker {
                 worker_http2_config_from_py(py, http2_opts)?,
                 websockets_enabled,
                 static_files,
+                error_pages,
+                vhosts,
+                mounts,
+                proxy,
+                listeners,
+                affinity,
                 ssl_enabled,
                 ssl_cert,
                 ssl_key,
@@ -90,17 +136,30 @@ impl ASGIWorker {
                 ssl_ca,
                 ssl_crl,
                 ssl_client_verify,
+                ssl_reload_interval,
+                ssl_sni_certs,
+                ssl_ocsp,
+                ssl_acme,
+                ssl_session_cache,
+                ssl_ciphers,
+                ssl_kx_groups,
+                ssl_pkcs12,
+                ssl_ktls,
+                ssl_client_rules,
+                ssl_tickets,
                 metrics,
-            ),
+            )?,
         })
     }
 
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
     fn serve_mtr(
         &self,
         py: Python,
         callback: Py<CallbackScheduler>,
         event_loop: &Bound<PyAny>,
         signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
     ) {
         gen_serve_match!(
             crate::serve::serve_mt,
@@ -109,6 +168,7 @@ impl ASGIWorker {
             self,
             py,
             callback,
+            apps,
             event_loop,
             signal,
             handle,
@@ -116,12 +176,14 @@ impl ASGIWorker {
         );
     }
 
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
     fn serve_str(
         &self,
         py: Python,
         callback: Py<CallbackScheduler>,
         event_loop: &Bound<PyAny>,
         signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
     ) {
         gen_serve_match!(
             crate::serve::serve_st,
@@ -130,6 +192,7 @@ impl ASGIWorker {
             self,
             py,
             callback,
+            apps,
             event_loop,
             signal,
             handle,
@@ -137,12 +200,14 @@ impl ASGIWorker {
         );
     }
 
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
     fn serve_async(
         &self,
         py: Python,
         callback: Py<CallbackScheduler>,
         event_loop: &Bound<PyAny>,
         signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
     ) {
         gen_serve_match!(
             crate::serve::serve_fut,
@@ -151,6 +216,7 @@ impl ASGIWorker {
             self,
             py,
             callback,
+            apps,
             event_loop,
             signal,
             handle,
@@ -159,12 +225,14 @@ impl ASGIWorker {
     }
 
     #[cfg(unix)]
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
     fn serve_mtr_uds(
         &self,
         py: Python,
         callback: Py<CallbackScheduler>,
         event_loop: &Bound<PyAny>,
         signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
     ) {
         gen_serve_match!(
             crate::serve::serve_mt_uds,
@@ -173,6 +241,7 @@ impl ASGIWorker {
             self,
             py,
             callback,
+            apps,
             event_loop,
             signal,
             handle,
@@ -181,12 +250,14 @@ impl ASGIWorker {
     }
 
     #[cfg(unix)]
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
     fn serve_str_uds(
         &self,
         py: Python,
         callback: Py<CallbackScheduler>,
         event_loop: &Bound<PyAny>,
         signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
     ) {
         gen_serve_match!(
             crate::serve::serve_st_uds,
@@ -195,6 +266,7 @@ impl ASGIWorker {
             self,
             py,
             callback,
+            apps,
             event_loop,
             signal,
             handle,
@@ -203,12 +275,14 @@ impl ASGIWorker {
     }
 
     #[cfg(unix)]
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
     fn serve_async_uds(
         &self,
         py: Python,
         callback: Py<CallbackScheduler>,
         event_loop: &Bound<PyAny>,
         signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
     ) {
         gen_serve_match!(
             crate::serve::serve_fut_uds,
@@ -217,6 +291,79 @@ impl ASGIWorker {
             self,
             py,
             callback,
+            apps,
+            event_loop,
+            signal,
+            handle,
+            handle_ws
+        );
+    }
+
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
+    fn serve_mtr_multi(
+        &self,
+        py: Python,
+        callback: Py<CallbackScheduler>,
+        event_loop: &Bound<PyAny>,
+        signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
+    ) {
+        gen_serve_match!(
+            crate::serve::serve_mt_multi,
+            WorkerAcceptorMultiPlain,
+            WorkerAcceptorMultiTls,
+            self,
+            py,
+            callback,
+            apps,
+            event_loop,
+            signal,
+            handle,
+            handle_ws
+        );
+    }
+
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
+    fn serve_str_multi(
+        &self,
+        py: Python,
+        callback: Py<CallbackScheduler>,
+        event_loop: &Bound<PyAny>,
+        signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
+    ) {
+        gen_serve_match!(
+            crate::serve::serve_st_multi,
+            WorkerAcceptorMultiPlain,
+            WorkerAcceptorMultiTls,
+            self,
+            py,
+            callback,
+            apps,
+            event_loop,
+            signal,
+            handle,
+            handle_ws
+        );
+    }
+
+    #[pyo3(signature = (callback, event_loop, signal, apps=vec![]))]
+    fn serve_async_multi(
+        &self,
+        py: Python,
+        callback: Py<CallbackScheduler>,
+        event_loop: &Bound<PyAny>,
+        signal: Py<WorkerSignal>,
+        apps: Vec<Py<CallbackScheduler>>,
+    ) {
+        gen_serve_match!(
+            crate::serve::serve_fut_multi,
+            WorkerAcceptorMultiPlain,
+            WorkerAcceptorMultiTls,
+            self,
+            py,
+            callback,
+            apps,
             event_loop,
             signal,
             handle,
@@ -8,10 +8,12 @@ use pyo3::{
     types::{PyBytes, PyDict, PyList, PyString},
 };
 
-use crate::{http::HTTPProto, net::SockAddr};
+use std::sync::Arc;
+
+use crate::{http::HTTPProto, net::SockAddr, tls::TlsInfo};
 
 static ASGI_VERSION: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
-static ASGI_EXTENSIONS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static ASGI_EXTENSIONS: PyOnceLock<Py<PyDict>> = PyOnceLock::new();
 
 macro_rules! scope_set {
     ($py:expr, $scope:expr, $key:expr, $val:expr) => {
@@ -39,19 +41,34 @@ macro_rules! build_scope_common {
                 })?
                 .bind($py)
         );
-        scope_set!(
-            $py,
-            $scope,
-            "extensions",
-            ASGI_EXTENSIONS
-                .get_or_try_init($py, || {
-                    let rv = PyDict::new($py);
-                    rv.set_item("http.response.pathsend", PyDict::new($py))?;
-                    rv.set_item("websocket.http.response", PyDict::new($py))?;
-                    Ok::<Py<PyAny>, PyErr>(rv.into())
-                })?
-                .bind($py)
-        );
+        let extensions = ASGI_EXTENSIONS
+            .get_or_try_init($py, || {
+                let rv = PyDict::new($py);
+                rv.set_item("http.response.pathsend", PyDict::new($py))?;
+                rv.set_item("websocket.http.response", PyDict::new($py))?;
+                Ok::<Py<PyDict>, PyErr>(rv.unbind())
+            })?
+            .bind($py);
+        // NOTE: the extensions dict is shared across requests, so we copy it only when
+        //       per-connection values are needed.
+        let tls = $req.extensions.get::<Arc<TlsInfo>>();
+        let peer_cred = $client.peer_cred();
+        if tls.is_some() || peer_cred.is_some() {
+            let extensions = extensions.copy()?;
+            if let Some(cred) = peer_cred {
+                let peer_cred = PyDict::new($py);
+                peer_cred.set_item("pid", cred.pid)?;
+                peer_cred.set_item("uid", cred.uid)?;
+                peer_cred.set_item("gid", cred.gid)?;
+                extensions.set_item("unix.peer_cred", peer_cred)?;
+            }
+            if let Some(tls) = tls {
+                extensions.set_item("tls", build_tls_extension($py, tls)?)?;
+            }
+            scope_set!($py, $scope, "extensions", extensions);
+        } else {
+            scope_set!($py, $scope, "extensions", extensions);
+        }
         scope_set!($py, $scope, "type", $proto);
         scope_set!(
             $py,
@@ -87,6 +104,18 @@ macro_rules! build_scope_common {
     };
 }
 
+// See https://asgi.readthedocs.io/en/latest/specs/tls.html
+fn build_tls_extension<'p>(py: Python<'p>, tls: &TlsInfo) -> PyResult<Bound<'p, PyDict>> {
+    let rv = PyDict::new(py);
+    rv.set_item("server_cert", py.None())?;
+    rv.set_item("client_cert_chain", tls.client_cert_chain())?;
+    rv.set_item("client_cert_name", tls.client_cert_name())?;
+    rv.set_item("client_cert_error", py.None())?;
+    rv.set_item("tls_version", tls.version.map(|(code, _)| code))?;
+    rv.set_item("cipher_suite", tls.cipher.as_ref().map(|(code, _)| code))?;
+    Ok(rv)
+}
+
 #[inline]
 pub(super) fn build_scope_http(
     py: Python,
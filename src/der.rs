@@ -0,0 +1,327 @@
+use anyhow::{Result, anyhow};
+use std::{
+    fmt::Write,
+    time::{Duration, SystemTime},
+};
+
+const TAG_BOOLEAN: u8 = 0x01;
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+pub(crate) const TAG_ENUMERATED: u8 = 0x0a;
+pub(crate) const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+pub(crate) const TAG_EXPLICIT_0: u8 = 0xa0;
+const TAG_EXPLICIT_3: u8 = 0xa3;
+
+pub(crate) const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+pub(crate) const OID_ORGANIZATIONAL_UNIT: &[u8] = &[0x55, 0x04, 0x0b];
+const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+
+// Splits the next DER element from the input, returning its tag, contents and the remaining input.
+// Only single byte tags and minimally encoded lengths (up to 4 bytes) are accepted, as DER requires
+// and X.509 needs; lengths past the end of the input are rejected.
+pub(crate) fn read_tlv(input: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let invalid = || anyhow!("invalid DER encoding");
+    let (&tag, rest) = input.split_first().ok_or_else(invalid)?;
+    if tag & 0x1f == 0x1f {
+        return Err(invalid());
+    }
+    let (&len, mut rest) = rest.split_first().ok_or_else(invalid)?;
+    let len = if len & 0x80 == 0 {
+        usize::from(len)
+    } else {
+        let size = usize::from(len & 0x7f);
+        if size == 0 || size > 4 || rest.len() < size || rest[0] == 0 {
+            return Err(invalid());
+        }
+        let (bytes, tail) = rest.split_at(size);
+        rest = tail;
+        let len = bytes.iter().fold(0, |acc, byte| (acc << 8) | usize::from(*byte));
+        if len < 0x80 {
+            return Err(invalid());
+        }
+        len
+    };
+    if rest.len() < len {
+        return Err(invalid());
+    }
+    let (contents, rest) = rest.split_at(len);
+    Ok((tag, contents, rest))
+}
+
+pub(crate) fn expect_tlv(input: &[u8], tag: u8) -> Result<(&[u8], &[u8])> {
+    let (found, contents, rest) = read_tlv(input)?;
+    if found != tag {
+        return Err(anyhow!("unexpected DER tag {found:#04x}"));
+    }
+    Ok((contents, rest))
+}
+
+fn parse_digits(value: &str, range: std::ops::Range<usize>) -> Result<u64> {
+    value
+        .get(range)
+        .and_then(|v| v.parse::<u64>().ok())
+        .ok_or_else(|| anyhow!("invalid DER time"))
+}
+
+// Parses `UTCTime` (`YYMMDDHHMMSSZ`) and `GeneralizedTime` (`YYYYMMDDHHMMSS[.fff]Z`) values,
+// ignoring fractional seconds.
+pub(crate) fn parse_time(tag: u8, value: &[u8]) -> Result<SystemTime> {
+    let invalid = || anyhow!("invalid DER time");
+    let value = std::str::from_utf8(value).map_err(|_| invalid())?;
+    if !value.ends_with('Z') {
+        return Err(invalid());
+    }
+    let (year, value) = match tag {
+        // RFC 5280: two digits years from 50 to 99 belong to the 20th century
+        TAG_UTC_TIME if value.len() == 13 => {
+            let year = parse_digits(value, 0..2)?;
+            (if year >= 50 { 1900 + year } else { 2000 + year }, &value[2..])
+        }
+        TAG_GENERALIZED_TIME if value.len() >= 15 => (parse_digits(value, 0..4)?, &value[4..]),
+        _ => return Err(invalid()),
+    };
+    let (month, day) = (parse_digits(value, 0..2)?, parse_digits(value, 2..4)?);
+    if year < 1970 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    let secs = parse_digits(value, 4..6)? * 3600 + parse_digits(value, 6..8)? * 60 + parse_digits(value, 8..10)?;
+
+    // days since the epoch, see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * month + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86400 + secs))
+}
+
+// Skips the certificate envelope and the optional version, returning the serial number and,
+// skipping the signature algorithm, the remaining `TBSCertificate` fields (starting from `issuer`).
+fn cert_tbs_serial(cert: &[u8]) -> Result<(&[u8], &[u8])> {
+    let (cert, _) = expect_tlv(cert, TAG_SEQUENCE)?;
+    let (tbs, _) = expect_tlv(cert, TAG_SEQUENCE)?;
+    let (tag, _, rest) = read_tlv(tbs)?;
+    let (serial, rest) = expect_tlv(if tag == TAG_EXPLICIT_0 { rest } else { tbs }, TAG_INTEGER)?;
+    let (_, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+    Ok((serial, rest))
+}
+
+// Same as `cert_tbs_serial`, without the serial number.
+fn cert_tbs_issuer(cert: &[u8]) -> Result<&[u8]> {
+    Ok(cert_tbs_serial(cert)?.1)
+}
+
+// Same as `cert_tbs_issuer`, also skipping the issuer (so starting from `validity`).
+fn cert_tbs_validity(cert: &[u8]) -> Result<&[u8]> {
+    let (_, rest) = expect_tlv(cert_tbs_issuer(cert)?, TAG_SEQUENCE)?;
+    Ok(rest)
+}
+
+// Extracts the raw (DER encoded) issuer and subject names from a DER encoded X.509 certificate.
+pub(crate) fn cert_names(cert: &[u8]) -> Result<(&[u8], &[u8])> {
+    let (issuer, rest) = expect_tlv(cert_tbs_issuer(cert)?, TAG_SEQUENCE)?;
+    let (_, rest) = expect_tlv(rest, TAG_SEQUENCE)?;
+    let (subject, _) = expect_tlv(rest, TAG_SEQUENCE)?;
+    Ok((issuer, subject))
+}
+
+// Extracts the serial number and the whole DER encoding of the issuer name from a DER encoded X.509 certificate,
+// as used by OCSP certificate IDs.
+pub(crate) fn cert_issuer_id(cert: &[u8]) -> Result<(&[u8], &[u8])> {
+    let (serial, rest) = cert_tbs_serial(cert)?;
+    let (_, _, tail) = read_tlv(rest)?;
+    Ok((serial, &rest[..rest.len() - tail.len()]))
+}
+
+// Extracts the subject public key bits (without the unused bits count) from a DER encoded X.509 certificate.
+pub(crate) fn cert_public_key(cert: &[u8]) -> Result<&[u8]> {
+    // skip issuer, validity and subject
+    let mut rest = cert_tbs_issuer(cert)?;
+    for _ in 0..3 {
+        rest = expect_tlv(rest, TAG_SEQUENCE)?.1;
+    }
+    let (key_info, _) = expect_tlv(rest, TAG_SEQUENCE)?;
+    let (_, key_info) = expect_tlv(key_info, TAG_SEQUENCE)?;
+    let (key, _) = expect_tlv(key_info, TAG_BIT_STRING)?;
+    match key.split_first() {
+        Some((0, bits)) => Ok(bits),
+        _ => Err(anyhow!("invalid DER encoding")),
+    }
+}
+
+// Extracts the `notBefore` and `notAfter` values from a DER encoded X.509 certificate.
+pub(crate) fn cert_validity(cert: &[u8]) -> Result<(SystemTime, SystemTime)> {
+    let (validity, _) = expect_tlv(cert_tbs_validity(cert)?, TAG_SEQUENCE)?;
+    let (tag, not_before, rest) = read_tlv(validity)?;
+    let not_before = parse_time(tag, not_before)?;
+    let (tag, not_after, _) = read_tlv(rest)?;
+    let not_after = parse_time(tag, not_after)?;
+    Ok((not_before, not_after))
+}
+
+fn oid_to_string(oid: &[u8]) -> String {
+    let mut arcs: Vec<u64> = Vec::new();
+    let mut acc: u64 = 0;
+    for byte in oid {
+        acc = (acc << 7) | u64::from(byte & 0x7f);
+        if byte & 0x80 == 0 {
+            if arcs.is_empty() {
+                let first = (acc / 40).min(2);
+                arcs.push(first);
+                arcs.push(acc - first * 40);
+            } else {
+                arcs.push(acc);
+            }
+            acc = 0;
+        }
+    }
+    arcs.iter().map(u64::to_string).collect::<Vec<_>>().join(".")
+}
+
+fn attribute_type(oid: &[u8]) -> String {
+    match oid {
+        [0x55, 0x04, 0x03] => "CN".into(),
+        [0x55, 0x04, 0x06] => "C".into(),
+        [0x55, 0x04, 0x07] => "L".into(),
+        [0x55, 0x04, 0x08] => "ST".into(),
+        [0x55, 0x04, 0x09] => "STREET".into(),
+        [0x55, 0x04, 0x0a] => "O".into(),
+        [0x55, 0x04, 0x0b] => "OU".into(),
+        [0x09, 0x92, 0x26, 0x89, 0x93, 0xf2, 0x2c, 0x64, 0x01, 0x01] => "UID".into(),
+        [0x09, 0x92, 0x26, 0x89, 0x93, 0xf2, 0x2c, 0x64, 0x01, 0x19] => "DC".into(),
+        _ => oid_to_string(oid),
+    }
+}
+
+// Formats an attribute value as per RFC 4514: string values get escaped, other types are
+// rendered as the hex encoding of their DER representation.
+fn attribute_value(value: &[u8]) -> Result<String> {
+    let (tag, contents, rest) = read_tlv(value)?;
+    // UTF8String, PrintableString, TeletexString, IA5String
+    if ![0x0c, 0x13, 0x14, 0x16].contains(&tag) {
+        let encoded = &value[..value.len() - rest.len()];
+        return Ok(encoded.iter().fold(String::from("#"), |mut acc, byte| {
+            _ = write!(acc, "{byte:02x}");
+            acc
+        }));
+    }
+    let contents = String::from_utf8_lossy(contents);
+    let last = contents.chars().count().saturating_sub(1);
+    let mut rv = String::with_capacity(contents.len());
+    for (idx, ch) in contents.chars().enumerate() {
+        match ch {
+            ',' | '+' | '"' | '\\' | '<' | '>' | ';' => rv.push('\\'),
+            '#' | ' ' if idx == 0 => rv.push('\\'),
+            ' ' if idx == last => rv.push('\\'),
+            _ => {}
+        }
+        rv.push(ch);
+    }
+    Ok(rv)
+}
+
+// Formats a raw X.509 name as an RFC 4514 distinguished name.
+pub(crate) fn format_name(mut name: &[u8]) -> Result<String> {
+    let mut rdns = Vec::new();
+    while !name.is_empty() {
+        let (mut rdn, rest) = expect_tlv(name, TAG_SET)?;
+        name = rest;
+        let mut attributes = Vec::new();
+        while !rdn.is_empty() {
+            let (attribute, rest) = expect_tlv(rdn, TAG_SEQUENCE)?;
+            rdn = rest;
+            let (oid, value) = expect_tlv(attribute, TAG_OID)?;
+            attributes.push(format!("{}={}", attribute_type(oid), attribute_value(value)?));
+        }
+        rdns.push(attributes.join("+"));
+    }
+    // RFC 4514 lists the RDNs starting from the last one of the sequence
+    rdns.reverse();
+    Ok(rdns.join(","))
+}
+
+// Extracts the subject of a DER encoded X.509 certificate, as an RFC 4514 distinguished name.
+pub(crate) fn cert_subject(cert: &[u8]) -> Result<String> {
+    format_name(cert_names(cert)?.1)
+}
+
+// Collects the (unescaped) string values of the given attribute type from a raw X.509 name.
+pub(crate) fn name_attributes(mut name: &[u8], attribute_oid: &[u8]) -> Result<Vec<String>> {
+    let mut rv = Vec::new();
+    while !name.is_empty() {
+        let (mut rdn, rest) = expect_tlv(name, TAG_SET)?;
+        name = rest;
+        while !rdn.is_empty() {
+            let (attribute, rest) = expect_tlv(rdn, TAG_SEQUENCE)?;
+            rdn = rest;
+            let (oid, value) = expect_tlv(attribute, TAG_OID)?;
+            if oid == attribute_oid {
+                let (_, contents, _) = read_tlv(value)?;
+                rv.push(String::from_utf8_lossy(contents).into_owned());
+            }
+        }
+    }
+    Ok(rv)
+}
+
+// Extracts the DNS names, emails, URIs and IP addresses listed in the subject alternative name
+// extension of a DER encoded X.509 certificate.
+pub(crate) fn cert_alt_names(cert: &[u8]) -> Result<Vec<String>> {
+    // skip issuer, validity, subject and the public key info
+    let mut rest = cert_tbs_issuer(cert)?;
+    for _ in 0..4 {
+        rest = expect_tlv(rest, TAG_SEQUENCE)?.1;
+    }
+    // skip the optional unique identifiers
+    let mut extensions: &[u8] = &[];
+    while !rest.is_empty() {
+        let (tag, contents, tail) = read_tlv(rest)?;
+        rest = tail;
+        if tag == TAG_EXPLICIT_3 {
+            extensions = expect_tlv(contents, TAG_SEQUENCE)?.0;
+        }
+    }
+
+    let mut rv = Vec::new();
+    while !extensions.is_empty() {
+        let (extension, tail) = expect_tlv(extensions, TAG_SEQUENCE)?;
+        extensions = tail;
+        let (oid, extension) = expect_tlv(extension, TAG_OID)?;
+        if oid != OID_SUBJECT_ALT_NAME {
+            continue;
+        }
+        // skip the optional `critical` flag
+        let (tag, mut value, rest) = read_tlv(extension)?;
+        if tag == TAG_BOOLEAN {
+            value = expect_tlv(rest, TAG_OCTET_STRING)?.0;
+        }
+        let (mut names, _) = expect_tlv(value, TAG_SEQUENCE)?;
+        while !names.is_empty() {
+            let (tag, contents, tail) = read_tlv(names)?;
+            names = tail;
+            match tag {
+                // rfc822Name, dNSName, uniformResourceIdentifier
+                0x81 | 0x82 | 0x86 => rv.push(String::from_utf8_lossy(contents).into_owned()),
+                // iPAddress
+                0x87 => match contents.len() {
+                    4 => rv.push(std::net::Ipv4Addr::from(<[u8; 4]>::try_from(contents)?).to_string()),
+                    16 => rv.push(std::net::Ipv6Addr::from(<[u8; 16]>::try_from(contents)?).to_string()),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+    Ok(rv)
+}
@@ -2,14 +2,17 @@ use http_body_util::BodyExt;
 use hyper::{
     Response,
     body::Bytes,
-    header::{HeaderValue, SERVER as HK_SERVER},
+    header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, HeaderValue, SERVER as HK_SERVER},
 };
+use std::collections::HashMap;
 
 pub(crate) type HTTPRequest = hyper::Request<hyper::body::Incoming>;
 pub(crate) type HTTPResponseBody = http_body_util::combinators::BoxBody<Bytes, anyhow::Error>;
 pub(crate) type HTTPResponse = hyper::Response<HTTPResponseBody>;
 
 pub(crate) const HV_SERVER: HeaderValue = HeaderValue::from_static("granian");
+const HV_CT_HTML: HeaderValue = HeaderValue::from_static("text/html; charset=utf-8");
+const HV_CT_JSON: HeaderValue = HeaderValue::from_static("application/json");
 
 #[derive(Clone)]
 pub(crate) enum HTTPProto {
@@ -18,7 +21,7 @@ pub(crate) enum HTTPProto {
 }
 
 impl HTTPProto {
-    pub(crate) fn as_str(&self) -> &str {
+    pub(crate) fn as_str(&self) -> &'static str {
         match self {
             Self::Plain => "http",
             Self::Tls => "https",
@@ -26,33 +29,197 @@ impl HTTPProto {
     }
 }
 
-pub(crate) fn response_404() -> HTTPResponse {
-    let mut builder = Response::builder().status(404);
+// Marks responses generated by Granian itself (not by the application),
+// so that they can be replaced with custom error pages.
+#[derive(Clone, Copy)]
+pub(crate) struct ServerGenerated;
+
+#[inline]
+fn response_server(status: u16, body: HTTPResponseBody) -> HTTPResponse {
+    let mut builder = Response::builder().status(status).extension(ServerGenerated);
     let headers = builder.headers_mut().unwrap();
     headers.insert(HK_SERVER, HV_SERVER);
-    builder
-        .body(
-            http_body_util::Full::new("Not found".into())
-                .map_err(|e| match e {})
-                .boxed(),
-        )
-        .unwrap()
+    builder.body(body).unwrap()
+}
+
+#[inline]
+fn response_server_text(status: u16, text: String) -> HTTPResponse {
+    response_server(
+        status,
+        http_body_util::Full::new(text.into()).map_err(|e| match e {}).boxed(),
+    )
+}
+
+pub(crate) fn response_400(text: String) -> HTTPResponse {
+    response_server_text(400, text)
+}
+
+pub(crate) fn response_403() -> HTTPResponse {
+    response_server_text(403, "Forbidden".into())
+}
+
+pub(crate) fn response_404() -> HTTPResponse {
+    response_server_text(404, "Not found".into())
 }
 
 pub(crate) fn response_500() -> HTTPResponse {
-    let mut builder = Response::builder().status(500);
-    let headers = builder.headers_mut().unwrap();
-    headers.insert(HK_SERVER, HV_SERVER);
-    builder
-        .body(
-            http_body_util::Full::new("Internal server error".into())
-                .map_err(|e| match e {})
-                .boxed(),
-        )
-        .unwrap()
+    response_server_text(500, "Internal server error".into())
+}
+
+pub(crate) fn response_502() -> HTTPResponse {
+    response_server_text(502, "Bad gateway".into())
+}
+
+pub(crate) fn response_504() -> HTTPResponse {
+    response_server_text(504, "Gateway timeout".into())
+}
+
+pub(crate) fn response_empty(status: u16) -> HTTPResponse {
+    response_server(status, empty_body())
 }
 
 #[inline(always)]
 pub(crate) fn empty_body() -> HTTPResponseBody {
     http_body_util::Empty::<Bytes>::new().map_err(|e| match e {}).boxed()
 }
+
+#[derive(Clone, Copy)]
+enum ErrorPageKind {
+    Html,
+    Json,
+}
+
+pub(crate) struct ErrorPages {
+    html: HashMap<u16, Bytes>,
+    json: HashMap<u16, Bytes>,
+}
+
+impl ErrorPages {
+    // Loads `<status>.html` and `<status>.json` templates from the given directory.
+    pub fn load(path: &str) -> Self {
+        let mut html = HashMap::new();
+        let mut json = HashMap::new();
+
+        let entries = match std::fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::error!("Unable to read error pages directory {path}: {err}");
+                return Self { html, json };
+            }
+        };
+
+        for entry in entries.flatten() {
+            let fpath = entry.path();
+            let (Some(stem), Some(ext)) = (
+                fpath.file_stem().and_then(|v| v.to_str()),
+                fpath.extension().and_then(|v| v.to_str()),
+            ) else {
+                continue;
+            };
+            let Ok(status) = stem.parse::<u16>() else {
+                continue;
+            };
+            if !(400..600).contains(&status) {
+                continue;
+            }
+            let target = match ext {
+                "html" => &mut html,
+                "json" => &mut json,
+                _ => continue,
+            };
+            match std::fs::read(&fpath) {
+                Ok(content) => {
+                    target.insert(status, Bytes::from(content));
+                }
+                Err(err) => log::error!("Unable to load error page {}: {err}", fpath.display()),
+            }
+        }
+
+        Self { html, json }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.html.is_empty() && self.json.is_empty()
+    }
+
+    fn negotiate(&self, status: u16, accept: Option<&HeaderValue>) -> Option<(ErrorPageKind, Bytes)> {
+        let html = self.html.get(&status);
+        let json = self.json.get(&status);
+        let (q_html, q_json) = match accept.and_then(|v| v.to_str().ok()) {
+            Some(accept) => accept_quality(accept),
+            None => (1.0, 1.0),
+        };
+
+        let mut candidates = [(ErrorPageKind::Html, q_html, html), (ErrorPageKind::Json, q_json, json)];
+        // stable sort: on equal quality HTML wins
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates
+            .into_iter()
+            .find_map(|(kind, q, page)| match (q > 0.0, page) {
+                (true, Some(page)) => Some((kind, page.clone())),
+                _ => None,
+            })
+    }
+
+    pub fn apply(&self, accept: Option<&HeaderValue>, res: HTTPResponse) -> HTTPResponse {
+        if res.extensions().get::<ServerGenerated>().is_none() {
+            return res;
+        }
+        let Some((kind, page)) = self.negotiate(res.status().as_u16(), accept) else {
+            return res;
+        };
+
+        let (mut parts, _) = res.into_parts();
+        parts.headers.insert(
+            CONTENT_TYPE,
+            match kind {
+                ErrorPageKind::Html => HV_CT_HTML,
+                ErrorPageKind::Json => HV_CT_JSON,
+            },
+        );
+        parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(page.len()));
+        Response::from_parts(parts, http_body_util::Full::new(page).map_err(|e| match e {}).boxed())
+    }
+}
+
+#[inline]
+pub(crate) fn header_accept(headers: &HeaderMap) -> Option<HeaderValue> {
+    headers.get(ACCEPT).cloned()
+}
+
+// Returns the quality values for HTML and JSON contents given an `Accept` header,
+// using the most specific matching media range for each.
+fn accept_quality(accept: &str) -> (f32, f32) {
+    let mut html = (0u8, 0.0f32);
+    let mut json = (0u8, 0.0f32);
+
+    for range in accept.split(',') {
+        let mut params = range.split(';');
+        let media = params.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let q = params
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|v| v.parse::<f32>().ok())
+            .unwrap_or(1.0);
+
+        let specificity_html = match &media[..] {
+            "text/html" | "application/xhtml+xml" => 3,
+            "text/*" => 2,
+            "*/*" => 1,
+            _ => 0,
+        };
+        let specificity_json = match &media[..] {
+            "application/json" | "application/problem+json" => 3,
+            "application/*" => 2,
+            "*/*" => 1,
+            _ => 0,
+        };
+        if specificity_html > html.0 {
+            html = (specificity_html, q);
+        }
+        if specificity_json > json.0 {
+            json = (specificity_json, q);
+        }
+    }
+
+    (html.1, json.1)
+}
@@ -35,7 +35,7 @@ pub(crate) struct IPCReceiverHandle {
 
 #[cfg(not(Py_GIL_DISABLED))]
 impl IPCReceiverHandle {
-    fn to_receiver(&self) -> Arc<Mutex<unnamed_pipe::tokio::Recver>> {
+    pub(crate) fn to_receiver(&self) -> Arc<Mutex<unnamed_pipe::tokio::Recver>> {
         let fd = self.fd.lock().unwrap().take().unwrap();
         let receiver = unnamed_pipe::tokio::Recver::try_from(fd).unwrap();
         Arc::new(Mutex::new(receiver))
@@ -180,6 +180,7 @@ pub(crate) struct IPCSenderHandle {}
 pub(crate) enum Message {
     // NOTE: potentially use IPC for more purposes
     Metrics(crate::metrics::MetricsData),
+    TicketKeys(Vec<Vec<u8>>),
 }
 
 #[cfg(not(Py_GIL_DISABLED))]
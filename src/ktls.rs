@@ -0,0 +1,355 @@
+use std::{
+    io::{self, Read},
+    os::fd::{AsRawFd, RawFd},
+    pin::Pin,
+    sync::{Arc, OnceLock},
+    task::{Context, Poll, ready},
+};
+use tls_listener::rustls::{
+    rustls::{CipherSuite, ConnectionTrafficSecrets, ProtocolVersion},
+    server::TlsStream,
+};
+use tokio::io::{AsyncRead, AsyncWrite, Interest, ReadBuf};
+
+use crate::tls::{CorkStream, TlsInfo, TlsTcpStream};
+
+// see linux/tls.h
+const SOL_TLS: libc::c_int = 282;
+const TLS_TX: libc::c_int = 1;
+const TLS_RX: libc::c_int = 2;
+const TLS_SET_RECORD_TYPE: libc::c_int = 1;
+const TLS_GET_RECORD_TYPE: libc::c_int = 2;
+const TLS_1_3_VERSION: u16 = 0x0304;
+const TLS_CIPHER_AES_GCM_128: u16 = 51;
+const TLS_CIPHER_AES_GCM_256: u16 = 52;
+const TLS_CIPHER_CHACHA20_POLY1305: u16 = 54;
+
+const RECORD_ALERT: u8 = 21;
+const RECORD_HANDSHAKE: u8 = 22;
+const RECORD_APPLICATION_DATA: u8 = 23;
+const ALERT_CLOSE_NOTIFY: [u8; 2] = [1, 0];
+
+#[repr(C)]
+struct CryptoInfo<const IV: usize, const KEY: usize, const SALT: usize> {
+    version: u16,
+    cipher_type: u16,
+    iv: [u8; IV],
+    key: [u8; KEY],
+    salt: [u8; SALT],
+    rec_seq: [u8; 8],
+}
+
+impl<const IV: usize, const KEY: usize, const SALT: usize> CryptoInfo<IV, KEY, SALT> {
+    // The kernel wants the 12 bytes TLS 1.3 IV split into salt and explicit IV.
+    fn new(cipher_type: u16, seq: u64, key: &[u8], iv: &[u8]) -> io::Result<Self> {
+        let invalid = |_| io::Error::new(io::ErrorKind::InvalidInput, "invalid TLS traffic secrets");
+        Ok(Self {
+            version: TLS_1_3_VERSION,
+            cipher_type,
+            iv: iv[SALT..].try_into().map_err(invalid)?,
+            key: key.try_into().map_err(invalid)?,
+            salt: iv[..SALT].try_into().map_err(invalid)?,
+            rec_seq: seq.to_be_bytes(),
+        })
+    }
+
+    fn apply(&self, fd: RawFd, direction: libc::c_int) -> io::Result<()> {
+        setsockopt(
+            fd,
+            SOL_TLS,
+            direction,
+            std::ptr::from_ref(self).cast(),
+            std::mem::size_of::<Self>(),
+        )
+    }
+}
+
+fn setsockopt(
+    fd: RawFd,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: *const libc::c_void,
+    len: usize,
+) -> io::Result<()> {
+    let ret = unsafe { libc::setsockopt(fd, level, name, value, len as libc::socklen_t) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn setup_ulp(fd: RawFd) -> io::Result<()> {
+    setsockopt(fd, libc::SOL_TCP, libc::TCP_ULP, b"tls".as_ptr().cast(), 3)
+}
+
+fn setup_crypto(
+    fd: RawFd,
+    direction: libc::c_int,
+    suite: CipherSuite,
+    seq: u64,
+    key: &[u8],
+    iv: &[u8],
+) -> io::Result<()> {
+    match suite {
+        CipherSuite::TLS13_AES_128_GCM_SHA256 => {
+            CryptoInfo::<8, 16, 4>::new(TLS_CIPHER_AES_GCM_128, seq, key, iv)?.apply(fd, direction)
+        }
+        CipherSuite::TLS13_AES_256_GCM_SHA384 => {
+            CryptoInfo::<8, 32, 4>::new(TLS_CIPHER_AES_GCM_256, seq, key, iv)?.apply(fd, direction)
+        }
+        CipherSuite::TLS13_CHACHA20_POLY1305_SHA256 => {
+            CryptoInfo::<12, 32, 0>::new(TLS_CIPHER_CHACHA20_POLY1305, seq, key, iv)?.apply(fd, direction)
+        }
+        _ => Err(io::Error::new(io::ErrorKind::Unsupported, "unsupported cipher suite")),
+    }
+}
+
+fn setup_secrets(
+    fd: RawFd,
+    direction: libc::c_int,
+    (seq, secrets): &(u64, ConnectionTrafficSecrets),
+) -> io::Result<()> {
+    let (suite, key, iv) = match secrets {
+        ConnectionTrafficSecrets::Aes128Gcm { key, iv } => (CipherSuite::TLS13_AES_128_GCM_SHA256, key, iv),
+        ConnectionTrafficSecrets::Aes256Gcm { key, iv } => (CipherSuite::TLS13_AES_256_GCM_SHA384, key, iv),
+        ConnectionTrafficSecrets::Chacha20Poly1305 { key, iv } => {
+            (CipherSuite::TLS13_CHACHA20_POLY1305_SHA256, key, iv)
+        }
+        _ => return Err(io::Error::new(io::ErrorKind::Unsupported, "unsupported cipher suite")),
+    };
+    setup_crypto(fd, direction, suite, *seq, key.as_ref(), iv.as_ref())
+}
+
+// Probes the kernel support for every TLS 1.3 cipher suite on a loopback connection,
+// so we never hand a connection to the kernel just to find out it can't deal with it.
+fn supported_suites() -> &'static [CipherSuite] {
+    static SUPPORTED: OnceLock<Vec<CipherSuite>> = OnceLock::new();
+
+    SUPPORTED.get_or_init(|| {
+        let probe = |suite: CipherSuite| -> io::Result<()> {
+            let key_len = match suite {
+                CipherSuite::TLS13_AES_128_GCM_SHA256 => 16,
+                _ => 32,
+            };
+            let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+            let stream = std::net::TcpStream::connect(listener.local_addr()?)?;
+            let fd = stream.as_raw_fd();
+            setup_ulp(fd)?;
+            setup_crypto(fd, TLS_TX, suite, 0, &[0; 32][..key_len], &[0; 12])?;
+            setup_crypto(fd, TLS_RX, suite, 0, &[0; 32][..key_len], &[0; 12])
+        };
+        let suites: Vec<CipherSuite> = [
+            CipherSuite::TLS13_AES_128_GCM_SHA256,
+            CipherSuite::TLS13_AES_256_GCM_SHA384,
+            CipherSuite::TLS13_CHACHA20_POLY1305_SHA256,
+        ]
+        .into_iter()
+        .filter(|suite| probe(*suite).is_ok())
+        .collect();
+        if suites.is_empty() {
+            log::warn!("kTLS is not available (is the `tls` kernel module loaded?), falling back to userspace TLS");
+        }
+        suites
+    })
+}
+
+pub(crate) fn supported() -> bool {
+    !supported_suites().is_empty()
+}
+
+// Hands the symmetric crypto of an established TLS 1.3 connection to the kernel.
+// Connections which can't be offloaded (TLS 1.2, unsupported suites, pending writes) stay on rustls.
+pub(crate) fn offload(mut stream: TlsStream<CorkStream<tokio::net::TcpStream>>) -> io::Result<TlsTcpStream> {
+    let conn = stream.get_ref().1;
+    let offloadable = conn.protocol_version() == Some(ProtocolVersion::TLSv1_3)
+        && conn
+            .negotiated_cipher_suite()
+            .is_some_and(|suite| supported_suites().contains(&suite.suite()))
+        && !conn.wants_write();
+    if !offloadable {
+        return Ok(TlsTcpStream::Rustls(stream));
+    }
+
+    let sni = conn.server_name().map(Into::into);
+    let info = Arc::new(TlsInfo::new(conn, true));
+    // plaintext already decrypted by rustls, if any
+    let mut pending = Vec::new();
+    let mut buf = [0u8; 4096];
+    while let Ok(read @ 1..) = stream.get_mut().1.reader().read(&mut buf) {
+        pending.extend_from_slice(&buf[..read]);
+    }
+
+    let (io, conn) = stream.into_inner();
+    let io = io.into_inner();
+    let secrets = conn.dangerous_extract_secrets().map_err(io::Error::other)?;
+    let fd = io.as_raw_fd();
+    setup_ulp(fd)?;
+    setup_secrets(fd, TLS_TX, &secrets.tx)?;
+    setup_secrets(fd, TLS_RX, &secrets.rx)?;
+
+    Ok(TlsTcpStream::Kernel(KtlsStream {
+        io,
+        pending,
+        pending_pos: 0,
+        closed: false,
+        shutdown: false,
+        sni,
+        info,
+    }))
+}
+
+// Reads a single record, returning its size and content type.
+fn recv_record(fd: RawFd, buf: &mut [u8]) -> io::Result<(usize, u8)> {
+    // control messages buffer, aligned for `cmsghdr`
+    let mut cmsg = [0u64; 8];
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &raw mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg.as_mut_ptr().cast();
+    msg.msg_controllen = std::mem::size_of_val(&cmsg) as _;
+
+    let read = unsafe { libc::recvmsg(fd, &raw mut msg, 0) };
+    if read < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut record_type = RECORD_APPLICATION_DATA;
+    unsafe {
+        let hdr = libc::CMSG_FIRSTHDR(&raw const msg);
+        if !hdr.is_null() && (*hdr).cmsg_level == SOL_TLS && (*hdr).cmsg_type == TLS_GET_RECORD_TYPE {
+            record_type = *libc::CMSG_DATA(hdr);
+        }
+    }
+    Ok((read as usize, record_type))
+}
+
+// Sends a TLS alert record through the kernel.
+fn send_alert(fd: RawFd, alert: &[u8; 2]) -> io::Result<()> {
+    // control messages buffer, aligned for `cmsghdr`
+    let mut cmsg = [0u64; 8];
+    let mut iov = libc::iovec {
+        iov_base: alert.as_ptr().cast_mut().cast(),
+        iov_len: alert.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &raw mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg.as_mut_ptr().cast();
+    msg.msg_controllen = unsafe { libc::CMSG_SPACE(1) } as _;
+    unsafe {
+        let hdr = libc::CMSG_FIRSTHDR(&raw const msg);
+        (*hdr).cmsg_level = SOL_TLS;
+        (*hdr).cmsg_type = TLS_SET_RECORD_TYPE;
+        (*hdr).cmsg_len = libc::CMSG_LEN(1) as _;
+        *libc::CMSG_DATA(hdr) = RECORD_ALERT;
+    }
+
+    if unsafe { libc::sendmsg(fd, &raw const msg, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+pub(crate) struct KtlsStream {
+    io: tokio::net::TcpStream,
+    pending: Vec<u8>,
+    pending_pos: usize,
+    closed: bool,
+    shutdown: bool,
+    pub sni: Option<Arc<str>>,
+    pub info: Arc<TlsInfo>,
+}
+
+impl AsyncRead for KtlsStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.pending_pos < this.pending.len() {
+            let len = buf.remaining().min(this.pending.len() - this.pending_pos);
+            buf.put_slice(&this.pending[this.pending_pos..this.pending_pos + len]);
+            this.pending_pos += len;
+            return Poll::Ready(Ok(()));
+        }
+        if this.closed || buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        let fd = this.io.as_raw_fd();
+        loop {
+            ready!(this.io.poll_read_ready(cx))?;
+            match this
+                .io
+                .try_io(Interest::READABLE, || recv_record(fd, buf.initialize_unfilled()))
+            {
+                Ok((read, RECORD_APPLICATION_DATA)) => {
+                    buf.advance(read);
+                    return Poll::Ready(Ok(()));
+                }
+                Ok((read, RECORD_ALERT)) => {
+                    this.closed = true;
+                    let unfilled = buf.initialize_unfilled();
+                    if read == 2 && unfilled[..2] == ALERT_CLOSE_NOTIFY {
+                        return Poll::Ready(Ok(()));
+                    }
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::ConnectionAborted,
+                        "received TLS alert",
+                    )));
+                }
+                // the kernel doesn't handle TLS 1.3 key updates
+                Ok((_, RECORD_HANDSHAKE)) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "TLS post-handshake messages are not supported with kTLS",
+                    )));
+                }
+                Ok(_) => {
+                    return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, "unexpected TLS record")));
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+    }
+}
+
+impl AsyncWrite for KtlsStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let fd = this.io.as_raw_fd();
+        // best effort close notify, as rustls does
+        while !this.shutdown {
+            ready!(this.io.poll_write_ready(cx))?;
+            match this
+                .io
+                .try_io(Interest::WRITABLE, || send_alert(fd, &ALERT_CLOSE_NOTIFY))
+            {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                _ => this.shutdown = true,
+            }
+        }
+        Pin::new(&mut this.io).poll_shutdown(cx)
+    }
+}
@@ -9,20 +9,29 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 use pyo3::prelude::*;
 use std::sync::OnceLock;
 
+mod access;
+mod acme;
+mod apps;
 mod asgi;
 mod asyncio;
 mod blocking;
 mod callbacks;
 mod conversion;
+mod der;
 mod files;
 mod http;
 mod ipc;
+#[cfg(target_os = "linux")]
+mod ktls;
 mod metrics;
 mod net;
+mod ocsp;
+mod proxy;
 mod rsgi;
 mod runtime;
 mod serve;
 mod sys;
+mod tickets;
 mod tls;
 mod utils;
 mod workers;
@@ -48,11 +57,14 @@ fn _granian(py: Python, module: &Bound<PyModule>) -> PyResult<()> {
     module.add("__version__", get_granian_version())?;
     module.add("BUILD_GIL", BUILD_GIL)?;
     module.add_class::<callbacks::CallbackScheduler>()?;
+    acme::init_pymodule(module)?;
     asgi::init_pymodule(module)?;
     ipc::init_pymodule(module)?;
     metrics::init_pymodule(module)?;
     rsgi::init_pymodule(py, module)?;
     sys::init_pymodule(module)?;
+    tickets::init_pymodule(module)?;
+    tls::init_pymodule(module)?;
     net::init_pymodule(module)?;
     workers::init_pymodule(module)?;
     Ok(())
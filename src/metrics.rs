@@ -1,18 +1,42 @@
-use std::sync::{Arc, Mutex, atomic};
+use http_body_util::BodyExt;
+use std::{
+    collections::BTreeMap,
+    fs,
+    pin::Pin,
+    sync::{Arc, Mutex, atomic},
+    task::{Context, Poll},
+    time::SystemTime,
+};
 
 use pyo3::prelude::*;
 use serde::{Deserialize, Serialize};
 
 #[cfg(not(Py_GIL_DISABLED))]
 use crate::ipc;
-use crate::runtime;
+use tokio_tungstenite::tungstenite::Message as WSMessage;
+
+use crate::{
+    http::{HTTPResponse, HTTPResponseBody},
+    runtime,
+};
 
 pub(crate) type MetricsData = Vec<MetricValue>;
 
+const HISTOGRAM_PROTOCOLS: [&str; 3] = ["HTTP/1.0", "HTTP/1.1", "HTTP/2"];
+const CONNECTION_DURATION_BUCKETS: [f64; 10] = [0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 600.0, 1800.0];
+const CONNECTION_REQUESTS_BUCKETS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0];
+const REQUEST_METHODS: [&str; 10] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH", "OTHER",
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) enum MetricValue {
     Abs(usize),
     Int(isize),
+    // per bucket counts (the last one being `+Inf`) and sum of the observed values in microseconds
+    Hist(Vec<usize>, usize),
+    // non-zero counts by label value
+    Labeled(Vec<(String, usize)>),
 }
 
 impl std::fmt::Display for MetricValue {
@@ -20,15 +44,75 @@ impl std::fmt::Display for MetricValue {
         match self {
             Self::Abs(v) => v.fmt(f),
             Self::Int(v) => v.fmt(f),
+            Self::Hist(v, _) => v.iter().sum::<usize>().fmt(f),
+            Self::Labeled(v) => v.iter().map(|(_, count)| count).sum::<usize>().fmt(f),
         }
     }
 }
 
+pub(crate) struct Histogram {
+    bounds: Arc<[u64]>,
+    counts: Box<[atomic::AtomicUsize]>,
+    sum: atomic::AtomicUsize,
+}
+
+impl Histogram {
+    fn new(bounds: Arc<[u64]>) -> Self {
+        Self {
+            counts: (0..=bounds.len()).map(|_| atomic::AtomicUsize::new(0)).collect(),
+            bounds,
+            sum: 0.into(),
+        }
+    }
+
+    #[inline]
+    pub fn observe(&self, value: std::time::Duration) {
+        self.observe_value(value.as_micros() as u64);
+    }
+
+    #[inline]
+    fn observe_value(&self, value: u64) {
+        let idx = self.bounds.partition_point(|bound| *bound < value);
+        self.counts[idx].fetch_add(1, atomic::Ordering::Relaxed);
+        self.sum.fetch_add(value as usize, atomic::Ordering::Relaxed);
+    }
+
+    fn value(&self) -> MetricValue {
+        MetricValue::Hist(
+            self.counts.iter().map(|v| v.load(atomic::Ordering::Relaxed)).collect(),
+            self.sum.load(atomic::Ordering::Relaxed),
+        )
+    }
+}
+
+// Expiry of a certificate file, cached until the file changes.
+// PKCS#12 bundles can't be read without their password, so they keep the expiry validated on startup.
+struct TlsCertExpiry {
+    path: String,
+    pkcs12: bool,
+    mtime: Option<SystemTime>,
+    expiry: Option<u64>,
+}
+
+impl TlsCertExpiry {
+    fn get(&mut self) -> Option<u64> {
+        if !self.pkcs12 {
+            let mtime = fs::metadata(&self.path).and_then(|meta| meta.modified()).ok();
+            if mtime != self.mtime {
+                self.mtime = mtime;
+                self.expiry = crate::tls::cert_expiry(&self.path).ok();
+            }
+        }
+        self.expiry
+    }
+}
+
 struct MainMetrics {
     spawn: atomic::AtomicUsize,
     respawn_err: atomic::AtomicUsize,
     respawn_ttl: atomic::AtomicUsize,
     respawn_rss: atomic::AtomicUsize,
+    tls_certs: Mutex<Vec<TlsCertExpiry>>,
 }
 
 pub(crate) struct WorkerMetrics {
@@ -43,6 +127,24 @@ pub(crate) struct WorkerMetrics {
     pub blocking_idle_cumul: atomic::AtomicUsize,
     pub blocking_busy_cumul: atomic::AtomicUsize,
     pub py_wait_cumul: atomic::AtomicUsize,
+    pub req_duration: [Histogram; 3],
+    pub req_first_byte: [Histogram; 3],
+    pub req_queue: [Histogram; 3],
+    req_status: Box<[atomic::AtomicUsize]>,
+    req_method: [atomic::AtomicUsize; REQUEST_METHODS.len()],
+    ws_active: atomic::AtomicUsize,
+    ws_accepted: atomic::AtomicUsize,
+    ws_rejected: atomic::AtomicUsize,
+    ws_rx_msgs: [atomic::AtomicUsize; 2],
+    ws_rx_bytes: [atomic::AtomicUsize; 2],
+    ws_tx_msgs: [atomic::AtomicUsize; 2],
+    ws_tx_bytes: [atomic::AtomicUsize; 2],
+    ws_close_rx: Mutex<BTreeMap<u16, usize>>,
+    ws_close_tx: Mutex<BTreeMap<u16, usize>>,
+    bytes_rx: atomic::AtomicUsize,
+    bytes_tx: atomic::AtomicUsize,
+    conn_duration: Histogram,
+    conn_requests: Histogram,
 }
 
 impl MainMetrics {
@@ -52,12 +154,15 @@ impl MainMetrics {
             respawn_err: 0.into(),
             respawn_rss: 0.into(),
             respawn_ttl: 0.into(),
+            tls_certs: Mutex::new(Vec::new()),
         }
     }
 }
 
 impl WorkerMetrics {
-    pub fn new() -> Self {
+    pub fn new(buckets: &[f64]) -> Self {
+        let bounds: Arc<[u64]> = buckets.iter().map(|v| (v * 1_000_000.0) as u64).collect();
+        let histograms = || std::array::from_fn(|_| Histogram::new(bounds.clone()));
         Self {
             conn_active: 0.into(),
             conn_handled: 0.into(),
@@ -70,16 +175,345 @@ impl WorkerMetrics {
             blocking_idle_cumul: 0.into(),
             blocking_busy_cumul: 0.into(),
             py_wait_cumul: 0.into(),
+            req_duration: histograms(),
+            req_first_byte: histograms(),
+            req_queue: histograms(),
+            req_status: (100..600).map(|_| atomic::AtomicUsize::new(0)).collect(),
+            req_method: std::array::from_fn(|_| 0.into()),
+            ws_active: 0.into(),
+            ws_accepted: 0.into(),
+            ws_rejected: 0.into(),
+            ws_rx_msgs: [0.into(), 0.into()],
+            ws_rx_bytes: [0.into(), 0.into()],
+            ws_tx_msgs: [0.into(), 0.into()],
+            ws_tx_bytes: [0.into(), 0.into()],
+            ws_close_rx: Mutex::new(BTreeMap::new()),
+            ws_close_tx: Mutex::new(BTreeMap::new()),
+            bytes_rx: 0.into(),
+            bytes_tx: 0.into(),
+            conn_duration: Histogram::new(
+                CONNECTION_DURATION_BUCKETS
+                    .iter()
+                    .map(|v| (v * 1_000_000.0) as u64)
+                    .collect(),
+            ),
+            conn_requests: Histogram::new(CONNECTION_REQUESTS_BUCKETS.iter().map(|v| *v as u64).collect()),
+        }
+    }
+
+    #[inline]
+    fn incr_method(&self, method: &hyper::Method) {
+        let idx = match method.as_str() {
+            "GET" => 0,
+            "HEAD" => 1,
+            "POST" => 2,
+            "PUT" => 3,
+            "DELETE" => 4,
+            "CONNECT" => 5,
+            "OPTIONS" => 6,
+            "TRACE" => 7,
+            "PATCH" => 8,
+            _ => 9,
+        };
+        self.req_method[idx].fetch_add(1, atomic::Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn incr_status(&self, status: hyper::StatusCode) {
+        if let Some(counter) = self.req_status.get(status.as_u16() as usize - 100) {
+            counter.fetch_add(1, atomic::Ordering::Relaxed);
         }
     }
 }
 
 pub(crate) type ArcWorkerMetrics = Arc<WorkerMetrics>;
 
+// Tracks the method, status and timings of a request, from the moment it gets received by the worker.
+pub(crate) struct RequestTimer {
+    metrics: ArcWorkerMetrics,
+    proto: usize,
+    start: std::time::Instant,
+}
+
+impl RequestTimer {
+    pub fn new(metrics: ArcWorkerMetrics, req: &crate::http::HTTPRequest) -> Self {
+        metrics.incr_method(req.method());
+        let proto = match req.version() {
+            hyper::Version::HTTP_10 => 0,
+            hyper::Version::HTTP_2 => 2,
+            _ => 1,
+        };
+        Self {
+            metrics,
+            proto,
+            start: std::time::Instant::now(),
+        }
+    }
+
+    pub fn request_metrics(&self) -> RequestMetrics {
+        RequestMetrics {
+            metrics: self.metrics.clone(),
+            proto: self.proto,
+            start: self.start,
+        }
+    }
+
+    // Observes the time to the response head, and the total duration once the response body gets dropped.
+    pub async fn observe<F>(self, fut: F) -> Result<HTTPResponse, hyper::Error>
+    where
+        F: Future<Output = Result<HTTPResponse, hyper::Error>>,
+    {
+        let res = fut.await?;
+        self.metrics.req_first_byte[self.proto].observe(self.start.elapsed());
+        self.metrics.incr_status(res.status());
+        Ok(res.map(|body| {
+            TimedBody {
+                inner: body,
+                timer: self,
+            }
+            .boxed()
+        }))
+    }
+}
+
+// Travels within the request extensions, so that protocols can observe the time
+// spent before the application callable gets invoked, and track websockets.
+#[derive(Clone)]
+pub(crate) struct RequestMetrics {
+    metrics: ArcWorkerMetrics,
+    proto: usize,
+    start: std::time::Instant,
+}
+
+impl RequestMetrics {
+    #[inline]
+    pub fn observe_queue(self) {
+        self.metrics.req_queue[self.proto].observe(self.start.elapsed());
+    }
+}
+
+// Per connection handle to the websocket metrics, which does nothing when metrics are disabled.
+#[derive(Clone)]
+pub(crate) struct WebsocketMetrics {
+    metrics: Option<ArcWorkerMetrics>,
+    active: Arc<atomic::AtomicBool>,
+}
+
+impl WebsocketMetrics {
+    pub fn new(request: Option<&RequestMetrics>) -> Self {
+        Self {
+            metrics: request.map(|v| v.metrics.clone()),
+            active: Arc::new(false.into()),
+        }
+    }
+
+    pub fn accepted(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.ws_accepted.fetch_add(1, atomic::Ordering::Relaxed);
+            if !self.active.swap(true, atomic::Ordering::Relaxed) {
+                metrics.ws_active.fetch_add(1, atomic::Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn rejected(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.ws_rejected.fetch_add(1, atomic::Ordering::Relaxed);
+        }
+    }
+
+    pub fn finished(&self) {
+        if let Some(metrics) = &self.metrics
+            && self.active.swap(false, atomic::Ordering::Relaxed)
+        {
+            metrics.ws_active.fetch_sub(1, atomic::Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    pub fn received(&self, message: &WSMessage) {
+        if let Some(metrics) = &self.metrics {
+            Self::observe(message, &metrics.ws_rx_msgs, &metrics.ws_rx_bytes, &metrics.ws_close_rx);
+        }
+    }
+
+    #[inline]
+    pub fn sent(&self, message: &WSMessage) {
+        if let Some(metrics) = &self.metrics {
+            Self::observe(message, &metrics.ws_tx_msgs, &metrics.ws_tx_bytes, &metrics.ws_close_tx);
+        }
+    }
+
+    fn observe(
+        message: &WSMessage,
+        msgs: &[atomic::AtomicUsize; 2],
+        bytes: &[atomic::AtomicUsize; 2],
+        close_codes: &Mutex<BTreeMap<u16, usize>>,
+    ) {
+        let (idx, len) = match message {
+            WSMessage::Text(data) => (0, data.len()),
+            WSMessage::Binary(data) => (1, data.len()),
+            WSMessage::Close(frame) => {
+                // 1005 is the reserved code for close frames with no status code
+                let code = frame.as_ref().map_or(1005, |frame| frame.code.into());
+                *close_codes.lock().unwrap().entry(code).or_default() += 1;
+                return;
+            }
+            _ => return,
+        };
+        msgs[idx].fetch_add(1, atomic::Ordering::Relaxed);
+        bytes[idx].fetch_add(len, atomic::Ordering::Relaxed);
+    }
+}
+
+// Tracks the lifetime and the number of requests of a connection, recorded once it gets dropped.
+pub(crate) struct ConnectionMetrics {
+    metrics: ArcWorkerMetrics,
+    requests: atomic::AtomicUsize,
+    start: std::time::Instant,
+}
+
+impl ConnectionMetrics {
+    #[inline]
+    pub fn request(&self) {
+        self.requests.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+}
+
+impl Drop for ConnectionMetrics {
+    fn drop(&mut self) {
+        self.metrics.conn_duration.observe(self.start.elapsed());
+        self.metrics
+            .conn_requests
+            .observe_value(self.requests.load(atomic::Ordering::Relaxed) as u64);
+    }
+}
+
+// Counts the bytes read from and written to the wrapped connection stream.
+pub(crate) struct MeteredStream<S> {
+    inner: S,
+    conn: Arc<ConnectionMetrics>,
+}
+
+impl<S> MeteredStream<S> {
+    pub fn new(inner: S, metrics: ArcWorkerMetrics) -> Self {
+        Self {
+            inner,
+            conn: Arc::new(ConnectionMetrics {
+                metrics,
+                requests: 0.into(),
+                start: std::time::Instant::now(),
+            }),
+        }
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn connection_metrics(&self) -> Arc<ConnectionMetrics> {
+        self.conn.clone()
+    }
+
+    #[inline]
+    fn written(&self, res: Poll<std::io::Result<usize>>) -> Poll<std::io::Result<usize>> {
+        if let Poll::Ready(Ok(len)) = res {
+            self.conn.metrics.bytes_tx.fetch_add(len, atomic::Ordering::Relaxed);
+        }
+        res
+    }
+}
+
+impl<S> tokio::io::AsyncRead for MeteredStream<S>
+where
+    S: tokio::io::AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        let res = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = res {
+            self.conn
+                .metrics
+                .bytes_rx
+                .fetch_add(buf.filled().len() - filled, atomic::Ordering::Relaxed);
+        }
+        res
+    }
+}
+
+impl<S> tokio::io::AsyncWrite for MeteredStream<S>
+where
+    S: tokio::io::AsyncWrite + Unpin,
+{
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        let res = Pin::new(&mut self.inner).poll_write(cx, buf);
+        self.written(res)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let res = Pin::new(&mut self.inner).poll_write_vectored(cx, bufs);
+        self.written(res)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+struct TimedBody {
+    inner: HTTPResponseBody,
+    timer: RequestTimer,
+}
+
+impl hyper::body::Body for TimedBody {
+    type Data = hyper::body::Bytes;
+    type Error = anyhow::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<hyper::body::Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> hyper::body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for TimedBody {
+    fn drop(&mut self) {
+        self.timer.metrics.req_duration[self.timer.proto].observe(self.timer.start.elapsed());
+    }
+}
+
 #[pyclass(frozen, module = "granian._granian")]
 pub(crate) struct MetricsAggregator {
     data_m: MainMetrics,
     data_w: Arc<Mutex<Vec<MetricsData>>>,
+    labels_w: Vec<String>,
+    interface: String,
+    buckets: Vec<f64>,
 }
 
 impl MetricsAggregator {
@@ -112,6 +546,21 @@ impl MetricsAggregator {
                 self.data_m.respawn_rss.load(atomic::Ordering::Relaxed)
             ),
         ];
+        {
+            // certificates are read again on changes, so reloads and renewals are reflected
+            let mut tls_certs = self.data_m.tls_certs.lock().unwrap();
+            if !tls_certs.is_empty() {
+                all.push(format!("# TYPE {prefix}tls_certificate_expiry_timestamp gauge"));
+                for cert in tls_certs.iter_mut() {
+                    if let Some(expiry) = cert.get() {
+                        all.push(format!(
+                            "{prefix}tls_certificate_expiry_timestamp{{cert=\"{}\"}} {expiry}",
+                            cert.path.replace('\\', "\\\\").replace('"', "\\\"")
+                        ));
+                    }
+                }
+            }
+        }
         let wrk_metrics = vec![
             (format!("{prefix}worker_lifetime"), "counter"),
             (format!("{prefix}connections_active"), "gauge"),
@@ -125,6 +574,11 @@ impl MetricsAggregator {
             (format!("{prefix}blocking_idle_cumulative"), "counter"),
             (format!("{prefix}blocking_busy_cumulative"), "counter"),
             (format!("{prefix}py_wait_cumulative"), "counter"),
+            (format!("{prefix}websocket_connections_active"), "gauge"),
+            (format!("{prefix}websocket_upgrades_accepted"), "counter"),
+            (format!("{prefix}websocket_upgrades_rejected"), "counter"),
+            (format!("{prefix}bytes_received"), "counter"),
+            (format!("{prefix}bytes_sent"), "counter"),
         ];
         let mut wrk: Vec<Vec<String>> = vec![vec![]; wrk_metrics.len()];
         {
@@ -133,7 +587,7 @@ impl MetricsAggregator {
                 wrk[metric_idx].push(format!("# TYPE {metric_label} {metric_type}"));
                 for (idx, values) in wrk_data.iter().enumerate() {
                     if let Some(value) = values.get(metric_idx) {
-                        wrk[metric_idx].push(format!("{}{{worker=\"{}\"}} {}", metric_label, idx + 1, value));
+                        wrk[metric_idx].push(format!("{}{{{}}} {}", metric_label, self.labels_w[idx], value));
                     }
                 }
             }
@@ -141,6 +595,103 @@ impl MetricsAggregator {
         for items in &mut wrk {
             all.append(items);
         }
+        let hist_metrics = [
+            format!("{prefix}request_duration_seconds"),
+            format!("{prefix}request_first_byte_seconds"),
+            format!("{prefix}request_queue_seconds"),
+        ];
+        {
+            let wrk_data = self.data_w.lock().unwrap();
+            for (hist_idx, metric_label) in hist_metrics.iter().enumerate() {
+                all.push(format!("# TYPE {metric_label} histogram"));
+                for (idx, values) in wrk_data.iter().enumerate() {
+                    for (proto_idx, proto) in HISTOGRAM_PROTOCOLS.iter().enumerate() {
+                        let metric_idx = wrk_metrics.len() + hist_idx * HISTOGRAM_PROTOCOLS.len() + proto_idx;
+                        if let Some(MetricValue::Hist(counts, sum)) = values.get(metric_idx) {
+                            let total: usize = counts.iter().sum();
+                            if total == 0 {
+                                continue;
+                            }
+                            let labels = format!(
+                                "{},interface=\"{}\",protocol=\"{proto}\"",
+                                self.labels_w[idx], self.interface
+                            );
+                            let mut cumul = 0;
+                            for (bound, count) in self.buckets.iter().zip(counts) {
+                                cumul += count;
+                                all.push(format!("{metric_label}_bucket{{{labels},le=\"{bound}\"}} {cumul}"));
+                            }
+                            all.push(format!("{metric_label}_bucket{{{labels},le=\"+Inf\"}} {total}"));
+                            all.push(format!("{metric_label}_sum{{{labels}}} {}", *sum as f64 / 1_000_000.0));
+                            all.push(format!("{metric_label}_count{{{labels}}} {total}"));
+                        }
+                    }
+                }
+            }
+        }
+        let labeled_metrics = [
+            (format!("{prefix}requests_by_status"), "status"),
+            (format!("{prefix}requests_by_method"), "method"),
+            (format!("{prefix}websocket_messages_received"), "type"),
+            (format!("{prefix}websocket_bytes_received"), "type"),
+            (format!("{prefix}websocket_messages_sent"), "type"),
+            (format!("{prefix}websocket_bytes_sent"), "type"),
+            (format!("{prefix}websocket_close_codes_received"), "code"),
+            (format!("{prefix}websocket_close_codes_sent"), "code"),
+        ];
+        {
+            let wrk_data = self.data_w.lock().unwrap();
+            let offset = wrk_metrics.len() + hist_metrics.len() * HISTOGRAM_PROTOCOLS.len();
+            for (labeled_idx, (metric_label, label)) in labeled_metrics.iter().enumerate() {
+                all.push(format!("# TYPE {metric_label} counter"));
+                for (idx, values) in wrk_data.iter().enumerate() {
+                    if let Some(MetricValue::Labeled(counts)) = values.get(offset + labeled_idx) {
+                        for (value, count) in counts {
+                            all.push(format!(
+                                "{metric_label}{{{},{label}=\"{value}\"}} {count}",
+                                self.labels_w[idx]
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        let conn_metrics = [
+            (
+                format!("{prefix}connection_duration_seconds"),
+                &CONNECTION_DURATION_BUCKETS,
+                1_000_000.0,
+            ),
+            (
+                format!("{prefix}connection_requests"),
+                &CONNECTION_REQUESTS_BUCKETS,
+                1.0,
+            ),
+        ];
+        {
+            let wrk_data = self.data_w.lock().unwrap();
+            let offset = wrk_metrics.len() + hist_metrics.len() * HISTOGRAM_PROTOCOLS.len() + labeled_metrics.len();
+            for (conn_idx, (metric_label, buckets, scale)) in conn_metrics.iter().enumerate() {
+                all.push(format!("# TYPE {metric_label} histogram"));
+                for (idx, values) in wrk_data.iter().enumerate() {
+                    if let Some(MetricValue::Hist(counts, sum)) = values.get(offset + conn_idx) {
+                        let total: usize = counts.iter().sum();
+                        if total == 0 {
+                            continue;
+                        }
+                        let labels = format!("{},interface=\"{}\"", self.labels_w[idx], self.interface);
+                        let mut cumul = 0;
+                        for (bound, count) in buckets.iter().zip(counts) {
+                            cumul += count;
+                            all.push(format!("{metric_label}_bucket{{{labels},le=\"{bound}\"}} {cumul}"));
+                        }
+                        all.push(format!("{metric_label}_bucket{{{labels},le=\"+Inf\"}} {total}"));
+                        all.push(format!("{metric_label}_sum{{{labels}}} {}", *sum as f64 / scale));
+                        all.push(format!("{metric_label}_count{{{labels}}} {total}"));
+                    }
+                }
+            }
+        }
         all.join("\n")
     }
 }
@@ -148,14 +699,23 @@ impl MetricsAggregator {
 #[pymethods]
 impl MetricsAggregator {
     #[new]
-    fn new(w_size: usize) -> Self {
+    #[pyo3(signature = (w_size, cpus=vec![], interface=String::new(), buckets=vec![]))]
+    fn new(w_size: usize, cpus: Vec<String>, interface: String, buckets: Vec<f64>) -> Self {
         let mut data = Vec::with_capacity(w_size);
-        for _ in 0..w_size {
+        let mut labels = Vec::with_capacity(w_size);
+        for idx in 0..w_size {
             data.push(Vec::new());
+            labels.push(match cpus.get(idx).filter(|v| !v.is_empty()) {
+                Some(cpu) => format!("worker=\"{}\",cpu=\"{cpu}\"", idx + 1),
+                None => format!("worker=\"{}\"", idx + 1),
+            });
         }
         Self {
             data_m: MainMetrics::new(),
             data_w: Arc::new(Mutex::new(data)),
+            labels_w: labels,
+            interface,
+            buckets,
         }
     }
 
@@ -174,6 +734,18 @@ impl MetricsAggregator {
     fn incr_respawn_rss(&self, val: usize) {
         self.data_m.respawn_rss.fetch_add(val, atomic::Ordering::Relaxed);
     }
+
+    fn set_tls_certs(&self, certs: Vec<(String, Option<u64>, bool)>) {
+        *self.data_m.tls_certs.lock().unwrap() = certs
+            .into_iter()
+            .map(|(path, expiry, pkcs12)| TlsCertExpiry {
+                path,
+                pkcs12,
+                mtime: None,
+                expiry,
+            })
+            .collect();
+    }
 }
 
 #[pyclass(frozen, module = "granian._granian")]
@@ -233,7 +805,7 @@ struct IPCMetricsCollector {
 
 #[inline(always)]
 fn collect_metrics(birth: &std::time::Instant, data: &Arc<WorkerMetrics>) -> MetricsData {
-    vec![
+    let mut ret = vec![
         MetricValue::Abs(birth.elapsed().as_secs() as usize),
         MetricValue::Abs(data.conn_active.load(atomic::Ordering::Relaxed)),
         MetricValue::Abs(data.conn_handled.load(atomic::Ordering::Relaxed)),
@@ -246,7 +818,53 @@ fn collect_metrics(birth: &std::time::Instant, data: &Arc<WorkerMetrics>) -> Met
         MetricValue::Abs(data.blocking_idle_cumul.load(atomic::Ordering::Relaxed)),
         MetricValue::Abs(data.blocking_busy_cumul.load(atomic::Ordering::Relaxed)),
         MetricValue::Abs(data.py_wait_cumul.load(atomic::Ordering::Relaxed)),
-    ]
+        MetricValue::Abs(data.ws_active.load(atomic::Ordering::Relaxed)),
+        MetricValue::Abs(data.ws_accepted.load(atomic::Ordering::Relaxed)),
+        MetricValue::Abs(data.ws_rejected.load(atomic::Ordering::Relaxed)),
+        MetricValue::Abs(data.bytes_rx.load(atomic::Ordering::Relaxed)),
+        MetricValue::Abs(data.bytes_tx.load(atomic::Ordering::Relaxed)),
+    ];
+    for histograms in [&data.req_duration, &data.req_first_byte, &data.req_queue] {
+        ret.extend(histograms.iter().map(Histogram::value));
+    }
+    ret.push(MetricValue::Labeled(
+        data.req_status
+            .iter()
+            .enumerate()
+            .map(|(idx, count)| ((idx + 100).to_string(), count.load(atomic::Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect(),
+    ));
+    ret.push(MetricValue::Labeled(
+        REQUEST_METHODS
+            .iter()
+            .zip(&data.req_method)
+            .map(|(method, count)| ((*method).to_string(), count.load(atomic::Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .collect(),
+    ));
+    for counters in [&data.ws_rx_msgs, &data.ws_rx_bytes, &data.ws_tx_msgs, &data.ws_tx_bytes] {
+        ret.push(MetricValue::Labeled(
+            ["text", "binary"]
+                .iter()
+                .zip(counters)
+                .map(|(kind, count)| ((*kind).to_string(), count.load(atomic::Ordering::Relaxed)))
+                .collect(),
+        ));
+    }
+    for close_codes in [&data.ws_close_rx, &data.ws_close_tx] {
+        ret.push(MetricValue::Labeled(
+            close_codes
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(code, count)| (code.to_string(), *count))
+                .collect(),
+        ));
+    }
+    ret.push(data.conn_duration.value());
+    ret.push(data.conn_requests.value());
+    ret
 }
 
 #[cfg(Py_GIL_DISABLED)]
@@ -12,21 +12,62 @@ use std::os::windows::io::{AsRawSocket, FromRawSocket};
 
 use socket2::{Domain, Protocol, Socket, Type};
 
+// Credentials of the process on the other end of a Unix socket (`SO_PEERCRED`).
+#[cfg_attr(not(unix), allow(dead_code))]
+#[derive(Clone, Copy)]
+pub(crate) struct PeerCred {
+    pub pid: Option<i32>,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+#[cfg(unix)]
+impl From<tokio::net::unix::UCred> for PeerCred {
+    fn from(value: tokio::net::unix::UCred) -> Self {
+        Self {
+            pid: value.pid(),
+            uid: value.uid(),
+            gid: value.gid(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) enum SockAddr {
     #[allow(clippy::upper_case_acronyms)]
     TCP(std::net::SocketAddr),
     #[cfg(unix)]
     #[allow(clippy::upper_case_acronyms)]
-    UDS(tokio::net::unix::SocketAddr),
+    UDS(tokio::net::unix::SocketAddr, Option<PeerCred>),
 }
 
 impl SockAddr {
+    #[cfg(unix)]
+    pub fn uds(addr: tokio::net::unix::SocketAddr) -> Self {
+        Self::UDS(addr, None)
+    }
+
+    #[cfg(unix)]
+    pub fn with_peer_cred(self, cred: Option<PeerCred>) -> Self {
+        match self {
+            Self::UDS(addr, _) => Self::UDS(addr, cred),
+            addr @ Self::TCP(_) => addr,
+        }
+    }
+
+    pub fn peer_cred(&self) -> Option<PeerCred> {
+        match self {
+            Self::TCP(_) => None,
+            #[cfg(unix)]
+            Self::UDS(_, cred) => *cred,
+        }
+    }
+
     pub fn ip(&self) -> String {
         match self {
             Self::TCP(addr) => addr.ip().to_string(),
             #[cfg(unix)]
-            Self::UDS(addr) => addr.as_pathname().map_or("", |v| v.to_str().unwrap()).to_string(),
+            Self::UDS(addr, _) => addr.as_pathname().map_or("", |v| v.to_str().unwrap()).to_string(),
         }
     }
 
@@ -34,7 +75,7 @@ impl SockAddr {
         match self {
             Self::TCP(addr) => addr.port(),
             #[cfg(unix)]
-            Self::UDS(_) => 0,
+            Self::UDS(..) => 0,
         }
     }
 
@@ -43,11 +84,132 @@ impl SockAddr {
         match self {
             Self::TCP(addr) => addr.to_string(),
             #[cfg(unix)]
-            Self::UDS(addr) => addr.as_pathname().map_or("", |v| v.to_str().unwrap()).to_string(),
+            Self::UDS(addr, _) => addr.as_pathname().map_or("", |v| v.to_str().unwrap()).to_string(),
         }
     }
 }
 
+type TcpSocketOptsInput = (
+    Option<u64>,
+    Option<u64>,
+    Option<u32>,
+    Option<i32>,
+    Option<i32>,
+    Option<usize>,
+    Option<usize>,
+    Option<u64>,
+    Option<bool>,
+);
+
+#[derive(Clone, Default)]
+struct TcpSocketOpts {
+    keepalive_idle: Option<u64>,
+    keepalive_interval: Option<u64>,
+    keepalive_count: Option<u32>,
+    defer_accept: Option<i32>,
+    fastopen: Option<i32>,
+    recv_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+    user_timeout: Option<u64>,
+    ipv6_only: Option<bool>,
+}
+
+impl From<TcpSocketOptsInput> for TcpSocketOpts {
+    fn from(value: TcpSocketOptsInput) -> Self {
+        Self {
+            keepalive_idle: value.0,
+            keepalive_interval: value.1,
+            keepalive_count: value.2,
+            defer_accept: value.3,
+            fastopen: value.4,
+            recv_buffer_size: value.5,
+            send_buffer_size: value.6,
+            user_timeout: value.7,
+            ipv6_only: value.8,
+        }
+    }
+}
+
+impl TcpSocketOpts {
+    fn as_input(&self) -> TcpSocketOptsInput {
+        (
+            self.keepalive_idle,
+            self.keepalive_interval,
+            self.keepalive_count,
+            self.defer_accept,
+            self.fastopen,
+            self.recv_buffer_size,
+            self.send_buffer_size,
+            self.user_timeout,
+            self.ipv6_only,
+        )
+    }
+
+    // NOTE: options are set on the listening socket, as accepted connections inherit them.
+    fn apply(&self, socket: &Socket, domain: Domain) -> Result<()> {
+        if domain == Domain::IPV6
+            && let Some(only) = self.ipv6_only
+        {
+            socket.set_only_v6(only)?;
+        }
+
+        if self.keepalive_idle.is_some() || self.keepalive_interval.is_some() || self.keepalive_count.is_some() {
+            let mut keepalive = socket2::TcpKeepalive::new();
+            if let Some(idle) = self.keepalive_idle {
+                keepalive = keepalive.with_time(std::time::Duration::from_secs(idle));
+            }
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "freebsd", windows))]
+            if let Some(interval) = self.keepalive_interval {
+                keepalive = keepalive.with_interval(std::time::Duration::from_secs(interval));
+            }
+            #[cfg(any(target_os = "linux", target_os = "macos", target_os = "freebsd"))]
+            if let Some(count) = self.keepalive_count {
+                keepalive = keepalive.with_retries(count);
+            }
+            socket.set_tcp_keepalive(&keepalive)?;
+        }
+
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(timeout) = self.user_timeout {
+                socket.set_tcp_user_timeout(Some(std::time::Duration::from_millis(timeout)))?;
+            }
+            if let Some(secs) = self.defer_accept {
+                tcp_setsockopt(socket, libc::TCP_DEFER_ACCEPT, secs)?;
+            }
+            if let Some(qlen) = self.fastopen {
+                tcp_setsockopt(socket, libc::TCP_FASTOPEN, qlen)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn tcp_setsockopt(socket: &Socket, opt: libc::c_int, value: libc::c_int) -> std::io::Result<()> {
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            opt,
+            std::ptr::from_ref(&value).cast(),
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if ret == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
 #[pyclass(frozen, from_py_object, module = "granian._granian")]
 #[derive(Clone)]
 pub struct ListenerSpec {
@@ -55,6 +217,7 @@ pub struct ListenerSpec {
     address: std::net::SocketAddr,
     domain: Domain,
     backlog: i32,
+    opts: TcpSocketOpts,
     resolved_port: std::sync::OnceLock<u16>,
 }
 
@@ -66,6 +229,7 @@ pub struct UnixListenerSpec {
     address: socket2::SockAddr,
     backlog: i32,
     permissions: Option<u32>,
+    owner: (Option<u32>, Option<u32>),
 }
 
 impl ListenerSpec {
@@ -87,6 +251,7 @@ impl ListenerSpec {
         }
         socket.set_reuse_address(true)?;
         socket.set_tcp_nodelay(true)?;
+        self.opts.apply(&socket, self.domain)?;
         socket.bind(&address.into())?;
 
         if address.port() == 0
@@ -116,7 +281,8 @@ impl ListenerSpec {
 #[pymethods]
 impl ListenerSpec {
     #[new]
-    fn new(host: String, port: u16, backlog: i32) -> PyResult<Self> {
+    #[pyo3(signature = (host, port, backlog, opts=None))]
+    fn new(host: String, port: u16, backlog: i32, opts: Option<TcpSocketOptsInput>) -> PyResult<Self> {
         let address: std::net::SocketAddr = (host.parse::<IpAddr>()?, port).into();
         let domain = match address {
             std::net::SocketAddr::V4(_) => Domain::IPV4,
@@ -127,6 +293,7 @@ impl ListenerSpec {
             address,
             domain,
             backlog,
+            opts: opts.map(TcpSocketOpts::from).unwrap_or_default(),
             resolved_port: std::sync::OnceLock::new(),
         })
     }
@@ -140,7 +307,7 @@ impl ListenerSpec {
     }
 
     pub fn __getstate__(&self, py: Python) -> Py<PyAny> {
-        (self.inp.0.clone(), self.local_port(), self.inp.2)
+        (self.inp.0.clone(), self.local_port(), self.inp.2, self.opts.as_input())
             .into_py_any(py)
             .unwrap()
     }
@@ -151,34 +318,82 @@ impl UnixListenerSpec {
     pub(crate) fn as_socket(&self) -> Result<Socket> {
         use std::{fs, os::unix::fs::PermissionsExt};
 
+        // NOTE: abstract sockets have no path, thus nothing to cleanup or set permissions on.
+        let socket_path = self.address.as_pathname();
+        if let Some(path) = socket_path {
+            Self::cleanup_stale(path)?;
+        }
+
         let socket = Socket::new(Domain::UNIX, Type::STREAM, None)?;
 
         socket.bind(&self.address)?;
         #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
         socket.listen(self.backlog)?;
 
-        if let Some(mode) = self.permissions {
-            let socket_path = self.address.as_pathname().unwrap();
-            let mut permissions = fs::metadata(socket_path)?.permissions();
-            permissions.set_mode(mode);
-            fs::set_permissions(socket_path, permissions)?;
+        if let Some(path) = socket_path {
+            if let Some(mode) = self.permissions {
+                let mut permissions = fs::metadata(path)?.permissions();
+                permissions.set_mode(mode);
+                fs::set_permissions(path, permissions)?;
+            }
+            if self.owner.0.is_some() || self.owner.1.is_some() {
+                std::os::unix::fs::chown(path, self.owner.0, self.owner.1)?;
+            }
         }
 
         Ok(socket)
     }
+
+    // Removes a leftover socket file, but only if nobody is listening on it.
+    fn cleanup_stale(path: &std::path::Path) -> Result<()> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let Ok(metadata) = std::fs::symlink_metadata(path) else {
+            return Ok(());
+        };
+        if !metadata.file_type().is_socket() {
+            anyhow::bail!("{} already exists and is not a socket", path.display());
+        }
+        match std::os::unix::net::UnixStream::connect(path) {
+            Ok(_) => anyhow::bail!("{} is already in use by another process", path.display()),
+            Err(err) if err.kind() == std::io::ErrorKind::ConnectionRefused => {
+                std::fs::remove_file(path)?;
+                Ok(())
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 #[cfg(unix)]
 #[pymethods]
 impl UnixListenerSpec {
     #[new]
-    fn new(bind: String, backlog: i32, permissions: Option<u32>) -> PyResult<Self> {
-        let address = socket2::SockAddr::unix(&bind)?;
+    #[pyo3(signature = (bind, backlog, permissions=None, owner=None, group=None))]
+    fn new(
+        bind: String,
+        backlog: i32,
+        permissions: Option<u32>,
+        owner: Option<u32>,
+        group: Option<u32>,
+    ) -> PyResult<Self> {
+        let address = match bind.strip_prefix('@') {
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Some(name) => socket2::SockAddr::unix(format!("\0{name}"))?,
+            #[cfg(not(any(target_os = "linux", target_os = "android")))]
+            Some(_) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "abstract Unix sockets are only available on Linux",
+                ));
+            }
+            None => socket2::SockAddr::unix(&bind)?,
+        };
         Ok(Self {
             inp: (bind, backlog),
             address,
             backlog,
             permissions,
+            owner: (owner, group),
         })
     }
 
@@ -400,11 +615,58 @@ impl SocketHolder {
     }
 }
 
+// First file descriptor passed by systemd socket activation (`SD_LISTEN_FDS_START`).
+#[cfg(unix)]
+const LISTEN_FDS_START: i32 = 3;
+
+// Wraps the sockets inherited through systemd socket activation (`LISTEN_FDS`),
+// along with their names from `LISTEN_FDNAMES`.
+#[cfg(unix)]
+#[pyfunction]
+fn listen_fds(backlog: i32) -> Result<Vec<(String, SocketHolder)>> {
+    let Some(count) = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        .filter(|pid| *pid == std::process::id())
+        .and_then(|_| std::env::var("LISTEN_FDS").ok())
+        .and_then(|fds| fds.parse::<i32>().ok())
+    else {
+        return Ok(vec![]);
+    };
+    let names: Vec<String> = std::env::var("LISTEN_FDNAMES")
+        .map(|names| names.split(':').map(String::from).collect())
+        .unwrap_or_default();
+
+    let mut rv = Vec::with_capacity(count.max(0) as usize);
+    for idx in 0..count {
+        let fd = LISTEN_FDS_START + idx;
+        let socket = std::mem::ManuallyDrop::new(unsafe { Socket::from_raw_fd(fd) });
+        if socket.r#type()? != Type::STREAM {
+            anyhow::bail!("inherited file descriptor {fd} is not a stream socket");
+        }
+        let uds = socket.local_addr()?.domain() == Domain::UNIX;
+        let name = names
+            .get(idx as usize)
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string());
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        rv.push((name, SocketHolder::new(fd, uds, backlog)));
+        #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+        {
+            _ = backlog;
+            rv.push((name, SocketHolder::new(fd, uds)));
+        }
+    }
+    Ok(rv)
+}
+
 pub(crate) fn init_pymodule(module: &Bound<PyModule>) -> PyResult<()> {
     module.add_class::<ListenerSpec>()?;
     module.add_class::<SocketHolder>()?;
     #[cfg(unix)]
     module.add_class::<UnixListenerSpec>()?;
+    #[cfg(unix)]
+    module.add_function(wrap_pyfunction!(listen_fds, module)?)?;
 
     Ok(())
 }
@@ -0,0 +1,83 @@
+use anyhow::{Result, anyhow};
+use ring::digest;
+use std::time::SystemTime;
+
+use crate::der::{
+    TAG_ENUMERATED, TAG_EXPLICIT_0, TAG_GENERALIZED_TIME, TAG_INTEGER, TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE,
+    cert_issuer_id, cert_public_key, expect_tlv, parse_time, read_tlv,
+};
+
+// id-pkix-ocsp-basic (1.3.6.1.5.5.7.48.1.1)
+const OID_OCSP_BASIC: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01];
+// id-sha1 (1.3.14.3.2.26), id-sha256/384/512 (2.16.840.1.101.3.4.2.1/2/3)
+const OID_SHA1: &[u8] = &[0x2b, 0x0e, 0x03, 0x02, 0x1a];
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_SHA384: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02];
+const OID_SHA512: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03];
+
+// Checks whether a `certID` refers to the given DER encoded certificate, signed by the given issuer certificate.
+fn cert_id_matches(cert_id: &[u8], cert: &[u8], issuer: &[u8]) -> Result<bool> {
+    let (algorithm, rest) = expect_tlv(cert_id, TAG_SEQUENCE)?;
+    let (algorithm, _) = expect_tlv(algorithm, TAG_OID)?;
+    let algorithm = match algorithm {
+        OID_SHA1 => &digest::SHA1_FOR_LEGACY_USE_ONLY,
+        OID_SHA256 => &digest::SHA256,
+        OID_SHA384 => &digest::SHA384,
+        OID_SHA512 => &digest::SHA512,
+        _ => return Err(anyhow!("unsupported OCSP certificate ID hash algorithm")),
+    };
+    let (name_hash, rest) = expect_tlv(rest, TAG_OCTET_STRING)?;
+    let (key_hash, rest) = expect_tlv(rest, TAG_OCTET_STRING)?;
+    let (serial, _) = expect_tlv(rest, TAG_INTEGER)?;
+
+    let (cert_serial, cert_issuer) = cert_issuer_id(cert)?;
+    Ok(serial == cert_serial
+        && name_hash == digest::digest(algorithm, cert_issuer).as_ref()
+        && key_hash == digest::digest(algorithm, cert_public_key(issuer)?).as_ref())
+}
+
+// Extracts the `nextUpdate` value of the single response matching the given certificate (and its issuer)
+// contained in a DER OCSP response. `None` means the responder didn't specify it.
+pub(crate) fn next_update(response: &[u8], cert: &[u8], issuer: &[u8]) -> Result<Option<SystemTime>> {
+    let (ocsp_response, _) = expect_tlv(response, TAG_SEQUENCE)?;
+    let (status, rest) = expect_tlv(ocsp_response, TAG_ENUMERATED)?;
+    if status != [0] {
+        return Err(anyhow!("unsuccessful OCSP response status"));
+    }
+    let (response_bytes, _) = expect_tlv(rest, TAG_EXPLICIT_0)?;
+    let (response_bytes, _) = expect_tlv(response_bytes, TAG_SEQUENCE)?;
+    let (response_type, rest) = expect_tlv(response_bytes, TAG_OID)?;
+    if response_type != OID_OCSP_BASIC {
+        return Err(anyhow!("unsupported OCSP response type"));
+    }
+    let (basic_response, _) = expect_tlv(rest, TAG_OCTET_STRING)?;
+    let (basic_response, _) = expect_tlv(basic_response, TAG_SEQUENCE)?;
+    let (response_data, _) = expect_tlv(basic_response, TAG_SEQUENCE)?;
+
+    // skip the optional version and the responder id, then `producedAt`
+    let (tag, _, rest) = read_tlv(response_data)?;
+    let rest = if tag == TAG_EXPLICIT_0 { read_tlv(rest)?.2 } else { rest };
+    let (_, rest) = expect_tlv(rest, TAG_GENERALIZED_TIME)?;
+
+    let (mut responses, _) = expect_tlv(rest, TAG_SEQUENCE)?;
+    while !responses.is_empty() {
+        let (single_response, tail) = expect_tlv(responses, TAG_SEQUENCE)?;
+        responses = tail;
+        let (cert_id, rest) = expect_tlv(single_response, TAG_SEQUENCE)?;
+        if !cert_id_matches(cert_id, cert, issuer)? {
+            continue;
+        }
+        // skip `certStatus` and `thisUpdate`
+        let (_, _, rest) = read_tlv(rest)?;
+        let (_, rest) = expect_tlv(rest, TAG_GENERALIZED_TIME)?;
+
+        return match read_tlv(rest) {
+            Ok((TAG_EXPLICIT_0, next_update, _)) => {
+                let (value, _) = expect_tlv(next_update, TAG_GENERALIZED_TIME)?;
+                Ok(Some(parse_time(TAG_GENERALIZED_TIME, value)?))
+            }
+            _ => Ok(None),
+        };
+    }
+    Err(anyhow!("OCSP response doesn't match the certificate"))
+}
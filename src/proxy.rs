@@ -0,0 +1,361 @@
+use http_body_util::BodyExt;
+use hyper::{
+    Uri, Version,
+    body::Incoming,
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+};
+use hyper_util::{
+    client::legacy::{
+        Client,
+        connect::{Connected, Connection},
+    },
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+};
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{
+    http::{HTTPProto, HTTPRequest, HTTPResponse, response_502, response_504},
+    net::SockAddr,
+};
+
+// (routes as (prefix, upstream), connect timeout, response timeout)
+pub(crate) type ProxyConfig = (Vec<(String, String)>, u64, u64);
+
+const HK_X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const HK_X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+const HK_X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+const HK_KEEP_ALIVE: HeaderName = HeaderName::from_static("keep-alive");
+const HOP_HEADERS: [HeaderName; 8] = [
+    header::CONNECTION,
+    HK_KEEP_ALIVE,
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+#[derive(Clone)]
+enum ProxyUpstream {
+    Tcp(String),
+    #[cfg(unix)]
+    Uds(Arc<std::path::PathBuf>),
+}
+
+impl ProxyUpstream {
+    fn new(value: &str) -> Self {
+        #[cfg(unix)]
+        if let Some(path) = value.strip_prefix("unix:") {
+            return Self::Uds(Arc::new(path.into()));
+        }
+        // upstreams are validated to be HOST:PORT only, still we never want to build
+        // the request URI from anything else than the authority part.
+        let authority = value.strip_prefix("http://").unwrap_or(value);
+        let authority = authority.split(['/', '?', '#']).next().unwrap_or_default();
+        Self::Tcp(authority.to_string())
+    }
+
+    fn authority(&self) -> &str {
+        match self {
+            Self::Tcp(authority) => authority,
+            #[cfg(unix)]
+            Self::Uds(_) => "localhost",
+        }
+    }
+}
+
+enum ProxyIo {
+    Tcp(tokio::net::TcpStream),
+    #[cfg(unix)]
+    Uds(tokio::net::UnixStream),
+}
+
+macro_rules! proxy_io_delegate {
+    ($self:expr, $stream:ident => $expr:expr) => {
+        match $self.get_mut() {
+            ProxyIo::Tcp($stream) => $expr,
+            #[cfg(unix)]
+            ProxyIo::Uds($stream) => $expr,
+        }
+    };
+}
+
+impl AsyncRead for ProxyIo {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        proxy_io_delegate!(self, stream => Pin::new(stream).poll_read(cx, buf))
+    }
+}
+
+impl AsyncWrite for ProxyIo {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        proxy_io_delegate!(self, stream => Pin::new(stream).poll_write(cx, buf))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        proxy_io_delegate!(self, stream => Pin::new(stream).poll_write_vectored(cx, bufs))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Tcp(stream) => stream.is_write_vectored(),
+            #[cfg(unix)]
+            Self::Uds(stream) => stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        proxy_io_delegate!(self, stream => Pin::new(stream).poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        proxy_io_delegate!(self, stream => Pin::new(stream).poll_shutdown(cx))
+    }
+}
+
+struct ProxyStream(TokioIo<ProxyIo>);
+
+impl hyper::rt::Read for ProxyStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: hyper::rt::ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+impl hyper::rt::Write for ProxyStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.0.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_shutdown(cx)
+    }
+}
+
+impl Connection for ProxyStream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+#[derive(Clone)]
+struct ProxyConnector {
+    upstream: ProxyUpstream,
+    timeout: Duration,
+}
+
+impl tower_service::Service<Uri> for ProxyConnector {
+    type Response = ProxyStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let upstream = self.upstream.clone();
+        let timeout = self.timeout;
+        Box::pin(async move {
+            let connect = async {
+                match upstream {
+                    ProxyUpstream::Tcp(authority) => {
+                        let stream = tokio::net::TcpStream::connect(authority).await?;
+                        _ = stream.set_nodelay(true);
+                        Ok(ProxyIo::Tcp(stream))
+                    }
+                    #[cfg(unix)]
+                    ProxyUpstream::Uds(path) => Ok(ProxyIo::Uds(tokio::net::UnixStream::connect(&*path).await?)),
+                }
+            };
+            match tokio::time::timeout(timeout, connect).await {
+                Ok(stream) => stream.map(|io| ProxyStream(TokioIo::new(io))),
+                Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "upstream connection timed out")),
+            }
+        })
+    }
+}
+
+pub(crate) struct ProxyRoute {
+    prefix: String,
+    authority: String,
+    client: Client<ProxyConnector, Incoming>,
+    timeout: Duration,
+}
+
+impl ProxyRoute {
+    fn new(prefix: &str, upstream: &str, connect_timeout: Duration, timeout: Duration) -> Self {
+        let upstream = ProxyUpstream::new(upstream);
+        let authority = upstream.authority().to_string();
+        let client = Client::builder(TokioExecutor::new())
+            .pool_timer(TokioTimer::new())
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build(ProxyConnector {
+                upstream,
+                timeout: connect_timeout,
+            });
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            authority,
+            client,
+            timeout,
+        }
+    }
+
+    pub fn forward(
+        &self,
+        req: HTTPRequest,
+        addr_remote: &SockAddr,
+        proto: &HTTPProto,
+    ) -> impl Future<Output = HTTPResponse> + Send + 'static {
+        let (mut parts, body) = req.into_parts();
+        let uri = Uri::builder()
+            .scheme("http")
+            .authority(self.authority.as_str())
+            .path_and_query(parts.uri.path_and_query().map_or("/", |v| v.as_str()))
+            .build();
+        let host = parts.headers.get(header::HOST).cloned().or_else(|| {
+            parts
+                .uri
+                .authority()
+                .and_then(|v| HeaderValue::from_str(v.as_str()).ok())
+        });
+
+        strip_hop_headers(&mut parts.headers);
+        forwarded_headers(&mut parts.headers, host, addr_remote, proto);
+
+        let client = self.client.clone();
+        let timeout = self.timeout;
+        let req = uri.map(|uri| {
+            parts.uri = uri;
+            parts.version = Version::HTTP_11;
+            hyper::Request::from_parts(parts, body)
+        });
+
+        async move {
+            let Ok(req) = req else {
+                return response_502();
+            };
+            match tokio::time::timeout(timeout, client.request(req)).await {
+                Ok(Ok(res)) => {
+                    let (mut parts, body) = res.into_parts();
+                    strip_hop_headers(&mut parts.headers);
+                    hyper::Response::from_parts(parts, body.map_err(anyhow::Error::from).boxed())
+                }
+                Ok(Err(err)) if is_timeout(&err) => response_504(),
+                Ok(Err(err)) => {
+                    log::warn!("Proxy upstream request failed: {err:?}");
+                    response_502()
+                }
+                Err(_) => response_504(),
+            }
+        }
+    }
+}
+
+pub(crate) struct WorkerProxy {
+    routes: Vec<ProxyRoute>,
+}
+
+impl WorkerProxy {
+    pub fn new(config: Option<&ProxyConfig>) -> Option<Arc<Self>> {
+        let (routes_cfg, connect_timeout, timeout) = config?;
+        let connect_timeout = Duration::from_secs(*connect_timeout);
+        let timeout = Duration::from_secs(*timeout);
+        let mut routes: Vec<ProxyRoute> = routes_cfg
+            .iter()
+            .map(|(prefix, upstream)| ProxyRoute::new(prefix, upstream, connect_timeout, timeout))
+            .collect();
+        // longest prefix first, so the first match is also the most specific one
+        routes.sort_by(|a, b| b.prefix.len().cmp(&a.prefix.len()));
+        Some(Arc::new(Self { routes }))
+    }
+
+    #[inline]
+    pub fn match_route(&self, uri_path: &str) -> Option<&ProxyRoute> {
+        self.routes.iter().find(|route| {
+            uri_path
+                .strip_prefix(route.prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+fn strip_hop_headers(headers: &mut HeaderMap) {
+    let conn_headers: Vec<HeaderName> = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|v| HeaderName::from_bytes(v.trim().as_bytes()).ok())
+        .collect();
+    for name in conn_headers.iter().chain(HOP_HEADERS.iter()) {
+        headers.remove(name);
+    }
+}
+
+fn forwarded_headers(headers: &mut HeaderMap, host: Option<HeaderValue>, addr_remote: &SockAddr, proto: &HTTPProto) {
+    let remote_ip = addr_remote.ip();
+    if !remote_ip.is_empty() {
+        // the header might be split over multiple lines, which all count as a single list
+        let forwarded_for = headers
+            .get_all(HK_X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .chain(std::iter::once(remote_ip.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Ok(value) = HeaderValue::from_str(&forwarded_for) {
+            headers.insert(HK_X_FORWARDED_FOR, value);
+        }
+    }
+    headers.insert(HK_X_FORWARDED_PROTO, HeaderValue::from_static(proto.as_str()));
+    if let Some(host) = host {
+        headers.insert(HK_X_FORWARDED_HOST, host.clone());
+        headers.insert(header::HOST, host);
+    }
+}
+
+fn is_timeout(err: &hyper_util::client::legacy::Error) -> bool {
+    let mut source = std::error::Error::source(err);
+    while let Some(inner) = source {
+        if let Some(io_err) = inner.downcast_ref::<io::Error>()
+            && io_err.kind() == io::ErrorKind::TimedOut
+        {
+            return true;
+        }
+        source = inner.source();
+    }
+    false
+}
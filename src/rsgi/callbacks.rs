@@ -8,6 +8,7 @@ use super::{
 };
 use crate::{
     callbacks::ArcCBScheduler,
+    metrics::{RequestMetrics, WebsocketMetrics},
     runtime::{Runtime, RuntimeRef},
     utils::log_application_callable_exception,
     ws::{HyperWebsocket, UpgradeData},
@@ -121,11 +122,15 @@ pub(crate) fn call_http(
     disconnect_guard: Arc<Notify>,
     body: hyper::body::Incoming,
     scope: HTTPScope,
+    metrics: Option<RequestMetrics>,
 ) -> oneshot::Receiver<PyResponse> {
     let (tx, rx) = oneshot::channel();
     let protocol = HTTPProtocol::new(rt.clone(), tx, body, disconnect_guard);
 
     rt.spawn_blocking(move |py| {
+        if let Some(metrics) = metrics {
+            metrics.observe_queue();
+        }
         if let Ok(watcher) = CallbackWatcherHTTP::new(py, protocol, scope) {
             cb.get().schedule(py, watcher);
         }
@@ -142,9 +147,10 @@ pub(crate) fn call_ws(
     ws: HyperWebsocket,
     upgrade: UpgradeData,
     scope: WebsocketScope,
+    metrics: WebsocketMetrics,
 ) -> oneshot::Receiver<WebsocketDetachedTransport> {
     let (tx, rx) = oneshot::channel();
-    let protocol = WebsocketProtocol::new(rt.clone(), tx, ws, upgrade, disconnect_guard);
+    let protocol = WebsocketProtocol::new(rt.clone(), tx, ws, upgrade, disconnect_guard, metrics);
 
     rt.spawn_blocking(move |py| {
         if let Ok(watcher) = CallbackWatcherWebsocket::new(py, protocol, scope) {
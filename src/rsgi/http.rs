@@ -1,6 +1,4 @@
 use futures::sink::SinkExt;
-use http_body_util::BodyExt;
-use hyper::{StatusCode, header::SERVER as HK_SERVER, http::response::Builder as ResponseBuilder};
 use std::sync::Arc;
 use tokio::sync::{Notify, mpsc};
 
@@ -10,9 +8,11 @@ use super::{
 };
 use crate::{
     callbacks::ArcCBScheduler,
-    http::{HTTPProto, HTTPRequest, HTTPResponse, HV_SERVER, empty_body, response_500},
+    http::{HTTPProto, HTTPRequest, HTTPResponse, response_400, response_500, response_empty},
+    metrics::{RequestMetrics, WebsocketMetrics},
     net::SockAddr,
     runtime::{Runtime, RuntimeRef},
+    tls::TlsInfo,
     ws::{UpgradeData, is_upgrade_request as is_ws_upgrade, upgrade_intent as ws_upgrade},
 };
 
@@ -26,13 +26,14 @@ macro_rules! build_scope {
             $server_addr,
             $client_addr,
             $req.headers,
+            $req.extensions.get::<Arc<TlsInfo>>().cloned(),
         )
     };
 }
 
 macro_rules! handle_http_response {
-    ($handler:expr, $rt:expr, $disconnect_guard:expr, $callback:expr, $body:expr, $scope:expr) => {
-        match $handler($callback, $rt, $disconnect_guard, $body, $scope).await {
+    ($handler:expr, $rt:expr, $disconnect_guard:expr, $callback:expr, $body:expr, $scope:expr, $metrics:expr) => {
+        match $handler($callback, $rt, $disconnect_guard, $body, $scope, $metrics).await {
             Ok(PyResponse::Body(pyres)) => pyres.to_response(),
             Ok(PyResponse::File(pyres)) => pyres.to_response().await,
             Ok(PyResponse::FileRange(pyres)) => pyres.to_response().await,
@@ -56,9 +57,10 @@ macro_rules! handle_request {
             req: HTTPRequest,
             scheme: HTTPProto,
         ) -> HTTPResponse {
-            let (parts, body) = req.into_parts();
+            let (mut parts, body) = req.into_parts();
+            let metrics = parts.extensions.remove::<RequestMetrics>();
             let scope = build_scope!(HTTPScope, server_addr, client_addr, parts, scheme);
-            handle_http_response!($handler, rt, disconnect_guard, callback, body, scope)
+            handle_http_response!($handler, rt, disconnect_guard, callback, body, scope, metrics)
         }
     };
 }
@@ -79,6 +81,7 @@ macro_rules! handle_request_with_ws {
                 match ws_upgrade(&mut req, None) {
                     Ok((res, ws)) => {
                         let (parts, _) = req.into_parts();
+                        let metrics = WebsocketMetrics::new(parts.extensions.get::<RequestMetrics>());
                         let scope = build_scope!(WebsocketScope, server_addr, client_addr, parts, scheme);
                         let (restx, mut resrx) = mpsc::channel(1);
                         let rth = rt.clone();
@@ -87,19 +90,20 @@ macro_rules! handle_request_with_ws {
                         rt.spawn_cancellable(cancel_sig.clone(), async move {
                             let tx_ref = restx.clone();
 
-                            match $handler_ws(callback, rth, cancel_sig, ws, UpgradeData::new(res, restx), scope).await
+                            match $handler_ws(
+                                callback,
+                                rth,
+                                cancel_sig,
+                                ws,
+                                UpgradeData::new(res, restx),
+                                scope,
+                                metrics,
+                            )
+                            .await
                             {
                                 Ok((status, consumed, stream)) => match (consumed, stream) {
                                     (false, _) => {
-                                        let _ = tx_ref
-                                            .send(
-                                                ResponseBuilder::new()
-                                                    .status(status as u16)
-                                                    .header(HK_SERVER, HV_SERVER)
-                                                    .body(empty_body())
-                                                    .unwrap(),
-                                            )
-                                            .await;
+                                        let _ = tx_ref.send(response_empty(status as u16)).await;
                                     }
                                     (true, Some(mut stream)) => {
                                         let _ = stream.close().await;
@@ -123,22 +127,15 @@ macro_rules! handle_request_with_ws {
                     }
                     Err(err) => {
                         log::info!("Websocket handshake failed with {:?}", err);
-                        return ResponseBuilder::new()
-                            .status(StatusCode::BAD_REQUEST)
-                            .header(HK_SERVER, HV_SERVER)
-                            .body(
-                                http_body_util::Full::new(format!("{}", err).into())
-                                    .map_err(|e| match e {})
-                                    .boxed(),
-                            )
-                            .unwrap();
+                        return response_400(format!("{}", err));
                     }
                 }
             }
 
-            let (parts, body) = req.into_parts();
+            let (mut parts, body) = req.into_parts();
+            let metrics = parts.extensions.remove::<RequestMetrics>();
             let scope = build_scope!(HTTPScope, server_addr, client_addr, parts, scheme);
-            handle_http_response!($handler_req, rt, disconnect_guard, callback, body, scope)
+            handle_http_response!($handler_req, rt, disconnect_guard, callback, body, scope, metrics)
         }
     };
 }
@@ -15,6 +15,7 @@ use super::{
 };
 use crate::{
     conversion::FutureResultToPy,
+    metrics::WebsocketMetrics,
     runtime::{Runtime, RuntimeRef, empty_future_into_py, err_future_into_py, future_into_py_futlike},
     ws::{HyperWebsocket, UpgradeData, WSRxStream, WSTxStream},
 };
@@ -228,6 +229,7 @@ pub(crate) struct RSGIWebsocketTransport {
     tx: Arc<AsyncMutex<Option<WSTxStream>>>,
     rx: Arc<AsyncMutex<WSRxStream>>,
     closed: Arc<atomic::AtomicBool>,
+    metrics: WebsocketMetrics,
 }
 
 impl RSGIWebsocketTransport {
@@ -237,6 +239,7 @@ impl RSGIWebsocketTransport {
         tx: Arc<AsyncMutex<Option<WSTxStream>>>,
         rx: WSRxStream,
         closed: Arc<atomic::AtomicBool>,
+        metrics: WebsocketMetrics,
     ) -> Self {
         Self {
             rt,
@@ -244,6 +247,7 @@ impl RSGIWebsocketTransport {
             tx,
             rx: Arc::new(AsyncMutex::new(rx)),
             closed,
+            metrics,
         }
     }
 }
@@ -253,6 +257,7 @@ impl RSGIWebsocketTransport {
     fn receive<'p>(&self, py: Python<'p>) -> PyResult<Bound<'p, PyAny>> {
         let transport = self.rx.clone();
         let dg = self.dg.clone();
+        let metrics = self.metrics.clone();
 
         future_into_py_futlike(self.rt.clone(), py, async move {
             if let Ok(mut stream) = transport.try_lock() {
@@ -263,7 +268,10 @@ impl RSGIWebsocketTransport {
                 } {
                     match recv {
                         Ok(Message::Ping(_) | Message::Pong(_)) => {}
-                        Ok(message) => return FutureResultToPy::RSGIWSMessage(message),
+                        Ok(message) => {
+                            metrics.received(&message);
+                            return FutureResultToPy::RSGIWSMessage(message);
+                        }
                         _ => break,
                     }
                 }
@@ -279,10 +287,11 @@ impl RSGIWebsocketTransport {
         }
 
         let transport = self.tx.clone();
-        let bdata: Box<[u8]> = data.into();
+        let message = Message::Binary(data.into_owned().into());
+        self.metrics.sent(&message);
         future_into_py_futlike(self.rt.clone(), py, async move {
             if let Some(stream) = &mut *(transport.lock().await) {
-                return match stream.send(bdata[..].into()).await {
+                return match stream.send(message).await {
                     Ok(()) => FutureResultToPy::None,
                     _ => FutureResultToPy::Err(error_stream!()),
                 };
@@ -297,9 +306,11 @@ impl RSGIWebsocketTransport {
         }
 
         let transport = self.tx.clone();
+        let message = Message::Text(data.into());
+        self.metrics.sent(&message);
         future_into_py_futlike(self.rt.clone(), py, async move {
             if let Some(stream) = &mut *(transport.lock().await) {
-                return match stream.send(data.into()).await {
+                return match stream.send(message).await {
                     Ok(()) => FutureResultToPy::None,
                     _ => FutureResultToPy::Err(error_stream!()),
                 };
@@ -318,6 +329,7 @@ pub(crate) struct RSGIWebsocketProtocol {
     upgrade: RwLock<Option<UpgradeData>>,
     closed: Arc<atomic::AtomicBool>,
     transport: Arc<AsyncMutex<Option<WSTxStream>>>,
+    metrics: WebsocketMetrics,
 }
 
 impl RSGIWebsocketProtocol {
@@ -327,6 +339,7 @@ impl RSGIWebsocketProtocol {
         websocket: HyperWebsocket,
         upgrade: UpgradeData,
         disconnect_guard: Arc<Notify>,
+        metrics: WebsocketMetrics,
     ) -> Self {
         Self {
             rt,
@@ -336,6 +349,7 @@ impl RSGIWebsocketProtocol {
             upgrade: RwLock::new(Some(upgrade)),
             closed: Arc::new(false.into()),
             transport: Arc::new(AsyncMutex::new(None)),
+            metrics,
         }
     }
 
@@ -352,13 +366,20 @@ impl RSGIWebsocketProtocol {
             self.closed.store(true, atomic::Ordering::Release);
             let transport = self.transport.clone();
             let consumed = self.consumed();
+            let metrics = self.metrics.clone();
+            if !consumed {
+                metrics.rejected();
+            }
 
             self.rt.spawn(async move {
                 let mut handle = None;
                 let mut transport = transport.lock().await;
                 if let Some(transport) = transport.take() {
+                    // the stream gets closed with no status code
+                    metrics.sent(&Message::Close(None));
                     handle = Some(transport);
                 }
+                metrics.finished();
 
                 let _ = tx.send((status.unwrap_or(0), consumed, handle));
             });
@@ -372,6 +393,7 @@ impl RSGIWebsocketProtocol {
         let closed = self.closed.clone();
         let transport = self.websocket.clone();
         let itransport = self.transport.clone();
+        let metrics = self.metrics.clone();
 
         future_into_py_futlike(self.rt.clone(), py, async move {
             let mut ws = transport.lock().await;
@@ -383,7 +405,10 @@ impl RSGIWebsocketProtocol {
                             let mut guard = itransport.lock().await;
                             *guard = Some(stx);
                         }
-                        FutureResultToPy::RSGIWSAccept(RSGIWebsocketTransport::new(rth, dg, itransport, srx, closed))
+                        metrics.accepted();
+                        FutureResultToPy::RSGIWSAccept(RSGIWebsocketTransport::new(
+                            rth, dg, itransport, srx, closed, metrics,
+                        ))
                     }
                     _ => FutureResultToPy::Err(error_proto!()),
                 },
@@ -3,7 +3,7 @@ use pyo3::prelude::*;
 mod callbacks;
 pub(crate) mod conversion;
 mod errors;
-mod http;
+pub(crate) mod http;
 pub(crate) mod io;
 pub(crate) mod serve;
 mod types;
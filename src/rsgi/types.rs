@@ -3,9 +3,9 @@ use futures::TryStreamExt;
 use http_body_util::BodyExt;
 use hyper::{Method, Uri, Version, body::Bytes, header::HeaderMap, http::uri::Authority};
 use percent_encoding::percent_decode_str;
-use pyo3::types::{PyBytes, PyIterator, PyList, PyString};
+use pyo3::types::{PyBytes, PyDict, PyIterator, PyList, PyString};
 use pyo3::{prelude::*, pybacked::PyBackedStr};
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
 
@@ -13,6 +13,7 @@ use crate::{
     conversion::headers_from_py,
     http::{HTTPProto, HTTPResponseBody, empty_body, response_404, response_500},
     net::SockAddr,
+    tls::TlsInfo,
 };
 
 const RSGI_PROTO_VERSION: &str = "1.6";
@@ -114,6 +115,7 @@ macro_rules! rsgi_scope_cls {
             client: SockAddr,
             #[pyo3(get)]
             headers: RSGIHeaders,
+            tls: Option<Arc<TlsInfo>>,
         }
 
         impl $name {
@@ -125,6 +127,7 @@ macro_rules! rsgi_scope_cls {
                 server: SockAddr,
                 client: SockAddr,
                 headers: HeaderMap,
+                tls: Option<Arc<TlsInfo>>,
             ) -> Self {
                 Self {
                     http_version,
@@ -134,6 +137,7 @@ macro_rules! rsgi_scope_cls {
                     server,
                     client,
                     headers: RSGIHeaders::new(headers),
+                    tls,
                 }
             }
         }
@@ -171,6 +175,27 @@ macro_rules! rsgi_scope_cls {
                 self.client.to_string()
             }
 
+            #[getter(peer_cred)]
+            fn get_peer_cred(&self) -> Option<(Option<i32>, u32, u32)> {
+                self.client.peer_cred().map(|cred| (cred.pid, cred.uid, cred.gid))
+            }
+
+            #[getter(tls)]
+            fn get_tls<'p>(&self, py: Python<'p>) -> PyResult<Option<Bound<'p, PyDict>>> {
+                let Some(tls) = &self.tls else {
+                    return Ok(None);
+                };
+                let rv = PyDict::new(py);
+                rv.set_item("version", tls.version.map(|(_, name)| name))?;
+                rv.set_item("cipher", tls.cipher.as_ref().map(|(_, name)| name))?;
+                rv.set_item("alpn", &tls.alpn)?;
+                rv.set_item("sni", &tls.sni)?;
+                rv.set_item("client_cert_chain", tls.client_cert_chain())?;
+                rv.set_item("client_cert_name", tls.client_cert_name())?;
+                rv.set_item("ktls", tls.ktls)?;
+                Ok(Some(rv))
+            }
+
             #[getter(scheme)]
             fn get_scheme(&self) -> &str {
                 self.scheme.as_str()
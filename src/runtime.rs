@@ -182,21 +182,73 @@ fn default_runtime(blocking_threads: usize) -> tokio::runtime::Runtime {
         .unwrap()
 }
 
+#[cfg(target_os = "linux")]
+pub(crate) fn set_thread_affinity(cpus: &[usize]) {
+    if cpus.is_empty() {
+        return;
+    }
+    // `CPU_SET` panics on CPUs out of the set bounds
+    let (cpus_valid, cpus_invalid): (Vec<usize>, Vec<usize>) =
+        cpus.iter().copied().partition(|cpu| *cpu < libc::CPU_SETSIZE as usize);
+    if !cpus_invalid.is_empty() {
+        log::warn!("Ignoring CPUs out of range for affinity: {cpus_invalid:?}");
+    }
+    if cpus_valid.is_empty() {
+        return;
+    }
+    let res = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for cpu in cpus_valid {
+            libc::CPU_SET(cpu, &mut set);
+        }
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &raw const set)
+    };
+    if res != 0 {
+        log::warn!(
+            "Unable to set CPU affinity to {cpus:?}: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn set_thread_affinity(_cpus: &[usize]) {}
+
+// Pins the calling thread to a single CPU from the given ones, in round-robin.
+pub(crate) fn set_thread_affinity_rr(cpus: &[usize], idx: usize) {
+    if let Some(cpu) = cpus.get(idx % cpus.len().max(1)) {
+        set_thread_affinity(&[*cpu]);
+    }
+}
+
 pub(crate) fn init_runtime_mt(
     threads: usize,
     blocking_threads: usize,
     py_threads: usize,
     py_threads_idle_timeout: u64,
+    affinity: Vec<usize>,
     py_loop: Arc<Py<PyAny>>,
     metrics: Option<metrics::ArcWorkerMetrics>,
 ) -> RuntimeWrapper {
+    let mut builder = RuntimeBuilder::new_multi_thread();
+    builder
+        .worker_threads(threads)
+        .max_blocking_threads(blocking_threads)
+        .enable_all();
+    if !affinity.is_empty() {
+        // NOTE: the runtime workers are the first threads to be started,
+        //       blocking threads just inherit the affinity of the whole worker.
+        let started = std::sync::atomic::AtomicUsize::new(0);
+        builder.on_thread_start(move || {
+            let idx = started.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            if idx < threads {
+                set_thread_affinity_rr(&affinity, idx);
+            }
+        });
+    }
+
     RuntimeWrapper::with_runtime(
-        RuntimeBuilder::new_multi_thread()
-            .worker_threads(threads)
-            .max_blocking_threads(blocking_threads)
-            .enable_all()
-            .build()
-            .unwrap(),
+        builder.build().unwrap(),
         py_threads,
         py_threads_idle_timeout,
         py_loop,
@@ -34,6 +34,7 @@ macro_rules! serve_fn {
 
             let worker_id = cfg.id;
             log::info!("Started worker-{worker_id}");
+            crate::runtime::set_thread_affinity(&cfg.affinity.0);
 
             let listener = cfg.$listener_gen();
             let backpressure = cfg.backpressure;
@@ -45,6 +46,7 @@ macro_rules! serve_fn {
                     cfg.blocking_threads,
                     cfg.py_threads,
                     cfg.py_threads_idle_timeout,
+                    cfg.threads_affinity(),
                     rtpyloop,
                     metrics.1.clone(),
                 )
@@ -155,6 +157,7 @@ macro_rules! serve_fn {
 
             let worker_id = cfg.id;
             log::info!("Started worker-{worker_id}");
+            crate::runtime::set_thread_affinity(&cfg.affinity.0);
 
             let (stx, srx) = tokio::sync::watch::channel(false);
             let mut workers = vec![];
@@ -215,6 +218,7 @@ macro_rules! serve_fn {
                 let py_threads = cfg.py_threads;
                 let py_threads_idle_timeout = cfg.py_threads_idle_timeout;
                 let backpressure = cfg.backpressure;
+                let affinity = cfg.threads_affinity();
                 let metrics = metrics.clone();
                 let ctx = ctx.clone();
                 let acceptor = acceptor.clone();
@@ -224,6 +228,7 @@ macro_rules! serve_fn {
                 let srx = srx.clone();
 
                 workers.push(std::thread::spawn(move || {
+                    crate::runtime::set_thread_affinity_rr(&affinity, thread_id);
                     let rt = crate::runtime::init_runtime_st(
                         blocking_threads,
                         py_threads,
@@ -308,6 +313,7 @@ macro_rules! serve_fn {
 
             let worker_id = cfg.id;
             log::info!("Started worker-{worker_id}");
+            crate::runtime::set_thread_affinity(&cfg.affinity.0);
 
             let tcp_listener = cfg.$listener_gen();
             let blocking_threads = cfg.blocking_threads;
@@ -370,6 +376,9 @@ serve_fn!(mt serve_mt_uds, std::os::unix::net::UnixListener, uds_listener);
 serve_fn!(st serve_st_uds, std::os::unix::net::UnixListener, uds_listener);
 #[cfg(unix)]
 serve_fn!(fut serve_fut_uds, std::os::unix::net::UnixListener, uds_listener);
+serve_fn!(mt serve_mt_multi, Vec<crate::workers::WorkerListener>, multi_listeners);
+serve_fn!(st serve_st_multi, Vec<crate::workers::WorkerListener>, multi_listeners);
+serve_fn!(fut serve_fut_multi, Vec<crate::workers::WorkerListener>, multi_listeners);
 
 macro_rules! gen_serve_impl {
     ($sm:expr, $self:expr, $py:expr, $event_loop:expr, $signal:expr, $metrics:expr, $metrics_opt:expr, $ctx:expr, $acceptor:expr, $proto:expr, $target:expr) => {{
@@ -517,8 +526,8 @@ macro_rules! gen_serve_match_tls {
 }
 
 macro_rules! gen_serve_match_files {
-    ($sm:expr, $self:expr, $py:expr, $event_loop:expr, $signal:expr, $metrics:expr, $metrics_opt:expr, $callback:expr, $acceptor_plain:ident, $acceptor_tls:ident, $target:expr, $targetws:expr) => {{
-        match $self.config.static_files.is_some() {
+    ($sm:expr, $self:expr, $py:expr, $event_loop:expr, $signal:expr, $metrics:expr, $metrics_opt:expr, $callback:expr, $apps:expr, $acceptor_plain:ident, $acceptor_tls:ident, $target:expr, $targetws:expr) => {{
+        match $self.config.static_files.is_some() || $self.config.proxy.is_some() {
             false => crate::serve::gen_serve_match_tls!(
                 $sm,
                 $self,
@@ -527,7 +536,18 @@ macro_rules! gen_serve_match_files {
                 $signal,
                 $metrics,
                 $metrics_opt,
-                crate::workers::WorkerCTXBase::new($callback, $metrics.clone()),
+                crate::workers::WorkerCTXBase::new(
+                    $callback,
+                    $metrics.clone(),
+                    $self.config.error_pages.clone(),
+                    crate::apps::WorkerApps::new(
+                        &$self.config.vhosts,
+                        &$self.config.mounts,
+                        $self.config.websockets_enabled,
+                        $apps,
+                    ),
+                    $self.config.tls_client_rules(),
+                ),
                 $acceptor_plain,
                 $acceptor_tls,
                 $target,
@@ -541,7 +561,20 @@ macro_rules! gen_serve_match_files {
                 $signal,
                 $metrics,
                 $metrics_opt,
-                crate::workers::WorkerCTXFiles::new($callback, $metrics.clone(), $self.config.static_files.clone()),
+                crate::workers::WorkerCTXFiles::new(
+                    $callback,
+                    $metrics.clone(),
+                    $self.config.static_files.clone(),
+                    $self.config.error_pages.clone(),
+                    crate::apps::WorkerApps::new(
+                        &$self.config.vhosts,
+                        &$self.config.mounts,
+                        $self.config.websockets_enabled,
+                        $apps,
+                    ),
+                    crate::proxy::WorkerProxy::new($self.config.proxy.as_ref()),
+                    $self.config.tls_client_rules(),
+                ),
                 $acceptor_plain,
                 $acceptor_tls,
                 $target,
@@ -552,8 +585,8 @@ macro_rules! gen_serve_match_files {
 }
 
 macro_rules! gen_serve_match {
-    ($sm:expr, $acceptor_plain:ident, $acceptor_tls:ident, $self:expr, $py:expr, $callback:expr, $event_loop:expr, $signal:expr, $target:expr, $targetws:expr) => {{
-        let metrics_obj = std::sync::Arc::new(crate::metrics::WorkerMetrics::new());
+    ($sm:expr, $acceptor_plain:ident, $acceptor_tls:ident, $self:expr, $py:expr, $callback:expr, $apps:expr, $event_loop:expr, $signal:expr, $target:expr, $targetws:expr) => {{
+        let metrics_obj = std::sync::Arc::new(crate::metrics::WorkerMetrics::new(&$self.config.metrics.2));
         match $self.config.metrics.0.is_some() {
             false => crate::serve::gen_serve_match_files!(
                 $sm,
@@ -564,6 +597,7 @@ macro_rules! gen_serve_match {
                 (),
                 None,
                 $callback,
+                $apps,
                 $acceptor_plain,
                 $acceptor_tls,
                 $target,
@@ -578,6 +612,7 @@ macro_rules! gen_serve_match {
                 metrics_obj.clone(),
                 Some(metrics_obj.clone()),
                 $callback,
+                $apps,
                 $acceptor_plain,
                 $acceptor_tls,
                 $target,
@@ -0,0 +1,283 @@
+use anyhow::{Result, anyhow};
+use pyo3::{prelude::*, pybacked::PyBackedBytes, types::PyBytes};
+use ring::{
+    aead,
+    rand::{SecureRandom, SystemRandom},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tls_listener::rustls::rustls::server::ProducesTickets;
+
+// Keys are made of a 16 bytes name followed by a 32 bytes AES-256-GCM key.
+const TICKET_KEY_LEN: usize = 48;
+const TICKET_KEY_NAME_LEN: usize = 16;
+// number of generated keys kept around, so tickets issued with the previous key still decrypt
+const TICKET_KEYS_KEPT: usize = 2;
+
+struct TicketKey {
+    raw: Vec<u8>,
+    key: aead::LessSafeKey,
+}
+
+impl TicketKey {
+    fn new(raw: &[u8]) -> Result<Self> {
+        if raw.len() != TICKET_KEY_LEN {
+            return Err(anyhow!("ticket keys should be {TICKET_KEY_LEN} bytes long"));
+        }
+        let key = aead::UnboundKey::new(&aead::AES_256_GCM, &raw[TICKET_KEY_NAME_LEN..])
+            .map_err(|_| anyhow!("invalid ticket key"))?;
+        Ok(Self {
+            raw: raw.to_vec(),
+            key: aead::LessSafeKey::new(key),
+        })
+    }
+
+    fn name(&self) -> &[u8] {
+        &self.raw[..TICKET_KEY_NAME_LEN]
+    }
+}
+
+// Session tickets producer using externally provided keys: the first key encrypts new tickets,
+// while all of them are used to decrypt.
+pub(crate) struct Ticketer {
+    keys: RwLock<Vec<TicketKey>>,
+    lifetime: u32,
+    rng: SystemRandom,
+}
+
+impl Ticketer {
+    fn new(lifetime: u64) -> Self {
+        Self {
+            keys: RwLock::new(Vec::new()),
+            lifetime: lifetime.try_into().unwrap_or(u32::MAX),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    fn raw_keys(&self) -> Vec<Vec<u8>> {
+        self.keys.read().unwrap().iter().map(|key| key.raw.clone()).collect()
+    }
+
+    fn set_keys(&self, keys: &[impl AsRef<[u8]>]) -> Result<()> {
+        let keys = keys
+            .iter()
+            .map(|key| TicketKey::new(key.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        *self.keys.write().unwrap() = keys;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Ticketer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ticketer").field("lifetime", &self.lifetime).finish()
+    }
+}
+
+impl ProducesTickets for Ticketer {
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    fn lifetime(&self) -> u32 {
+        self.lifetime
+    }
+
+    fn encrypt(&self, plain: &[u8]) -> Option<Vec<u8>> {
+        let keys = self.keys.read().unwrap();
+        let key = keys.first()?;
+        let mut nonce = [0u8; aead::NONCE_LEN];
+        self.rng.fill(&mut nonce).ok()?;
+
+        let mut data = plain.to_vec();
+        key.key
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::from(key.name()),
+                &mut data,
+            )
+            .ok()?;
+
+        let mut rv = Vec::with_capacity(TICKET_KEY_NAME_LEN + aead::NONCE_LEN + data.len());
+        rv.extend_from_slice(key.name());
+        rv.extend_from_slice(&nonce);
+        rv.extend_from_slice(&data);
+        Some(rv)
+    }
+
+    fn decrypt(&self, cipher: &[u8]) -> Option<Vec<u8>> {
+        if cipher.len() < TICKET_KEY_NAME_LEN + aead::NONCE_LEN {
+            return None;
+        }
+        let (name, rest) = cipher.split_at(TICKET_KEY_NAME_LEN);
+        let (nonce, data) = rest.split_at(aead::NONCE_LEN);
+        let nonce = aead::Nonce::try_assume_unique_for_key(nonce).ok()?;
+
+        let keys = self.keys.read().unwrap();
+        let key = keys.iter().find(|key| key.name() == name)?;
+        let mut data = data.to_vec();
+        let plain = key.key.open_in_place(nonce, aead::Aad::from(name), &mut data).ok()?;
+        Some(plain.to_vec())
+    }
+}
+
+fn load_ticket_keys(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let data = fs::read(path)?;
+    if data.is_empty() || !data.len().is_multiple_of(TICKET_KEY_LEN) {
+        return Err(anyhow!(
+            "ticket keys file should contain one or more {TICKET_KEY_LEN} bytes keys"
+        ));
+    }
+    Ok(data.chunks(TICKET_KEY_LEN).map(<[u8]>::to_vec).collect())
+}
+
+#[pyclass(frozen, module = "granian._granian")]
+pub(crate) struct TLSTicketKeys {
+    pub ticketer: Arc<Ticketer>,
+    rotation: u64,
+    path: Option<PathBuf>,
+}
+
+impl TLSTicketKeys {
+    // Generates a new key, or reloads the keys file, returning whether the keys changed.
+    fn rotate(&self) -> Result<bool> {
+        let keys = match &self.path {
+            Some(path) => {
+                let keys = load_ticket_keys(path)?;
+                if keys == self.ticketer.raw_keys() {
+                    return Ok(false);
+                }
+                keys
+            }
+            None => {
+                let mut key = vec![0; TICKET_KEY_LEN];
+                self.ticketer
+                    .rng
+                    .fill(&mut key)
+                    .map_err(|_| anyhow!("unable to generate ticket key"))?;
+                let mut keys = self.ticketer.raw_keys();
+                keys.insert(0, key);
+                keys.truncate(TICKET_KEYS_KEPT);
+                keys
+            }
+        };
+        self.ticketer.set_keys(&keys)?;
+        Ok(true)
+    }
+}
+
+#[pymethods]
+impl TLSTicketKeys {
+    #[new]
+    #[pyo3(signature = (rotation, path=None))]
+    fn new(rotation: u64, path: Option<String>) -> PyResult<Self> {
+        let rv = Self {
+            ticketer: Arc::new(Ticketer::new(rotation)),
+            rotation,
+            path: path.map(Into::into),
+        };
+        rv.rotate()
+            .map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))?;
+        Ok(rv)
+    }
+
+    #[staticmethod]
+    fn from_keys(rotation: u64, keys: Vec<PyBackedBytes>) -> PyResult<Self> {
+        let rv = Self {
+            ticketer: Arc::new(Ticketer::new(rotation)),
+            rotation,
+            path: None,
+        };
+        rv.ticketer
+            .set_keys(&keys)
+            .map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))?;
+        Ok(rv)
+    }
+
+    fn keys<'p>(&self, py: Python<'p>) -> Vec<Bound<'p, PyBytes>> {
+        self.ticketer
+            .raw_keys()
+            .iter()
+            .map(|key| PyBytes::new(py, key))
+            .collect()
+    }
+
+    fn run(pyself: Py<Self>, sig: Py<crate::workers::WorkerSignal>, senders: Vec<Py<crate::ipc::IPCSenderHandle>>) {
+        let mut sig = sig.get().arx.lock().unwrap().clone().unwrap();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            rt.block_on(async {
+                let keys = pyself.get();
+                #[cfg(not(Py_GIL_DISABLED))]
+                let senders: Vec<_> = senders.iter().map(|sender| sender.get().to_sender()).collect();
+                loop {
+                    tokio::select! {
+                        () = tokio::time::sleep(Duration::from_secs(keys.rotation)) => {}
+                        _ = sig.changed() => break,
+                    }
+                    match keys.rotate() {
+                        Ok(true) => log::info!("TLS session ticket keys rotated"),
+                        Ok(false) => continue,
+                        Err(err) => {
+                            log::error!("Unable to rotate TLS session ticket keys: {err}");
+                            continue;
+                        }
+                    }
+                    #[cfg(not(Py_GIL_DISABLED))]
+                    for sender in &senders {
+                        let mut sender = sender.lock().await;
+                        let msg = crate::ipc::Message::TicketKeys(keys.ticketer.raw_keys());
+                        if let Err(err) = crate::ipc::write_msg(msg, &mut *sender).await {
+                            log::warn!("Unable to send TLS session ticket keys to worker: {err}");
+                        }
+                    }
+                }
+            });
+            Python::attach(|_| {
+                drop(pyself);
+                drop(senders);
+            });
+        });
+    }
+
+    #[cfg(not(Py_GIL_DISABLED))]
+    fn listen(pyself: Py<Self>, ipc: Py<crate::ipc::IPCReceiverHandle>) {
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            rt.block_on(async {
+                let keys = pyself.get();
+                let receiver = ipc.get().to_receiver();
+                let mut receiver = receiver.lock().await;
+                // the main process owns the other end of the pipe, we stop when it goes away
+                while let Ok(msg) = crate::ipc::read_msg(&mut *receiver).await {
+                    if let crate::ipc::Message::TicketKeys(data) = msg
+                        && let Err(err) = keys.ticketer.set_keys(&data)
+                    {
+                        log::warn!("Invalid TLS session ticket keys received: {err}");
+                    }
+                }
+            });
+            Python::attach(|_| {
+                drop(pyself);
+                drop(ipc);
+            });
+        });
+    }
+}
+
+pub(crate) fn init_pymodule(module: &Bound<PyModule>) -> PyResult<()> {
+    module.add_class::<TLSTicketKeys>()?;
+
+    Ok(())
+}
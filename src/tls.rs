@@ -1,22 +1,40 @@
 use anyhow::{Result, anyhow};
-use std::{fs, io, iter::Iterator, sync::Arc};
+use pyo3::prelude::*;
+use std::{
+    fs, io,
+    iter::Iterator,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex, OnceLock, RwLock},
+    task::{Context, Poll, ready},
+    time::{Duration, SystemTime},
+};
 use tls_listener::{
     TlsListener,
     rustls::{
         TlsAcceptor,
         rustls::{
-            SupportedProtocolVersion,
+            Error as TLSError, InconsistentKeys, ProtocolVersion, RootCertStore, SupportedProtocolVersion,
+            crypto::{CryptoProvider, ring::default_provider, ring::sign::any_supported_type},
             pki_types::{
                 CertificateDer as Certificate, CertificateRevocationListDer as CRL, PrivateKeyDer as PrivateKey,
                 pem::PemObject,
             },
-            server::ServerConfig,
+            server::{ClientHello, ResolvesServerCert, ServerConfig, ServerConnection, WebPkiClientVerifier},
+            sign::CertifiedKey,
             version as tls_version,
         },
     },
 };
 
-use crate::net::SockAddr;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{apps::HostPattern, net::SockAddr};
+
+// seconds between OCSP response file checks, when certificates reload is not enabled
+pub(crate) const OCSP_REFRESH_INTERVAL: u64 = 3600;
+// seconds between ACME certificate file checks, when certificates reload is not enabled
+pub(crate) const ACME_RELOAD_INTERVAL: u64 = 10;
 
 pub(crate) fn resolve_protocol_versions(min_version: &str) -> Vec<&'static SupportedProtocolVersion> {
     match min_version {
@@ -26,13 +44,243 @@ pub(crate) fn resolve_protocol_versions(min_version: &str) -> Vec<&'static Suppo
     }
 }
 
+// Cipher suites are named after the IANA registry, so TLS 1.3 ones are `TLS_*` rather than rustls' `TLS13_*`.
+fn cipher_suite_name(suite: tls_listener::rustls::rustls::CipherSuite) -> Option<String> {
+    suite.as_str().map(|name| name.replacen("TLS13_", "TLS_", 1))
+}
+
+fn supported_cipher_suites() -> Vec<String> {
+    default_provider()
+        .cipher_suites
+        .iter()
+        .filter_map(|suite| cipher_suite_name(suite.suite()))
+        .collect()
+}
+
+fn supported_kx_groups() -> Vec<&'static str> {
+    default_provider()
+        .kx_groups
+        .iter()
+        .filter_map(|group| group.name().as_str())
+        .collect()
+}
+
+// Restricts the default provider to the given cipher suites and key exchange groups, in the given
+// order of preference. Empty lists keep the defaults.
+pub(crate) fn resolve_crypto_provider(ciphers: &[String], kx_groups: &[String]) -> Result<CryptoProvider> {
+    let mut provider = default_provider();
+    if !ciphers.is_empty() {
+        provider.cipher_suites = ciphers
+            .iter()
+            .map(|name| {
+                provider
+                    .cipher_suites
+                    .iter()
+                    .find(|suite| cipher_suite_name(suite.suite()).is_some_and(|v| v.eq_ignore_ascii_case(name)))
+                    .copied()
+                    .ok_or_else(|| anyhow!("unsupported cipher suite {name}"))
+            })
+            .collect::<Result<_>>()?;
+    }
+    if !kx_groups.is_empty() {
+        provider.kx_groups = kx_groups
+            .iter()
+            .map(|name| {
+                provider
+                    .kx_groups
+                    .iter()
+                    .find(|group| group.name().as_str().is_some_and(|v| v.eq_ignore_ascii_case(name)))
+                    .copied()
+                    .ok_or_else(|| anyhow!("unsupported key exchange group {name}"))
+            })
+            .collect::<Result<_>>()?;
+    }
+    Ok(provider)
+}
+
+// Reads TLS records one at a time while corked, so rustls never buffers data past the handshake,
+// which would be lost when handing the connection to the kernel.
+pub(crate) struct CorkStream<IO> {
+    io: IO,
+    corked: bool,
+    header: [u8; 5],
+    header_len: usize,
+    record_left: usize,
+}
+
+impl<IO> CorkStream<IO> {
+    fn new(io: IO, corked: bool) -> Self {
+        Self {
+            io,
+            corked,
+            header: [0; 5],
+            header_len: 0,
+            record_left: 0,
+        }
+    }
+
+    fn uncork(&mut self) {
+        self.corked = false;
+    }
+
+    pub fn into_inner(self) -> IO {
+        self.io
+    }
+}
+
+impl<IO: AsyncRead + Unpin> AsyncRead for CorkStream<IO> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.corked {
+            return Pin::new(&mut this.io).poll_read(cx, buf);
+        }
+
+        let limit = match this.record_left {
+            0 => this.header.len() - this.header_len,
+            left => left,
+        };
+        let read = {
+            let limit = limit.min(buf.remaining());
+            let mut record_buf = ReadBuf::new(&mut buf.initialize_unfilled()[..limit]);
+            ready!(Pin::new(&mut this.io).poll_read(cx, &mut record_buf))?;
+            let read = record_buf.filled().len();
+            if this.record_left == 0 {
+                this.header[this.header_len..this.header_len + read].copy_from_slice(record_buf.filled());
+                this.header_len += read;
+                if this.header_len == this.header.len() {
+                    this.record_left = u16::from_be_bytes([this.header[3], this.header[4]]).into();
+                    this.header_len = 0;
+                }
+            } else {
+                this.record_left -= read;
+            }
+            read
+        };
+        buf.advance(read);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<IO: AsyncWrite + Unpin> AsyncWrite for CorkStream<IO> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+}
+
+pub(crate) enum TlsTcpStream {
+    Rustls(tls_listener::rustls::server::TlsStream<CorkStream<tokio::net::TcpStream>>),
+    #[cfg(target_os = "linux")]
+    Kernel(crate::ktls::KtlsStream),
+}
+
+macro_rules! tls_tcp_stream_delegate {
+    ($self:expr, $method:ident, $($args:expr),*) => {
+        match $self.get_mut() {
+            TlsTcpStream::Rustls(stream) => Pin::new(stream).$method($($args),*),
+            #[cfg(target_os = "linux")]
+            TlsTcpStream::Kernel(stream) => Pin::new(stream).$method($($args),*),
+        }
+    };
+}
+
+impl AsyncRead for TlsTcpStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        tls_tcp_stream_delegate!(self, poll_read, cx, buf)
+    }
+}
+
+impl AsyncWrite for TlsTcpStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        tls_tcp_stream_delegate!(self, poll_write, cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        tls_tcp_stream_delegate!(self, poll_write_vectored, cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Rustls(stream) => stream.is_write_vectored(),
+            #[cfg(target_os = "linux")]
+            Self::Kernel(stream) => stream.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tls_tcp_stream_delegate!(self, poll_flush, cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tls_tcp_stream_delegate!(self, poll_shutdown, cx)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct TlsTcpAcceptor {
+    acceptor: TlsAcceptor,
+    ktls: bool,
+}
+
+impl tls_listener::AsyncTls<tokio::net::TcpStream> for TlsTcpAcceptor {
+    type Stream = TlsTcpStream;
+    type Error = io::Error;
+    type AcceptFuture = Pin<Box<dyn Future<Output = io::Result<TlsTcpStream>> + Send>>;
+
+    fn accept(&self, stream: tokio::net::TcpStream) -> Self::AcceptFuture {
+        let acceptor = self.acceptor.clone();
+        let ktls = self.ktls;
+        Box::pin(async move {
+            let mut stream = acceptor.accept(CorkStream::new(stream, ktls)).await?;
+            stream.get_mut().0.uncork();
+            #[cfg(target_os = "linux")]
+            if ktls {
+                return crate::ktls::offload(stream);
+            }
+            Ok(TlsTcpStream::Rustls(stream))
+        })
+    }
+}
+
 pub(crate) fn tls_tcp_listener(
     config: Arc<ServerConfig>,
     tcp: std::net::TcpListener,
-) -> Result<(TlsListener<tokio::net::TcpListener, TlsAcceptor>, SockAddr)> {
+) -> Result<(TlsListener<tokio::net::TcpListener, TlsTcpAcceptor>, SockAddr)> {
     let tcp_listener = tokio::net::TcpListener::from_std(tcp).unwrap();
     let local_addr = tcp_listener.local_addr()?;
-    let listener = TlsListener::new(TlsAcceptor::from(config), tcp_listener);
+    // secrets extraction is enabled in the configuration only when kTLS is requested
+    #[cfg(target_os = "linux")]
+    let ktls = config.enable_secret_extraction && crate::ktls::supported();
+    #[cfg(not(target_os = "linux"))]
+    let ktls = false;
+    let acceptor = TlsTcpAcceptor {
+        acceptor: TlsAcceptor::from(config),
+        ktls,
+    };
+    let listener = TlsListener::new(acceptor, tcp_listener);
     Ok((listener, SockAddr::TCP(local_addr)))
 }
 
@@ -44,47 +292,475 @@ pub(crate) fn tls_uds_listener(
     let uds_listener = tokio::net::UnixListener::from_std(uds).unwrap();
     let local_addr = uds_listener.local_addr()?;
     let listener = TlsListener::new(TlsAcceptor::from(config), uds_listener);
-    Ok((listener, SockAddr::UDS(local_addr)))
+    Ok((listener, SockAddr::uds(local_addr)))
 }
 
-pub(crate) fn load_certs(filename: String) -> Vec<Certificate<'static>> {
+pub(crate) fn load_certs(filename: String) -> Result<Vec<Certificate<'static>>> {
     Certificate::pem_file_iter(filename)
-        .expect("cannot open certificate file")
-        .map(|result| result.unwrap())
-        .collect()
+        .map_err(|_| anyhow!("cannot open certificate file"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow!("invalid certificate"))
 }
 
-pub(crate) fn load_crls(filenames: impl Iterator<Item = impl AsRef<std::path::Path>>) -> Vec<CRL<'static>> {
+pub(crate) fn load_crls(filenames: impl Iterator<Item = impl AsRef<std::path::Path>>) -> Result<Vec<CRL<'static>>> {
     filenames
-        .map(|filename| CRL::from_pem_file(filename).expect("cannot read CRL file"))
+        .map(|filename| {
+            let filename = filename.as_ref();
+            CRL::from_pem_file(filename).map_err(|_| anyhow!("cannot read CRL file {}", filename.display()))
+        })
         .collect()
 }
 
-pub(crate) fn load_private_key(filename: String, password: Option<String>) -> PrivateKey<'static> {
+pub(crate) fn load_private_key(filename: String, password: Option<String>) -> Result<PrivateKey<'static>> {
     match &password {
         Some(pwd) => {
             let expected_tag = "ENCRYPTED PRIVATE KEY";
-            let content = fs::read(filename).expect("cannot load key");
-            let sections = pem::parse_many(content).expect("invalid key");
-            let mut iter = sections
+            let content = fs::read(filename).map_err(|_| anyhow!("cannot load key"))?;
+            let sections = pem::parse_many(content).map_err(|_| anyhow!("invalid key"))?;
+            let section = sections
                 .into_iter()
-                .filter(|v| v.tag() == expected_tag)
-                .map(|v| v.contents().to_vec());
-            let key = pkcs8::EncryptedPrivateKeyInfo::<Vec<u8>>::try_from(
-                iter.next()
-                    .map_or_else(|| Err(anyhow!("Invalid key")), Ok)
-                    .expect("invalid key")
-                    .as_slice(),
-            )
-            .expect("Invalid key")
-            .decrypt(pwd)
-            .expect("Cannot decrypt key");
-            PrivateKey::Pkcs8(key.to_bytes().to_vec().into())
+                .find(|v| v.tag() == expected_tag)
+                .ok_or_else(|| anyhow!("no encrypted key found, while a key password was given"))?;
+            let key = pkcs8::EncryptedPrivateKeyInfo::<Vec<u8>>::try_from(section.contents())
+                .map_err(|_| anyhow!("invalid key"))?
+                .decrypt(pwd)
+                .map_err(|_| anyhow!("cannot decrypt key"))?;
+            Ok(PrivateKey::Pkcs8(key.to_bytes().to_vec().into()))
         }
         None => rustls_pemfile::private_key(&mut io::BufReader::new(
-            fs::File::open(filename).expect("cannot load key"),
+            fs::File::open(filename).map_err(|_| anyhow!("cannot load key"))?,
         ))
-        .expect("invalid key")
-        .unwrap(),
+        .map_err(|_| anyhow!("invalid key"))?
+        .ok_or_else(|| anyhow!("no supported key found (encrypted keys require a password)")),
+    }
+}
+
+// Loads the first private key found in a PKCS#12 bundle, along with its certificates chain.
+pub(crate) fn load_pkcs12(
+    filename: &str,
+    password: Option<&str>,
+) -> Result<(Vec<Certificate<'static>>, PrivateKey<'static>)> {
+    let content = fs::read(filename).map_err(|_| anyhow!("cannot load PKCS#12 file"))?;
+    let store = p12_keystore::KeyStore::from_pkcs12(&content, password.unwrap_or_default())
+        .map_err(|_| anyhow!("invalid PKCS#12 file or password"))?;
+    let (_, chain) = store
+        .private_key_chain()
+        .ok_or_else(|| anyhow!("no private key found in PKCS#12 file"))?;
+    let certs = chain
+        .chain()
+        .iter()
+        .map(|cert| Certificate::from(cert.as_der().to_vec()))
+        .collect();
+    Ok((certs, PrivateKey::Pkcs8(chain.key().to_vec().into())))
+}
+
+fn load_certified_key(cert: &str, key: &(String, Option<String>), pkcs12: bool) -> Result<CertifiedKey> {
+    let (certs, key) = match pkcs12 {
+        true => load_pkcs12(cert, key.1.as_deref())?,
+        false => (
+            load_certs(cert.to_owned())?,
+            load_private_key(key.0.clone(), key.1.clone())?,
+        ),
+    };
+    if certs.is_empty() {
+        return Err(anyhow!("no certificates found"));
+    }
+    let signing_key = any_supported_type(&key).map_err(|_| anyhow!("unsupported key type"))?;
+    let certified_key = CertifiedKey::new(certs, signing_key);
+    if let Err(TLSError::InconsistentKeys(InconsistentKeys::KeyMismatch)) = certified_key.keys_match() {
+        return Err(anyhow!("the key doesn't match the certificate"));
+    }
+    Ok(certified_key)
+}
+
+fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH).map_or(0, |v| v.as_secs())
+}
+
+// Checks the chain is in order (each certificate being issued by the following one),
+// and all the certificates are currently valid.
+fn check_cert_chain(certs: &[Certificate]) -> Result<()> {
+    for (idx, pair) in certs.windows(2).enumerate() {
+        let (issuer, _) = crate::der::cert_names(&pair[0])?;
+        let (_, subject) = crate::der::cert_names(&pair[1])?;
+        if issuer != subject {
+            return Err(anyhow!(
+                "certificates are not in order: certificate #{} is issued by {}, but it's followed by {}",
+                idx + 1,
+                crate::der::format_name(issuer)?,
+                crate::der::format_name(subject)?
+            ));
+        }
     }
+    let now = SystemTime::now();
+    for cert in certs {
+        let (not_before, not_after) = crate::der::cert_validity(cert)?;
+        if now < not_before {
+            return Err(anyhow!(
+                "certificate {} is not valid yet",
+                crate::der::cert_subject(cert)?
+            ));
+        }
+        if now > not_after {
+            return Err(anyhow!("certificate {} is expired", crate::der::cert_subject(cert)?));
+        }
+    }
+    Ok(())
+}
+
+// Returns the expiration time (as a UNIX timestamp) of the first certificate in the given PEM file.
+pub(crate) fn cert_expiry(cert: &str) -> Result<u64> {
+    let certs = load_certs(cert.to_owned())?;
+    let cert = certs.first().ok_or_else(|| anyhow!("no certificates found"))?;
+    Ok(unix_timestamp(crate::der::cert_validity(cert)?.1))
+}
+
+// Validates the certificate and key the same way workers load them, returning the certificate
+// subject and expiration time (as a UNIX timestamp).
+#[pyfunction]
+#[pyo3(signature = (cert, key, password=None, pkcs12=false))]
+fn tls_validate_cert(cert: String, key: String, password: Option<String>, pkcs12: bool) -> PyResult<(String, u64)> {
+    let validate = || -> Result<(String, u64)> {
+        let certified_key = load_certified_key(&cert, &(key, password), pkcs12)?;
+        check_cert_chain(&certified_key.cert)?;
+        let cert = &certified_key.cert[0];
+        Ok((
+            crate::der::cert_subject(cert)?,
+            unix_timestamp(crate::der::cert_validity(cert)?.1),
+        ))
+    };
+    validate().map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))
+}
+
+// Validates the CA certificates and CRLs used to verify clients.
+#[pyfunction]
+fn tls_validate_client_auth(ca: String, crls: Vec<String>) -> PyResult<()> {
+    let validate = || -> Result<()> {
+        let cas = load_certs(ca)?;
+        if cas.is_empty() {
+            return Err(anyhow!("no CA certificates found"));
+        }
+        let mut roots = RootCertStore::empty();
+        for cert in cas {
+            roots
+                .add(cert)
+                .map_err(|err| anyhow!("invalid CA certificate: {err}"))?;
+        }
+        WebPkiClientVerifier::builder(roots.into())
+            .with_crls(load_crls(crls.iter())?)
+            .build()
+            .map_err(|err| anyhow!("invalid CRL: {err}"))?;
+        Ok(())
+    };
+    validate().map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))
+}
+
+// The response should refer to the leaf certificate: the issuer is the next one in the chain,
+// or the leaf itself when self-signed.
+fn load_ocsp_response(path: &str, key: &CertifiedKey) -> Result<(Vec<u8>, Option<SystemTime>)> {
+    let response = fs::read(path).map_err(|_| anyhow!("cannot open OCSP response file"))?;
+    let (cert, issuer) = match key.cert.as_slice() {
+        [cert, issuer, ..] => (cert, issuer),
+        [cert] if crate::der::cert_names(cert).is_ok_and(|(issuer, subject)| issuer == subject) => (cert, cert),
+        _ => return Err(anyhow!("the certificate chain doesn't include the issuer certificate")),
+    };
+    let next_update = crate::ocsp::next_update(&response, cert, issuer)?;
+    Ok((response, next_update))
+}
+
+#[derive(Debug)]
+struct StapledCert {
+    key: Arc<CertifiedKey>,
+    // the certified key with the OCSP response attached, along with the response `nextUpdate`
+    stapled: Option<(Arc<CertifiedKey>, Option<SystemTime>)>,
+}
+
+impl StapledCert {
+    // NOTE: OCSP errors never prevent serving the certificate, we just skip stapling.
+    fn new(key: CertifiedKey, ocsp: Option<&str>) -> Self {
+        let stapled = ocsp.and_then(|path| match load_ocsp_response(path, &key) {
+            Ok((response, next_update)) => {
+                let mut stapled = key.clone();
+                stapled.ocsp = Some(response);
+                Some((Arc::new(stapled), next_update))
+            }
+            Err(err) => {
+                log::error!("Unable to load OCSP response from {path}: {err}");
+                None
+            }
+        });
+        Self {
+            key: Arc::new(key),
+            stapled,
+        }
+    }
+
+    fn get(&self) -> Arc<CertifiedKey> {
+        match &self.stapled {
+            Some((stapled, next_update)) if next_update.is_none_or(|v| SystemTime::now() < v) => stapled.clone(),
+            _ => self.key.clone(),
+        }
+    }
+}
+
+// Serves the certificate loaded from files, swapping it for new handshakes when the files change.
+// Existing connections are not affected by the swap.
+// When an OCSP response file is given, the response gets stapled until its `nextUpdate` is reached.
+#[derive(Debug)]
+pub(crate) struct ReloadableCertResolver {
+    cert: String,
+    key: (String, Option<String>),
+    pkcs12: bool,
+    ocsp: Option<String>,
+    current: RwLock<Option<Arc<StapledCert>>>,
+    mtimes: Mutex<(Option<SystemTime>, Option<SystemTime>, Option<SystemTime>)>,
+}
+
+impl ReloadableCertResolver {
+    pub fn new(cert: String, key: (String, Option<String>), pkcs12: bool, ocsp: Option<String>) -> Result<Self> {
+        let mtimes = Self::mtimes(&cert, &key.0, ocsp.as_deref());
+        let current = StapledCert::new(load_certified_key(&cert, &key, pkcs12)?, ocsp.as_deref());
+        Ok(Self {
+            cert,
+            key,
+            pkcs12,
+            ocsp,
+            current: RwLock::new(Some(Arc::new(current))),
+            mtimes: Mutex::new(mtimes),
+        })
+    }
+
+    // Builds a resolver with no certificate, waiting for the files to be created.
+    pub fn pending(cert: String, key: (String, Option<String>)) -> Self {
+        Self {
+            cert,
+            key,
+            pkcs12: false,
+            ocsp: None,
+            current: RwLock::new(None),
+            mtimes: Mutex::new((None, None, None)),
+        }
+    }
+
+    fn mtimes(
+        cert: &str,
+        key: &str,
+        ocsp: Option<&str>,
+    ) -> (Option<SystemTime>, Option<SystemTime>, Option<SystemTime>) {
+        let mtime = |path: &str| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        (mtime(cert), mtime(key), ocsp.and_then(mtime))
+    }
+
+    // NOTE: on errors we keep the current certificate, and retry only on the next files change,
+    //       eg: when the key gets written after the certificate.
+    pub fn reload(&self) {
+        let mtimes = Self::mtimes(&self.cert, &self.key.0, self.ocsp.as_deref());
+        let prev = {
+            let mut guard = self.mtimes.lock().unwrap();
+            if *guard == mtimes {
+                return;
+            }
+            std::mem::replace(&mut *guard, mtimes)
+        };
+
+        if let Some(current) = self.current()
+            && (prev.0, prev.1) == (mtimes.0, mtimes.1)
+        {
+            let key = (*current.key).clone();
+            *self.current.write().unwrap() = Some(Arc::new(StapledCert::new(key, self.ocsp.as_deref())));
+            log::info!("Reloaded OCSP response for TLS certificate {}", self.cert);
+            return;
+        }
+
+        match load_certified_key(&self.cert, &self.key, self.pkcs12) {
+            Ok(certified_key) => {
+                let current = StapledCert::new(certified_key, self.ocsp.as_deref());
+                *self.current.write().unwrap() = Some(Arc::new(current));
+                log::info!("Reloaded TLS certificate from {}", self.cert);
+            }
+            Err(err) => log::error!("Unable to reload TLS certificate from {}: {err}", self.cert),
+        }
+    }
+
+    fn current(&self) -> Option<Arc<StapledCert>> {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn certified_key(&self) -> Option<Arc<CertifiedKey>> {
+        self.current().map(|current| current.get())
+    }
+
+    pub fn watch(resolver: &Arc<Self>, interval: Duration) {
+        let resolver = Arc::downgrade(resolver);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+                let Some(resolver) = resolver.upgrade() else {
+                    break;
+                };
+                resolver.reload();
+            }
+        });
+    }
+}
+
+impl ResolvesServerCert for ReloadableCertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        self.certified_key()
+    }
+}
+
+// Picks the certificate matching the SNI server name (using the same rules of virtual hosts),
+// falling back to the default one when no name matches or the client sent no SNI.
+pub(crate) struct SniCertResolver {
+    default: Arc<ReloadableCertResolver>,
+    certs: Vec<(Vec<HostPattern>, Arc<ReloadableCertResolver>)>,
+}
+
+impl SniCertResolver {
+    pub fn new(default: Arc<ReloadableCertResolver>, certs: Vec<(Vec<String>, Arc<ReloadableCertResolver>)>) -> Self {
+        let certs = certs
+            .into_iter()
+            .map(|(hosts, resolver)| (hosts.iter().map(|v| HostPattern::new(v)).collect(), resolver))
+            .collect();
+        Self { default, certs }
+    }
+}
+
+impl std::fmt::Debug for SniCertResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SniCertResolver").finish_non_exhaustive()
+    }
+}
+
+impl ResolvesServerCert for SniCertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let resolver = client_hello
+            .server_name()
+            .and_then(|name| {
+                self.certs
+                    .iter()
+                    .find(|(patterns, _)| patterns.iter().any(|pattern| pattern.matches(name)))
+            })
+            .map_or(&self.default, |(_, resolver)| resolver);
+        resolver.certified_key()
+    }
+}
+
+// Answers ACME `tls-alpn-01` challenges using the certificates stored by the main process,
+// serving the issued certificate to any other client.
+#[derive(Debug)]
+pub(crate) struct ACMECertResolver {
+    inner: Arc<ReloadableCertResolver>,
+    challenges: PathBuf,
+}
+
+impl ACMECertResolver {
+    pub fn new(inner: Arc<ReloadableCertResolver>, challenges: PathBuf) -> Self {
+        Self { inner, challenges }
+    }
+}
+
+impl ResolvesServerCert for ACMECertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        if client_hello
+            .alpn()
+            .is_some_and(|mut protos| protos.any(|proto| proto == crate::acme::ACME_TLS_ALPN_PROTOCOL))
+        {
+            let path = self.challenges.join(format!("{}.pem", client_hello.server_name()?));
+            let path = path.to_str()?;
+            return match load_certified_key(path, &(path.to_owned(), None), false) {
+                Ok(certified_key) => Some(Arc::new(certified_key)),
+                Err(err) => {
+                    log::warn!("Unable to load ACME challenge certificate {path}: {err}");
+                    None
+                }
+            };
+        }
+        self.inner.certified_key()
+    }
+}
+
+// Negotiated parameters of a TLS connection, exposed to applications.
+pub(crate) struct TlsInfo {
+    pub version: Option<(u16, &'static str)>,
+    pub cipher: Option<(u16, String)>,
+    pub alpn: Option<String>,
+    pub sni: Option<String>,
+    pub client_certs: Vec<Certificate<'static>>,
+    // whether the connection encryption got offloaded to the kernel
+    pub ktls: bool,
+    client_identity: OnceLock<Option<crate::access::ClientCertIdentity>>,
+}
+
+impl TlsInfo {
+    pub fn new(conn: &ServerConnection, ktls: bool) -> Self {
+        let version = conn.protocol_version().map(|version| {
+            let name = match version {
+                ProtocolVersion::TLSv1_2 => "TLSv1.2",
+                ProtocolVersion::TLSv1_3 => "TLSv1.3",
+                _ => "",
+            };
+            (u16::from(version), name)
+        });
+        // we report IANA names, while rustls prefixes TLS 1.3 suites with `TLS13_`
+        let cipher = conn.negotiated_cipher_suite().map(|suite| {
+            let suite = suite.suite();
+            let name = suite.as_str().unwrap_or_default();
+            (
+                u16::from(suite),
+                name.strip_prefix("TLS13_")
+                    .map_or_else(|| name.to_owned(), |name| format!("TLS_{name}")),
+            )
+        });
+        Self {
+            version,
+            cipher,
+            alpn: conn.alpn_protocol().map(|v| String::from_utf8_lossy(v).into_owned()),
+            sni: conn.server_name().map(ToOwned::to_owned),
+            client_certs: conn.peer_certificates().map(<[_]>::to_vec).unwrap_or_default(),
+            ktls,
+            client_identity: OnceLock::new(),
+        }
+    }
+
+    // Parsed on first use, as it's only needed by client certificate access rules.
+    pub fn client_identity(&self) -> Option<&crate::access::ClientCertIdentity> {
+        self.client_identity
+            .get_or_init(|| {
+                self.client_certs
+                    .first()
+                    .and_then(|cert| crate::access::ClientCertIdentity::new(cert).ok())
+            })
+            .as_ref()
+    }
+
+    // PEM encoded client certificates, starting from the leaf one.
+    pub fn client_cert_chain(&self) -> Vec<String> {
+        self.client_certs
+            .iter()
+            .map(|cert| {
+                pem::encode_config(
+                    &pem::Pem::new("CERTIFICATE", cert.as_ref()),
+                    pem::EncodeConfig::new().set_line_ending(pem::LineEnding::LF),
+                )
+            })
+            .collect()
+    }
+
+    // RFC 4514 subject of the client certificate.
+    pub fn client_cert_name(&self) -> Option<String> {
+        self.client_certs
+            .first()
+            .and_then(|cert| crate::der::cert_subject(cert).ok())
+    }
+}
+
+pub(crate) fn init_pymodule(module: &Bound<PyModule>) -> PyResult<()> {
+    module.add("TLS_CIPHER_SUITES", supported_cipher_suites())?;
+    module.add("TLS_KX_GROUPS", supported_kx_groups())?;
+    module.add_function(wrap_pyfunction!(tls_validate_cert, module)?)?;
+    module.add_function(wrap_pyfunction!(tls_validate_client_auth, module)?)?;
+
+    Ok(())
 }
@@ -9,8 +9,9 @@ use super::asgi::serve::ASGIWorker;
 use super::metrics;
 use super::rsgi::serve::RSGIWorker;
 use super::tls::{
-    load_certs as tls_load_certs, load_crls as tls_load_crls, load_private_key as tls_load_pkey,
-    resolve_protocol_versions,
+    ACME_RELOAD_INTERVAL, ACMECertResolver, OCSP_REFRESH_INTERVAL, ReloadableCertResolver, SniCertResolver,
+    load_certs as tls_load_certs, load_crls as tls_load_crls, load_pkcs12 as tls_load_pkcs12,
+    load_private_key as tls_load_pkey, resolve_crypto_provider, resolve_protocol_versions,
 };
 use super::wsgi::serve::WSGIWorker;
 
@@ -97,10 +98,24 @@ pub(crate) struct WorkerConfig {
     pub http2_opts: HTTP2Config,
     pub websockets_enabled: bool,
     pub static_files: Option<(Vec<(String, String)>, Option<String>, Option<String>)>,
+    pub error_pages: Option<Arc<crate::http::ErrorPages>>,
+    pub vhosts: Vec<(Vec<String>, Option<crate::apps::StaticFilesConfig>, String)>,
+    pub mounts: Vec<(String, String)>,
+    pub proxy: Option<crate::proxy::ProxyConfig>,
+    listeners: Vec<(
+        bool,
+        (
+            Option<Py<crate::net::ListenerSpec>>,
+            Option<Py<crate::net::SocketHolder>>,
+        ),
+    )>,
+    pub affinity: (Vec<usize>, bool),
     pub tls_opts: Option<WorkerTlsConfig>,
+    tls_cfg: Option<Arc<tls_listener::rustls::rustls::ServerConfig>>,
     pub metrics: (
         Option<std::time::Duration>,
         Option<Py<crate::metrics::MetricsAggregator>>,
+        Vec<f64>,
     ),
 }
 
@@ -108,10 +123,28 @@ pub(crate) struct WorkerConfig {
 pub(crate) struct WorkerTlsConfig {
     cert: String,
     key: (String, Option<String>),
+    pkcs12: bool,
     proto: String,
+    ciphers: Vec<String>,
+    kx_groups: Vec<String>,
     ca: Option<String>,
     crl: Vec<String>,
     client_verify: bool,
+    reload_interval: Option<u64>,
+    sni: Vec<(Vec<String>, String, (String, Option<String>), Option<String>)>,
+    ocsp: Option<String>,
+    acme: Option<String>,
+    session_cache: usize,
+    tickets: Option<Arc<crate::tickets::Ticketer>>,
+    ktls: bool,
+    client_rules: Vec<crate::access::ClientCertRuleConfig>,
+}
+
+impl WorkerTlsConfig {
+    // Certificates might change at runtime or depend on the client, unless stated otherwise.
+    fn static_cert(&self) -> bool {
+        self.reload_interval.is_none() && self.sni.is_empty() && self.ocsp.is_none() && self.acme.is_none()
+    }
 }
 
 impl WorkerConfig {
@@ -132,6 +165,18 @@ impl WorkerConfig {
         http2_opts: HTTP2Config,
         websockets_enabled: bool,
         static_files: Option<(Vec<(String, String)>, Option<String>, Option<String>)>,
+        error_pages: Option<String>,
+        vhosts: Vec<(Vec<String>, Option<crate::apps::StaticFilesConfig>, String)>,
+        mounts: Vec<(String, String)>,
+        proxy: Option<crate::proxy::ProxyConfig>,
+        listeners: Vec<(
+            bool,
+            (
+                Option<Py<crate::net::ListenerSpec>>,
+                Option<Py<crate::net::SocketHolder>>,
+            ),
+        )>,
+        affinity: (Vec<usize>, bool),
         ssl_enabled: bool,
         ssl_cert: Option<String>,
         ssl_key: Option<String>,
@@ -140,21 +185,56 @@ impl WorkerConfig {
         ssl_ca: Option<String>,
         ssl_crl: Vec<String>,
         ssl_client_verify: bool,
-        metrics: (Option<u64>, Option<Py<crate::metrics::MetricsAggregator>>),
-    ) -> Self {
+        ssl_reload_interval: Option<u64>,
+        ssl_sni_certs: Vec<(Vec<String>, String, String, Option<String>, Option<String>)>,
+        ssl_ocsp: Option<String>,
+        ssl_acme: Option<String>,
+        ssl_session_cache: usize,
+        ssl_ciphers: Vec<String>,
+        ssl_kx_groups: Vec<String>,
+        ssl_pkcs12: bool,
+        ssl_ktls: bool,
+        ssl_client_rules: Vec<crate::access::ClientCertRuleConfig>,
+        ssl_tickets: Option<Py<crate::tickets::TLSTicketKeys>>,
+        metrics: (Option<u64>, Option<Py<crate::metrics::MetricsAggregator>>, Vec<f64>),
+    ) -> PyResult<Self> {
         let tls_opts = match ssl_enabled {
             true => Some(WorkerTlsConfig {
                 cert: ssl_cert.unwrap(),
                 key: (ssl_key.unwrap(), ssl_key_password),
+                pkcs12: ssl_pkcs12,
                 proto: ssl_protocol_min.into(),
+                ciphers: ssl_ciphers,
+                kx_groups: ssl_kx_groups,
                 ca: ssl_ca,
                 crl: ssl_crl,
                 client_verify: ssl_client_verify,
+                reload_interval: ssl_reload_interval,
+                sni: ssl_sni_certs
+                    .into_iter()
+                    .map(|(hosts, cert, key, password, ocsp)| (hosts, cert, (key, password), ocsp))
+                    .collect(),
+                ocsp: ssl_ocsp,
+                acme: ssl_acme,
+                session_cache: ssl_session_cache,
+                tickets: ssl_tickets.map(|keys| keys.get().ticketer.clone()),
+                ktls: ssl_ktls,
+                client_rules: ssl_client_rules,
             }),
             false => None,
         };
-
-        Self {
+        // files might have changed since the main process validated them, so we fail instead of panicking
+        let tls_cfg = tls_opts
+            .as_ref()
+            .map(|opts| Self::build_tls_cfg(opts, http_mode).map(Arc::new))
+            .transpose()
+            .map_err(|err| pyo3::exceptions::PyValueError::new_err(format!("Invalid SSL configuration: {err}")))?;
+        let error_pages = error_pages
+            .map(|path| crate::http::ErrorPages::load(&path))
+            .filter(|pages| !pages.is_empty())
+            .map(Arc::new);
+
+        Ok(Self {
             id,
             sock,
             #[cfg(not(Py_GIL_DISABLED))]
@@ -169,72 +249,217 @@ impl WorkerConfig {
             http2_opts,
             websockets_enabled,
             static_files,
+            error_pages,
+            vhosts,
+            mounts,
+            proxy,
+            listeners,
+            affinity,
             tls_opts,
-            metrics: (metrics.0.map(std::time::Duration::from_secs), metrics.1),
-        }
+            tls_cfg,
+            metrics: (metrics.0.map(std::time::Duration::from_secs), metrics.1, metrics.2),
+        })
     }
 
-    pub fn tcp_listener(&self) -> std::net::TcpListener {
-        let listener = if let Some(sock) = &self.sock.1 {
+    fn sock_tcp_listener(
+        sock: &(
+            Option<Py<crate::net::ListenerSpec>>,
+            Option<Py<crate::net::SocketHolder>>,
+        ),
+    ) -> std::net::TcpListener {
+        let listener = if let Some(sock) = &sock.1 {
             sock.get().as_tcp_listener().unwrap()
         } else {
-            self.sock.0.as_ref().unwrap().get().as_listener().unwrap()
+            sock.0.as_ref().unwrap().get().as_listener().unwrap()
         };
         _ = listener.set_nonblocking(true);
         listener
     }
 
     #[cfg(unix)]
-    pub fn uds_listener(&self) -> std::os::unix::net::UnixListener {
-        let listener = self.sock.1.as_ref().unwrap().get().as_unix_listener().unwrap();
+    fn sock_uds_listener(
+        sock: &(
+            Option<Py<crate::net::ListenerSpec>>,
+            Option<Py<crate::net::SocketHolder>>,
+        ),
+    ) -> std::os::unix::net::UnixListener {
+        let listener = sock.1.as_ref().unwrap().get().as_unix_listener().unwrap();
         _ = listener.set_nonblocking(true);
         listener
     }
 
-    pub fn tls_cfg(&self) -> tls_listener::rustls::rustls::ServerConfig {
-        let opts = self.tls_opts.as_ref().unwrap();
+    fn sock_listener(
+        sock: &(
+            Option<Py<crate::net::ListenerSpec>>,
+            Option<Py<crate::net::SocketHolder>>,
+        ),
+        tls: bool,
+    ) -> WorkerListener {
+        #[cfg(unix)]
+        if sock.1.as_ref().is_some_and(|holder| holder.get().is_uds()) {
+            return WorkerListener::Uds(Self::sock_uds_listener(sock), tls);
+        }
+        WorkerListener::Tcp(Self::sock_tcp_listener(sock), tls)
+    }
+
+    // CPUs runtime threads get pinned to (round-robin), empty unless using per-thread affinity.
+    pub fn threads_affinity(&self) -> Vec<usize> {
+        if self.affinity.1 {
+            return self.affinity.0.clone();
+        }
+        vec![]
+    }
+
+    pub fn tcp_listener(&self) -> std::net::TcpListener {
+        Self::sock_tcp_listener(&self.sock)
+    }
+
+    #[cfg(unix)]
+    pub fn uds_listener(&self) -> std::os::unix::net::UnixListener {
+        Self::sock_uds_listener(&self.sock)
+    }
+
+    // The main socket first, then any additional listener.
+    pub fn multi_listeners(&self) -> Vec<WorkerListener> {
+        let mut listeners = Vec::with_capacity(self.listeners.len() + 1);
+        listeners.push(Self::sock_listener(&self.sock, self.tls_opts.is_some()));
+        for (tls, sock) in &self.listeners {
+            listeners.push(Self::sock_listener(sock, *tls));
+        }
+        listeners
+    }
+
+    fn tls_cert_resolver(
+        opts: &WorkerTlsConfig,
+    ) -> anyhow::Result<Arc<dyn tls_listener::rustls::rustls::server::ResolvesServerCert>> {
+        if let Some(storage) = &opts.acme {
+            return Ok(Arc::new(ACMECertResolver::new(
+                Self::tls_acme_resolver(opts),
+                std::path::Path::new(storage).join(crate::acme::ACME_TLS_ALPN_DIR),
+            )));
+        }
+        let default = Self::tls_reloadable_resolver(opts, &opts.cert, &opts.key, opts.pkcs12, opts.ocsp.as_ref())?;
+        if opts.sni.is_empty() {
+            return Ok(default);
+        }
+        Ok(Arc::new(SniCertResolver::new(
+            default,
+            opts.sni
+                .iter()
+                .map(|(hosts, cert, key, ocsp)| {
+                    Ok((
+                        hosts.clone(),
+                        Self::tls_reloadable_resolver(opts, cert, key, false, ocsp.as_ref())?,
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+        )))
+    }
+
+    // The ACME certificate might not be there yet, so we wait for the main process to store it.
+    fn tls_acme_resolver(opts: &WorkerTlsConfig) -> Arc<ReloadableCertResolver> {
+        let resolver = Arc::new(ReloadableCertResolver::pending(opts.cert.clone(), opts.key.clone()));
+        let interval = opts.reload_interval.unwrap_or(ACME_RELOAD_INTERVAL);
+        resolver.reload();
+        ReloadableCertResolver::watch(&resolver, std::time::Duration::from_secs(interval));
+        resolver
+    }
+
+    fn tls_reloadable_resolver(
+        opts: &WorkerTlsConfig,
+        cert: &str,
+        key: &(String, Option<String>),
+        pkcs12: bool,
+        ocsp: Option<&String>,
+    ) -> anyhow::Result<Arc<ReloadableCertResolver>> {
+        let resolver = Arc::new(
+            ReloadableCertResolver::new(cert.into(), key.clone(), pkcs12, ocsp.cloned())
+                .map_err(|err| anyhow::anyhow!("{cert}: {err}"))?,
+        );
+        // OCSP responses need to be refreshed even when certificates reload is disabled
+        let interval = opts.reload_interval.or_else(|| ocsp.map(|_| OCSP_REFRESH_INTERVAL));
+        if let Some(interval) = interval {
+            ReloadableCertResolver::watch(&resolver, std::time::Duration::from_secs(interval));
+        }
+        Ok(resolver)
+    }
+
+    pub fn tls_cfg(&self) -> Arc<tls_listener::rustls::rustls::ServerConfig> {
+        self.tls_cfg.clone().unwrap()
+    }
+
+    // Built once, so the resolver is shared among all the listeners and runtime threads of the worker.
+    fn build_tls_cfg(
+        opts: &WorkerTlsConfig,
+        http_mode: &str,
+    ) -> anyhow::Result<tls_listener::rustls::rustls::ServerConfig> {
         let tls_protos = resolve_protocol_versions(&opts.proto);
+        let provider = Arc::new(resolve_crypto_provider(&opts.ciphers, &opts.kx_groups)?);
 
         let cfg_builder = match &opts.ca {
             Some(ca) => {
-                let cas = tls_load_certs(ca.clone());
+                let cas = tls_load_certs(ca.clone()).map_err(|err| anyhow::anyhow!("{ca}: {err}"))?;
                 let mut client_auth_cas = tls_listener::rustls::rustls::RootCertStore::empty();
                 for cert in cas {
-                    client_auth_cas.add(cert).unwrap();
+                    client_auth_cas.add(cert)?;
                 }
-                let crls = tls_load_crls(opts.crl.iter());
+                let crls = tls_load_crls(opts.crl.iter())?;
                 let verifier = match opts.client_verify {
                     true => tls_listener::rustls::rustls::server::WebPkiClientVerifier::builder(client_auth_cas.into())
                         .with_crls(crls)
-                        .build()
-                        .unwrap(),
+                        .build()?,
                     false => {
                         tls_listener::rustls::rustls::server::WebPkiClientVerifier::builder(client_auth_cas.into())
                             .with_crls(crls)
                             .allow_unauthenticated()
-                            .build()
-                            .unwrap()
+                            .build()?
                     }
                 };
-                tls_listener::rustls::rustls::ServerConfig::builder_with_protocol_versions(&tls_protos)
+                tls_listener::rustls::rustls::ServerConfig::builder_with_provider(provider)
+                    .with_protocol_versions(&tls_protos)?
                     .with_client_cert_verifier(verifier)
             }
-            None => tls_listener::rustls::rustls::ServerConfig::builder_with_protocol_versions(&tls_protos)
+            None => tls_listener::rustls::rustls::ServerConfig::builder_with_provider(provider)
+                .with_protocol_versions(&tls_protos)?
                 .with_no_client_auth(),
         };
-        let mut cfg = cfg_builder
-            .with_single_cert(
-                tls_load_certs(opts.cert.clone()),
-                tls_load_pkey(opts.key.0.clone(), opts.key.1.clone()),
-            )
-            .unwrap();
-        cfg.alpn_protocols = match &self.http_mode[..] {
+        let mut cfg = if opts.static_cert() {
+            let (certs, key) = match opts.pkcs12 {
+                true => tls_load_pkcs12(&opts.cert, opts.key.1.as_deref())
+                    .map_err(|err| anyhow::anyhow!("{}: {err}", opts.cert))?,
+                false => (
+                    tls_load_certs(opts.cert.clone()).map_err(|err| anyhow::anyhow!("{}: {err}", opts.cert))?,
+                    tls_load_pkey(opts.key.0.clone(), opts.key.1.clone())
+                        .map_err(|err| anyhow::anyhow!("{}: {err}", opts.key.0))?,
+                ),
+            };
+            cfg_builder.with_single_cert(certs, key)?
+        } else {
+            cfg_builder.with_cert_resolver(Self::tls_cert_resolver(opts)?)
+        };
+        cfg.alpn_protocols = match http_mode {
             "1" => vec![b"http/1.1".to_vec()],
             "2" => vec![b"h2".to_vec()],
             _ => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
         };
+        if opts.acme.is_some() {
+            cfg.alpn_protocols.push(crate::acme::ACME_TLS_ALPN_PROTOCOL.to_vec());
+        }
+        cfg.session_storage = match opts.session_cache {
+            0 => Arc::new(tls_listener::rustls::rustls::server::NoServerSessionStorage {}),
+            size => tls_listener::rustls::rustls::server::ServerSessionMemoryCache::new(size),
+        };
+        if let Some(ticketer) = &opts.tickets {
+            cfg.ticketer = ticketer.clone();
+        }
+        // traffic secrets are needed to hand connections to the kernel
+        cfg.enable_secret_extraction = opts.ktls;
         cfg
     }
+
+    pub fn tls_client_rules(&self) -> Option<Arc<crate::access::ClientCertRules>> {
+        crate::access::ClientCertRules::new(&self.tls_opts.as_ref()?.client_rules)
+    }
 }
 
 struct WorkerMarkerPlain;
@@ -250,13 +475,25 @@ pub(crate) struct WorkerMarkerConnUpgrades;
 pub(crate) struct WorkerCTXBase<M> {
     pub callback: crate::callbacks::ArcCBScheduler,
     pub metrics: M,
+    pub error_pages: Option<Arc<crate::http::ErrorPages>>,
+    pub apps: Option<Arc<crate::apps::WorkerApps>>,
+    pub client_rules: Option<Arc<crate::access::ClientCertRules>>,
 }
 
 impl<M> WorkerCTXBase<M> {
-    pub fn new(callback: crate::callbacks::PyCBScheduler, metrics: M) -> Self {
+    pub fn new(
+        callback: crate::callbacks::PyCBScheduler,
+        metrics: M,
+        error_pages: Option<Arc<crate::http::ErrorPages>>,
+        apps: Option<Arc<crate::apps::WorkerApps>>,
+        client_rules: Option<Arc<crate::access::ClientCertRules>>,
+    ) -> Self {
         Self {
             callback: Arc::new(callback),
             metrics,
+            error_pages,
+            apps,
+            client_rules,
         }
     }
 }
@@ -268,6 +505,10 @@ pub(crate) struct WorkerCTXFiles<M> {
     pub static_mounts: Vec<(String, String)>,
     pub static_dir_to_file: Option<String>,
     pub static_expires: Option<String>,
+    pub error_pages: Option<Arc<crate::http::ErrorPages>>,
+    pub apps: Option<Arc<crate::apps::WorkerApps>>,
+    pub proxy: Option<Arc<crate::proxy::WorkerProxy>>,
+    pub client_rules: Option<Arc<crate::access::ClientCertRules>>,
 }
 
 impl<M> WorkerCTXFiles<M> {
@@ -275,14 +516,22 @@ impl<M> WorkerCTXFiles<M> {
         callback: crate::callbacks::PyCBScheduler,
         metrics: M,
         files: Option<(Vec<(String, String)>, Option<String>, Option<String>)>,
+        error_pages: Option<Arc<crate::http::ErrorPages>>,
+        apps: Option<Arc<crate::apps::WorkerApps>>,
+        proxy: Option<Arc<crate::proxy::WorkerProxy>>,
+        client_rules: Option<Arc<crate::access::ClientCertRules>>,
     ) -> Self {
-        let (static_mounts, static_dir_to_file, static_expires) = files.unwrap();
+        let (static_mounts, static_dir_to_file, static_expires) = files.unwrap_or_default();
         Self {
             callback: Arc::new(callback),
             metrics,
             static_mounts,
             static_dir_to_file,
             static_expires,
+            error_pages,
+            apps,
+            proxy,
+            client_rules,
         }
     }
 }
@@ -298,6 +547,28 @@ pub(crate) struct Worker<C, A, H, F, M> {
     metrics: M,
 }
 
+impl<C, A, H, F, M> Worker<C, A, H, F, M>
+where
+    C: Clone,
+    H: Clone,
+    F: Copy,
+    M: Clone,
+{
+    // Builds a worker sharing everything but the acceptor,
+    // used to run several listeners within the same runtime.
+    fn with_acceptor<B>(&self, acceptor: B) -> Worker<C, B, H, F, M> {
+        Worker {
+            ctx: self.ctx.clone(),
+            acceptor,
+            handler: self.handler.clone(),
+            rt: self.rt.clone(),
+            tasks: self.tasks.clone(),
+            target: self.target,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
 impl<C, A, H, F, M, Ret> Worker<C, A, H, F, M>
 where
     F: Fn(
@@ -333,24 +604,139 @@ struct WorkerSvc<F, C, P> {
     disconnect_guard: Arc<tokio::sync::Notify>,
     addr_local: crate::net::SockAddr,
     addr_remote: crate::net::SockAddr,
+    sni: Option<Arc<str>>,
+    tls: Option<Arc<crate::tls::TlsInfo>>,
+    conn: Option<Arc<metrics::ConnectionMetrics>>,
     _proto: PhantomData<P>,
 }
 
+type ServiceFuture = Pin<Box<dyn Future<Output = Result<crate::http::HTTPResponse, hyper::Error>> + Send>>;
+
+macro_rules! service_error_pages {
+    ($self:expr, $req:expr) => {
+        $self
+            .ctx
+            .error_pages
+            .as_ref()
+            .map(|pages| (pages.clone(), crate::http::header_accept($req.headers())))
+    };
+}
+
+macro_rules! service_error_pages_apply {
+    ($error_pages:expr, $res:expr) => {
+        match $error_pages {
+            Some((pages, accept)) => pages.apply(accept.as_ref(), $res),
+            None => $res,
+        }
+    };
+}
+
 macro_rules! service_proto_fut {
-    ($proto:expr, $self:expr, $req:expr) => {{
-        let fut = ($self.f)(
+    ($proto:expr, $self:expr, $req:expr, $callback:expr) => {
+        service_proto_fut!($proto, $self, $req, $callback, $self.f)
+    };
+    ($proto:expr, $self:expr, $req:expr, $callback:expr, $handler:expr) => {{
+        let error_pages = service_error_pages!($self, $req);
+        let mut req = $req;
+        if let Some(tls) = &$self.tls {
+            req.extensions_mut().insert(tls.clone());
+        }
+        let fut = ($handler)(
             $self.rt.clone(),
             $self.disconnect_guard.clone(),
-            $self.ctx.callback.clone(),
+            $callback,
             $self.addr_local.clone(),
             $self.addr_remote.clone(),
-            $req,
+            req,
             $proto,
         );
-        Box::pin(async move { Ok::<_, hyper::Error>(fut.await) })
+        Box::pin(async move { Ok::<_, hyper::Error>(service_error_pages_apply!(error_pages, fut.await)) })
     }};
 }
 
+macro_rules! service_static_fut {
+    ($self:expr, $req:expr, $static_match:expr, $expires:expr) => {{
+        let error_pages = service_error_pages!($self, $req);
+        if $static_match.is_err() {
+            let res = crate::http::response_404();
+            return Box::pin(async move { Ok::<_, hyper::Error>(service_error_pages_apply!(error_pages, res)) });
+        }
+        let expires = $expires;
+        return Box::pin(async move {
+            let res = crate::files::serve_static_file($static_match.unwrap(), expires).await;
+            Ok::<_, hyper::Error>(service_error_pages_apply!(error_pages, res))
+        });
+    }};
+}
+
+macro_rules! service_proxy_fut {
+    ($proto:expr, $self:expr, $req:expr) => {
+        if let Some(route) = $self
+            .ctx
+            .proxy
+            .as_ref()
+            .and_then(|proxy| proxy.match_route($req.uri().path()))
+        {
+            let error_pages = service_error_pages!($self, $req);
+            let fut = route.forward($req, &$self.addr_remote, &$proto);
+            return Box::pin(async move { Ok::<_, hyper::Error>(service_error_pages_apply!(error_pages, fut.await)) });
+        }
+    };
+}
+
+macro_rules! service_client_rules {
+    ($self:expr, $req:expr) => {
+        if let Some(rules) = &$self.ctx.client_rules
+            && !rules.allows($req.uri().path(), $self.tls.as_deref())
+        {
+            let error_pages = service_error_pages!($self, $req);
+            let res = crate::http::response_403();
+            return Box::pin(async move { Ok::<_, hyper::Error>(service_error_pages_apply!(error_pages, res)) });
+        }
+    };
+}
+
+macro_rules! service_apps {
+    ($proto:expr, $self:expr, $req:expr) => {
+        if let Some(app) = $self
+            .ctx
+            .apps
+            .as_ref()
+            .and_then(|apps| apps.resolve(&$req, $self.sni.as_deref()))
+        {
+            if let Some(static_match) = app.match_static_file($req.uri().path()) {
+                service_static_fut!($self, $req, static_match, app.static_expires.clone());
+            }
+            return service_proto_fut!($proto, $self, $req, app.callback.clone(), app.handler);
+        }
+    };
+    (metrics $proto:expr, $self:expr, $req:expr) => {
+        if let Some(app) = $self
+            .ctx
+            .apps
+            .as_ref()
+            .and_then(|apps| apps.resolve(&$req, $self.sni.as_deref()))
+        {
+            if let Some(static_match) = app.match_static_file($req.uri().path()) {
+                $self
+                    .ctx
+                    .metrics
+                    .req_static_handled
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if static_match.is_err() {
+                    $self
+                        .ctx
+                        .metrics
+                        .req_static_err
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+                service_static_fut!($self, $req, static_match, app.static_expires.clone());
+            }
+            return service_proto_fut!($proto, $self, $req, app.callback.clone(), app.handler);
+        }
+    };
+}
+
 macro_rules! service_impl {
     ($proto_marker:ty, $proto:expr) => {
         impl<F, Ret> hyper::service::Service<crate::http::HTTPRequest>
@@ -376,7 +762,9 @@ macro_rules! service_impl {
             type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
             fn call(&self, req: crate::http::HTTPRequest) -> Self::Future {
-                service_proto_fut!($proto, self, req)
+                service_client_rules!(self, req);
+                service_apps!($proto, self, req);
+                service_proto_fut!($proto, self, req, self.ctx.callback.clone())
             }
         }
 
@@ -403,21 +791,45 @@ macro_rules! service_impl {
             type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
             fn call(&self, req: crate::http::HTTPRequest) -> Self::Future {
+                service_client_rules!(self, req);
+                service_proxy_fut!($proto, self, req);
+                service_apps!($proto, self, req);
+
                 if let Some(static_match) = crate::files::match_static_file(
                     req.uri().path(),
                     &self.ctx.static_mounts,
                     self.ctx.static_dir_to_file.as_ref(),
                 ) {
-                    if static_match.is_err() {
-                        return Box::pin(async move { Ok::<_, hyper::Error>(crate::http::response_404()) });
-                    }
-                    let expires = self.ctx.static_expires.clone();
-                    return Box::pin(async move {
-                        Ok::<_, hyper::Error>(crate::files::serve_static_file(static_match.unwrap(), expires).await)
-                    });
+                    service_static_fut!(self, req, static_match, self.ctx.static_expires.clone());
                 }
 
-                service_proto_fut!($proto, self, req)
+                service_proto_fut!($proto, self, req, self.ctx.callback.clone())
+            }
+        }
+
+        // Routing lives in its own method, as the `service_*` macros return early,
+        // so that `call` can time whatever future it gets back.
+        impl<F, Ret> WorkerSvc<F, WorkerCTXBase<crate::metrics::ArcWorkerMetrics>, $proto_marker>
+        where
+            F: Fn(
+                    crate::runtime::RuntimeRef,
+                    Arc<tokio::sync::Notify>,
+                    crate::callbacks::ArcCBScheduler,
+                    crate::net::SockAddr,
+                    crate::net::SockAddr,
+                    crate::http::HTTPRequest,
+                    crate::http::HTTPProto,
+                ) -> Ret
+                + Copy
+                + Send
+                + Sync
+                + 'static,
+            Ret: Future<Output = crate::http::HTTPResponse> + Send + 'static,
+        {
+            fn route(&self, req: crate::http::HTTPRequest) -> ServiceFuture {
+                service_client_rules!(self, req);
+                service_apps!(metrics $proto, self, req);
+                service_proto_fut!($proto, self, req, self.ctx.callback.clone())
             }
         }
 
@@ -443,17 +855,21 @@ macro_rules! service_impl {
             type Error = hyper::Error;
             type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
-            fn call(&self, req: crate::http::HTTPRequest) -> Self::Future {
+            fn call(&self, mut req: crate::http::HTTPRequest) -> Self::Future {
                 self.ctx
                     .metrics
                     .req_handled
                     .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                service_proto_fut!($proto, self, req)
+                if let Some(conn) = &self.conn {
+                    conn.request();
+                }
+                let timer = crate::metrics::RequestTimer::new(self.ctx.metrics.clone(), &req);
+                req.extensions_mut().insert(timer.request_metrics());
+                Box::pin(timer.observe(self.route(req)))
             }
         }
 
-        impl<F, Ret> hyper::service::Service<crate::http::HTTPRequest>
-            for WorkerSvc<F, WorkerCTXFiles<crate::metrics::ArcWorkerMetrics>, $proto_marker>
+        impl<F, Ret> WorkerSvc<F, WorkerCTXFiles<crate::metrics::ArcWorkerMetrics>, $proto_marker>
         where
             F: Fn(
                     crate::runtime::RuntimeRef,
@@ -470,15 +886,10 @@ macro_rules! service_impl {
                 + 'static,
             Ret: Future<Output = crate::http::HTTPResponse> + Send + 'static,
         {
-            type Response = crate::http::HTTPResponse;
-            type Error = hyper::Error;
-            type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
-
-            fn call(&self, req: crate::http::HTTPRequest) -> Self::Future {
-                self.ctx
-                    .metrics
-                    .req_handled
-                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            fn route(&self, req: crate::http::HTTPRequest) -> ServiceFuture {
+                service_client_rules!(self, req);
+                service_proxy_fut!($proto, self, req);
+                service_apps!(metrics $proto, self, req);
 
                 if let Some(static_match) = crate::files::match_static_file(
                     req.uri().path(),
@@ -494,15 +905,47 @@ macro_rules! service_impl {
                             .metrics
                             .req_static_err
                             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-                        return Box::pin(async move { Ok::<_, hyper::Error>(crate::http::response_404()) });
                     }
-                    let expires = self.ctx.static_expires.clone();
-                    return Box::pin(async move {
-                        Ok::<_, hyper::Error>(crate::files::serve_static_file(static_match.unwrap(), expires).await)
-                    });
+                    service_static_fut!(self, req, static_match, self.ctx.static_expires.clone());
                 }
 
-                service_proto_fut!($proto, self, req)
+                service_proto_fut!($proto, self, req, self.ctx.callback.clone())
+            }
+        }
+
+        impl<F, Ret> hyper::service::Service<crate::http::HTTPRequest>
+            for WorkerSvc<F, WorkerCTXFiles<crate::metrics::ArcWorkerMetrics>, $proto_marker>
+        where
+            F: Fn(
+                    crate::runtime::RuntimeRef,
+                    Arc<tokio::sync::Notify>,
+                    crate::callbacks::ArcCBScheduler,
+                    crate::net::SockAddr,
+                    crate::net::SockAddr,
+                    crate::http::HTTPRequest,
+                    crate::http::HTTPProto,
+                ) -> Ret
+                + Copy
+                + Send
+                + Sync
+                + 'static,
+            Ret: Future<Output = crate::http::HTTPResponse> + Send + 'static,
+        {
+            type Response = crate::http::HTTPResponse;
+            type Error = hyper::Error;
+            type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+            fn call(&self, mut req: crate::http::HTTPRequest) -> Self::Future {
+                self.ctx
+                    .metrics
+                    .req_handled
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                if let Some(conn) = &self.conn {
+                    conn.request();
+                }
+                let timer = crate::metrics::RequestTimer::new(self.ctx.metrics.clone(), &req);
+                req.extensions_mut().insert(timer.request_metrics());
+                Box::pin(timer.observe(self.route(req)))
             }
         }
     };
@@ -953,12 +1396,138 @@ pub(crate) struct WorkerAcceptorUdsTls {
     pub opts: Arc<tls_listener::rustls::rustls::ServerConfig>,
 }
 
+#[derive(Clone)]
+pub(crate) struct WorkerAcceptorMultiPlain {}
+
+#[derive(Clone)]
+pub(crate) struct WorkerAcceptorMultiTls {
+    pub opts: Arc<tls_listener::rustls::rustls::ServerConfig>,
+}
+
+pub(crate) enum WorkerListener {
+    Tcp(std::net::TcpListener, bool),
+    #[cfg(unix)]
+    Uds(std::os::unix::net::UnixListener, bool),
+}
+
+trait WorkerAcceptorMulti {
+    fn tls_opts(&self) -> Option<Arc<tls_listener::rustls::rustls::ServerConfig>>;
+}
+
+impl WorkerAcceptorMulti for WorkerAcceptorMultiPlain {
+    fn tls_opts(&self) -> Option<Arc<tls_listener::rustls::rustls::ServerConfig>> {
+        None
+    }
+}
+
+impl WorkerAcceptorMulti for WorkerAcceptorMultiTls {
+    fn tls_opts(&self) -> Option<Arc<tls_listener::rustls::rustls::ServerConfig>> {
+        Some(self.opts.clone())
+    }
+}
+
+trait WorkerStreamInfo {
+    fn sni(&self) -> Option<Arc<str>> {
+        None
+    }
+
+    fn tls_info(&self) -> Option<Arc<crate::tls::TlsInfo>> {
+        None
+    }
+
+    #[cfg(unix)]
+    fn peer_cred(&self) -> Option<crate::net::PeerCred> {
+        None
+    }
+
+    fn connection(&self) -> Option<Arc<metrics::ConnectionMetrics>> {
+        None
+    }
+}
+
+impl WorkerStreamInfo for tokio::net::TcpStream {}
+
+#[cfg(unix)]
+impl WorkerStreamInfo for tokio::net::UnixStream {
+    fn peer_cred(&self) -> Option<crate::net::PeerCred> {
+        tokio::net::UnixStream::peer_cred(self).ok().map(Into::into)
+    }
+}
+
+impl<S> WorkerStreamInfo for tls_listener::rustls::server::TlsStream<S>
+where
+    S: WorkerStreamInfo,
+{
+    fn sni(&self) -> Option<Arc<str>> {
+        self.get_ref().1.server_name().map(Into::into)
+    }
+
+    fn tls_info(&self) -> Option<Arc<crate::tls::TlsInfo>> {
+        Some(Arc::new(crate::tls::TlsInfo::new(self.get_ref().1, false)))
+    }
+
+    #[cfg(unix)]
+    fn peer_cred(&self) -> Option<crate::net::PeerCred> {
+        self.get_ref().0.peer_cred()
+    }
+}
+
+impl<S> WorkerStreamInfo for metrics::MeteredStream<S>
+where
+    S: WorkerStreamInfo,
+{
+    fn sni(&self) -> Option<Arc<str>> {
+        self.get_ref().sni()
+    }
+
+    fn tls_info(&self) -> Option<Arc<crate::tls::TlsInfo>> {
+        self.get_ref().tls_info()
+    }
+
+    #[cfg(unix)]
+    fn peer_cred(&self) -> Option<crate::net::PeerCred> {
+        self.get_ref().peer_cred()
+    }
+
+    fn connection(&self) -> Option<Arc<metrics::ConnectionMetrics>> {
+        Some(self.connection_metrics())
+    }
+}
+
+impl WorkerStreamInfo for crate::tls::TlsTcpStream {
+    fn sni(&self) -> Option<Arc<str>> {
+        match self {
+            Self::Rustls(stream) => stream.get_ref().1.server_name().map(Into::into),
+            #[cfg(target_os = "linux")]
+            Self::Kernel(stream) => stream.sni.clone(),
+        }
+    }
+
+    fn tls_info(&self) -> Option<Arc<crate::tls::TlsInfo>> {
+        match self {
+            Self::Rustls(stream) => Some(Arc::new(crate::tls::TlsInfo::new(stream.get_ref().1, false))),
+            #[cfg(target_os = "linux")]
+            Self::Kernel(stream) => Some(stream.info.clone()),
+        }
+    }
+}
+
 pub(crate) trait WorkerAcceptor<L> {
     fn listen(
         &self,
         sig: tokio::sync::watch::Receiver<bool>,
         listener: L,
         backpressure: usize,
+    ) -> impl Future<Output = ()> + Send {
+        self.accept(sig, listener, Arc::new(tokio::sync::Semaphore::new(backpressure)))
+    }
+
+    // Listeners accepting with the same semaphore share the backpressure.
+    fn accept(
+        &self,
+        sig: tokio::sync::watch::Receiver<bool>,
+        listener: L,
+        semaphore: Arc<tokio::sync::Semaphore>,
     ) -> impl Future<Output = ()> + Send;
 }
 
@@ -966,13 +1535,20 @@ macro_rules! acceptor_impl_stream {
     ($proto_marker:ty, $sockwrap:expr, $stream:expr, $addr_remote:expr, $self:expr, $addr_local:expr, $rt:expr, $tasks:expr, $permit:expr, $connsig:expr, $target:expr, $ctx:expr) => {{
         let disconnect_guard = Arc::new(tokio::sync::Notify::new());
         let handle = $self.handle(disconnect_guard.clone());
+        #[cfg(unix)]
+        let addr_remote = $sockwrap($addr_remote).with_peer_cred($stream.peer_cred());
+        #[cfg(not(unix))]
+        let addr_remote = $sockwrap($addr_remote);
         let svc = WorkerSvc {
             f: $target,
             ctx: $ctx,
             rt: $rt,
             disconnect_guard,
             addr_local: $addr_local.clone(),
-            addr_remote: $sockwrap($addr_remote),
+            addr_remote,
+            sni: $stream.sni(),
+            tls: $stream.tls_info(),
+            conn: $stream.connection(),
             _proto: PhantomData::<$proto_marker>,
         };
         $tasks.spawn(handle.call(svc, $stream, $permit, $connsig));
@@ -1016,6 +1592,7 @@ macro_rules! acceptor_impl_match_metrics {
                     .metrics
                     .conn_handled
                     .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                let stream = metrics::MeteredStream::new(stream, $self.metrics.clone());
                 acceptor_impl_stream!(
                     $proto_marker,
                     $sockwrap,
@@ -1043,8 +1620,8 @@ macro_rules! acceptor_impl_match_metrics {
 }
 
 macro_rules! acceptor_impl_loop {
-    ($proto_marker:ty, $sockwrap:expr, $matchi:ident, $self:expr, $sig:expr, $backpressure:expr, $listener:expr, $addr_local:expr) => {{
-        let semaphore = Arc::new(tokio::sync::Semaphore::new($backpressure));
+    ($proto_marker:ty, $sockwrap:expr, $matchi:ident, $self:expr, $sig:expr, $semaphore:expr, $listener:expr, $addr_local:expr) => {{
+        let semaphore = $semaphore;
         let connsig = Arc::new(tokio::sync::Notify::new());
         let mut accept_loop = true;
 
@@ -1084,7 +1661,7 @@ macro_rules! acceptor_impl_loop {
 }
 
 macro_rules! acceptor_impl {
-    ($target_plain:ty, $target_tls:ty, $listeneri:ty, $listenero:ty, $stream:ty, $tlswrap:expr, $sockwrap:expr) => {
+    ($target_plain:ty, $target_tls:ty, $listeneri:ty, $listenero:ty, $stream:ty, $tlsstream:ty, $tlswrap:expr, $sockwrap:expr) => {
         impl<C, H, F, Ret> WorkerAcceptor<$listeneri> for Worker<C, $target_plain, H, F, ()>
         where
             F: Fn(
@@ -1103,18 +1680,28 @@ macro_rules! acceptor_impl {
             Ret: Future<Output = crate::http::HTTPResponse> + 'static,
             C: Clone + Send + Sync + 'static,
             H: Send + Sync + 'static,
-            Worker<C, $target_plain, H, F, ()>: WorkerHandleBuilder<$stream, WorkerSvc<F, C, WorkerMarkerPlain>> + Clone,
+            Worker<C, $target_plain, H, F, ()>:
+                WorkerHandleBuilder<$stream, WorkerSvc<F, C, WorkerMarkerPlain>> + Clone,
         {
-            async fn listen(
+            async fn accept(
                 &self,
                 mut sig: tokio::sync::watch::Receiver<bool>,
                 listener: $listeneri,
-                backpressure: usize,
+                semaphore: Arc<tokio::sync::Semaphore>,
             ) {
                 let listener = <$listenero>::from_std(listener).unwrap();
                 let addr_local = $sockwrap(listener.local_addr().unwrap());
 
-                acceptor_impl_loop!(WorkerMarkerPlain, $sockwrap, acceptor_impl_match, self, sig, backpressure, listener, addr_local)
+                acceptor_impl_loop!(
+                    WorkerMarkerPlain,
+                    $sockwrap,
+                    acceptor_impl_match,
+                    self,
+                    sig,
+                    semaphore,
+                    listener,
+                    addr_local
+                )
             }
         }
 
@@ -1136,23 +1723,32 @@ macro_rules! acceptor_impl {
             Ret: Future<Output = crate::http::HTTPResponse> + 'static,
             C: Clone + Send + Sync + 'static,
             H: Send + Sync + 'static,
-            Worker<C, $target_tls, H, F, ()>:
-                WorkerHandleBuilder<tls_listener::rustls::server::TlsStream<$stream>, WorkerSvc<F, C, WorkerMarkerTls>> + Clone,
+            Worker<C, $target_tls, H, F, ()>: WorkerHandleBuilder<$tlsstream, WorkerSvc<F, C, WorkerMarkerTls>> + Clone,
         {
-            async fn listen(
+            async fn accept(
                 &self,
                 mut sig: tokio::sync::watch::Receiver<bool>,
                 listener: $listeneri,
-                backpressure: usize,
+                semaphore: Arc<tokio::sync::Semaphore>,
             ) {
                 let tls_cfg = self.acceptor.opts.clone();
                 let (mut tls_listener, addr_local) = $tlswrap(tls_cfg, listener).unwrap();
 
-                acceptor_impl_loop!(WorkerMarkerTls, $sockwrap, acceptor_impl_match, self, sig, backpressure, tls_listener, addr_local)
+                acceptor_impl_loop!(
+                    WorkerMarkerTls,
+                    $sockwrap,
+                    acceptor_impl_match,
+                    self,
+                    sig,
+                    semaphore,
+                    tls_listener,
+                    addr_local
+                )
             }
         }
 
-        impl<C, H, F, Ret> WorkerAcceptor<$listeneri> for Worker<C, $target_plain, H, F, crate::metrics::ArcWorkerMetrics>
+        impl<C, H, F, Ret> WorkerAcceptor<$listeneri>
+            for Worker<C, $target_plain, H, F, crate::metrics::ArcWorkerMetrics>
         where
             F: Fn(
                     crate::runtime::RuntimeRef,
@@ -1170,18 +1766,28 @@ macro_rules! acceptor_impl {
             Ret: Future<Output = crate::http::HTTPResponse> + 'static,
             C: Clone + Send + Sync + 'static,
             H: Send + Sync + 'static,
-            Worker<C, $target_plain, H, F, crate::metrics::ArcWorkerMetrics>: WorkerHandleBuilder<$stream, WorkerSvc<F, C, WorkerMarkerPlain>> + Clone,
+            Worker<C, $target_plain, H, F, crate::metrics::ArcWorkerMetrics>:
+                WorkerHandleBuilder<crate::metrics::MeteredStream<$stream>, WorkerSvc<F, C, WorkerMarkerPlain>> + Clone,
         {
-            async fn listen(
+            async fn accept(
                 &self,
                 mut sig: tokio::sync::watch::Receiver<bool>,
                 listener: $listeneri,
-                backpressure: usize,
+                semaphore: Arc<tokio::sync::Semaphore>,
             ) {
                 let listener = <$listenero>::from_std(listener).unwrap();
                 let addr_local = $sockwrap(listener.local_addr().unwrap());
 
-                acceptor_impl_loop!(WorkerMarkerPlain, $sockwrap, acceptor_impl_match_metrics, self, sig, backpressure, listener, addr_local)
+                acceptor_impl_loop!(
+                    WorkerMarkerPlain,
+                    $sockwrap,
+                    acceptor_impl_match_metrics,
+                    self,
+                    sig,
+                    semaphore,
+                    listener,
+                    addr_local
+                )
             }
         }
 
@@ -1204,18 +1810,27 @@ macro_rules! acceptor_impl {
             C: Clone + Send + Sync + 'static,
             H: Send + Sync + 'static,
             Worker<C, $target_tls, H, F, crate::metrics::ArcWorkerMetrics>:
-                WorkerHandleBuilder<tls_listener::rustls::server::TlsStream<$stream>, WorkerSvc<F, C, WorkerMarkerTls>> + Clone,
+                WorkerHandleBuilder<crate::metrics::MeteredStream<$tlsstream>, WorkerSvc<F, C, WorkerMarkerTls>> + Clone,
         {
-            async fn listen(
+            async fn accept(
                 &self,
                 mut sig: tokio::sync::watch::Receiver<bool>,
                 listener: $listeneri,
-                backpressure: usize,
+                semaphore: Arc<tokio::sync::Semaphore>,
             ) {
                 let tls_cfg = self.acceptor.opts.clone();
                 let (mut tls_listener, addr_local) = $tlswrap(tls_cfg, listener).unwrap();
 
-                acceptor_impl_loop!(WorkerMarkerTls, $sockwrap, acceptor_impl_match_metrics, self, sig, backpressure, tls_listener, addr_local)
+                acceptor_impl_loop!(
+                    WorkerMarkerTls,
+                    $sockwrap,
+                    acceptor_impl_match_metrics,
+                    self,
+                    sig,
+                    semaphore,
+                    tls_listener,
+                    addr_local
+                )
             }
         }
     };
@@ -1227,6 +1842,7 @@ acceptor_impl!(
     std::net::TcpListener,
     tokio::net::TcpListener,
     tokio::net::TcpStream,
+    crate::tls::TlsTcpStream,
     crate::tls::tls_tcp_listener,
     crate::net::SockAddr::TCP
 );
@@ -1237,9 +1853,73 @@ acceptor_impl!(
     std::os::unix::net::UnixListener,
     tokio::net::UnixListener,
     tokio::net::UnixStream,
+    tls_listener::rustls::server::TlsStream<tokio::net::UnixStream>,
     crate::tls::tls_uds_listener,
-    crate::net::SockAddr::UDS
+    crate::net::SockAddr::uds
+);
+
+macro_rules! acceptor_multi_impl {
+    ($($bounds:tt)*) => {
+        impl<C, A, H, F, M> WorkerAcceptor<Vec<WorkerListener>> for Worker<C, A, H, F, M>
+        where
+            A: WorkerAcceptorMulti + Send + Sync,
+            C: Clone + Send + Sync + 'static,
+            H: Clone + Send + Sync + 'static,
+            F: Copy + Send + Sync + 'static,
+            M: Clone + Send + Sync + 'static,
+            Worker<C, WorkerAcceptorTcpPlain, H, F, M>: WorkerAcceptor<std::net::TcpListener> + Send + Sync + 'static,
+            Worker<C, WorkerAcceptorTcpTls, H, F, M>: WorkerAcceptor<std::net::TcpListener> + Send + Sync + 'static,
+            $($bounds)*
+        {
+            async fn accept(
+                &self,
+                sig: tokio::sync::watch::Receiver<bool>,
+                listeners: Vec<WorkerListener>,
+                semaphore: Arc<tokio::sync::Semaphore>,
+            ) {
+                let tls_opts = self.acceptor.tls_opts();
+                let mut loops: Vec<Pin<Box<dyn Future<Output = ()> + Send>>> = Vec::with_capacity(listeners.len());
+
+                for listener in listeners {
+                    let sig = sig.clone();
+                    let semaphore = semaphore.clone();
+                    match (listener, tls_opts.clone()) {
+                        (WorkerListener::Tcp(listener, true), Some(opts)) => {
+                            let wrk = self.with_acceptor(WorkerAcceptorTcpTls { opts });
+                            loops.push(Box::pin(async move { wrk.accept(sig, listener, semaphore).await }));
+                        }
+                        (WorkerListener::Tcp(listener, false), _) => {
+                            let wrk = self.with_acceptor(WorkerAcceptorTcpPlain {});
+                            loops.push(Box::pin(async move { wrk.accept(sig, listener, semaphore).await }));
+                        }
+                        #[cfg(unix)]
+                        (WorkerListener::Uds(listener, true), Some(opts)) => {
+                            let wrk = self.with_acceptor(WorkerAcceptorUdsTls { opts });
+                            loops.push(Box::pin(async move { wrk.accept(sig, listener, semaphore).await }));
+                        }
+                        #[cfg(unix)]
+                        (WorkerListener::Uds(listener, false), _) => {
+                            let wrk = self.with_acceptor(WorkerAcceptorUdsPlain {});
+                            loops.push(Box::pin(async move { wrk.accept(sig, listener, semaphore).await }));
+                        }
+                        // the main process rejects these, but never serve a TLS listener as plaintext
+                        (_, None) => log::error!("TLS listener with no SSL configuration, not serving it"),
+                    }
+                }
+
+                futures::future::join_all(loops).await;
+            }
+        }
+    };
+}
+
+#[cfg(unix)]
+acceptor_multi_impl!(
+    Worker<C, WorkerAcceptorUdsPlain, H, F, M>: WorkerAcceptor<std::os::unix::net::UnixListener> + Send + Sync + 'static,
+    Worker<C, WorkerAcceptorUdsTls, H, F, M>: WorkerAcceptor<std::os::unix::net::UnixListener> + Send + Sync + 'static,
 );
+#[cfg(not(unix))]
+acceptor_multi_impl!();
 
 pub(crate) fn init_pymodule(module: &Bound<PyModule>) -> PyResult<()> {
     module.add_class::<WorkerSignal>()?;
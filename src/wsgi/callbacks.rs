@@ -5,14 +5,17 @@ use hyper::{
 use itertools::Itertools;
 use percent_encoding::percent_decode_str;
 use pyo3::{prelude::*, types::PyDict};
+use std::sync::Arc;
 use tokio::sync::oneshot;
 
 use super::{io::WSGIProtocol, types::WSGIBody};
 use crate::{
     callbacks::ArcCBScheduler,
     http::{HTTPProto, HTTPResponseBody, empty_body},
+    metrics::RequestMetrics,
     net::SockAddr,
     runtime::{Runtime, RuntimeRef},
+    tls::TlsInfo,
     utils::log_application_callable_exception,
 };
 
@@ -70,6 +73,37 @@ fn build_wsgi(
     environ_set!(py, environ, "QUERY_STRING", query_string);
     environ_set!(py, environ, "wsgi.url_scheme", scheme.as_str());
     environ_set!(py, environ, "wsgi.input", body);
+    if let Some(cred) = client_addr.peer_cred() {
+        environ_set!(py, environ, "granian.peer_pid", cred.pid);
+        environ_set!(py, environ, "granian.peer_uid", cred.uid);
+        environ_set!(py, environ, "granian.peer_gid", cred.gid);
+    }
+    // TLS variables follow the `mod_ssl` naming
+    if let Some(tls) = req.extensions.get::<Arc<TlsInfo>>() {
+        if let Some((_, version)) = tls.version {
+            environ_set!(py, environ, "SSL_PROTOCOL", version);
+        }
+        if let Some((_, cipher)) = &tls.cipher {
+            environ_set!(py, environ, "SSL_CIPHER", cipher);
+        }
+        if let Some(sni) = &tls.sni {
+            environ_set!(py, environ, "SSL_TLS_SNI", sni);
+        }
+        let mut client_certs = tls.client_cert_chain().into_iter();
+        match client_certs.next() {
+            Some(cert) => {
+                environ_set!(py, environ, "SSL_CLIENT_VERIFY", "SUCCESS");
+                environ_set!(py, environ, "SSL_CLIENT_CERT", cert);
+                if let Some(name) = tls.client_cert_name() {
+                    environ_set!(py, environ, "SSL_CLIENT_S_DN", name);
+                }
+                for (idx, cert) in client_certs.enumerate() {
+                    environ.set_item(format!("SSL_CLIENT_CERT_CHAIN_{idx}"), cert)?;
+                }
+            }
+            None => environ_set!(py, environ, "SSL_CLIENT_VERIFY", "NONE"),
+        }
+    }
 
     if let Some(content_type) = req.headers.remove(header::CONTENT_TYPE) {
         environ_set!(py, environ, "CONTENT_TYPE", content_type.to_str().unwrap_or_default());
@@ -120,14 +154,18 @@ pub(crate) fn call_http(
     server_addr: SockAddr,
     client_addr: SockAddr,
     scheme: HTTPProto,
-    req: request::Parts,
+    mut req: request::Parts,
     body: body::Incoming,
 ) -> oneshot::Receiver<(u16, HeaderMap, HTTPResponseBody)> {
     let (tx, rx) = oneshot::channel();
     let protocol = WSGIProtocol::new(tx);
     let body = WSGIBody::new(rt.clone(), body);
+    let metrics = req.extensions.remove::<RequestMetrics>();
 
     rt.spawn_blocking(move |py| {
+        if let Some(metrics) = metrics {
+            metrics.observe_queue();
+        }
         if let Ok((proto, environ)) = build_wsgi(py, server_addr, client_addr, scheme, req, protocol, body) {
             if let Err(err) = cb.get().cb.call1(py, (proto.clone_ref(py), environ)) {
                 log_application_callable_exception(py, &err);
@@ -1,5 +1,5 @@
 mod callbacks;
-mod http;
+pub(crate) mod http;
 mod io;
 pub(crate) mod serve;
 mod types;
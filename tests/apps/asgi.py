@@ -1,5 +1,6 @@
 import asyncio
 import json
+import os
 import pathlib
 
 import sniffio
@@ -49,6 +50,17 @@ async def sniff_aio_impl(scope, receive, send):
     )
 
 
+async def affinity(scope, receive, send):
+    await send(JSON_RESPONSE)
+    await send(
+        {
+            'type': 'http.response.body',
+            'body': json.dumps(sorted(os.sched_getaffinity(0))).encode('utf8'),
+            'more_body': False,
+        }
+    )
+
+
 async def echo(scope, receive, send):
     await send(PLAINTEXT_RESPONSE)
     more_body = True
@@ -219,6 +231,7 @@ def app(scope, receive, send):
     return {
         '/info': info,
         '/sniffio': sniff_aio_impl,
+        '/affinity': affinity,
         '/echo': echo,
         '/file': pathsend,
         '/ws_reject': ws_reject,
@@ -0,0 +1,31 @@
+import json
+
+
+async def asgi(scope, receive, send):
+    await send({'type': 'http.response.start', 'status': 200, 'headers': [[b'content-type', b'application/json']]})
+    await send(
+        {
+            'type': 'http.response.body',
+            'body': json.dumps(
+                {'app': 'mount', 'interface': 'asgi', 'root_path': scope['root_path'], 'path': scope['path']}
+            ).encode('utf8'),
+            'more_body': False,
+        }
+    )
+
+
+async def rsgi(scope, protocol):
+    protocol.response_bytes(
+        200,
+        [('content-type', 'application/json')],
+        json.dumps({'app': 'mount', 'interface': 'rsgi', 'root_path': None, 'path': scope.path}).encode('utf8'),
+    )
+
+
+def wsgi(environ, protocol):
+    protocol('200 OK', [('content-type', 'application/json')])
+    return [
+        json.dumps(
+            {'app': 'mount', 'interface': 'wsgi', 'root_path': environ['SCRIPT_NAME'], 'path': environ['PATH_INFO']}
+        ).encode('utf8')
+    ]
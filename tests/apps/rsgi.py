@@ -20,6 +20,8 @@ async def info(scope: Scope, protocol: HTTPProtocol):
                 'query_string': scope.query_string,
                 'headers': dict(scope.headers.items()),
                 'authority': scope.authority,
+                'peer_cred': scope.peer_cred,
+                'tls': scope.tls,
             }
         ).encode('utf8'),
     )
@@ -0,0 +1,28 @@
+import json
+
+
+async def asgi(scope, receive, send):
+    if scope['type'] == 'lifespan':
+        return
+    host = dict(scope['headers'])[b'host'].decode('latin-1')
+    await send({'type': 'http.response.start', 'status': 200, 'headers': [[b'content-type', b'application/json']]})
+    await send(
+        {
+            'type': 'http.response.body',
+            'body': json.dumps({'app': 'vhost', 'interface': 'asgi', 'host': host}).encode('utf8'),
+            'more_body': False,
+        }
+    )
+
+
+async def rsgi(scope, protocol):
+    protocol.response_bytes(
+        200,
+        [('content-type', 'application/json')],
+        json.dumps({'app': 'vhost', 'interface': 'rsgi', 'host': scope.headers.get('host')}).encode('utf8'),
+    )
+
+
+def wsgi(environ, protocol):
+    protocol('200 OK', [('content-type', 'application/json')])
+    return [json.dumps({'app': 'vhost', 'interface': 'wsgi', 'host': environ['HTTP_HOST']}).encode('utf8')]
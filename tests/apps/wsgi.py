@@ -12,6 +12,8 @@ def info(environ, protocol):
                 'query_string': environ['QUERY_STRING'],
                 'content_length': environ.get('CONTENT_LENGTH'),
                 'headers': {k: v for k, v in environ.items() if k.startswith('HTTP_')},
+                'peer_cred': [environ.get(f'granian.peer_{key}') for key in ('pid', 'uid', 'gid')],
+                'tls': {k: v for k, v in environ.items() if k.startswith('SSL_')},
             }
         ).encode('utf8')
     ]
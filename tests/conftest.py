@@ -8,6 +8,7 @@ from pathlib import Path
 import pytest
 
 from granian import Granian
+from granian.apps import Mount, VirtualHost
 
 
 def _serve(**kwargs):
@@ -26,6 +27,12 @@ async def _server(
     task_impl='asyncio',
     static_mount=False,
     static_rewrite=False,
+    error_pages=False,
+    vhosts=False,
+    mounts=False,
+    proxy_routes=None,
+    listeners=None,
+    server_kwargs=None,
 ):
     certs_path = Path.cwd() / 'tests' / 'fixtures' / 'tls'
     kwargs = {
@@ -58,6 +65,43 @@ async def _server(
         if static_rewrite:
             kwargs['static_path_dir_to_file'] = 'index.txt'
 
+    if error_pages:
+        kwargs['error_pages_path'] = Path.cwd() / 'tests' / 'fixtures' / 'errors'
+
+    if vhosts is True:
+        kwargs['vhosts'] = [
+            VirtualHost(
+                ['static.local', '*.static.local'],
+                f'tests.apps.{interface}:app',
+                static_path_mount=[Path.cwd() / 'tests' / 'fixtures' / 'static'],
+            )
+        ]
+    elif vhosts:
+        kwargs['vhosts'] = [
+            VirtualHost(hosts, f'tests.apps.vhosts:{vhost_interface or interface}', interface=vhost_interface)
+            for hosts, vhost_interface in vhosts
+        ]
+
+    if mounts is True:
+        kwargs['mounts'] = [
+            Mount('/mnt', f'tests.apps.mounts:{interface}'),
+            Mount('/mnt/sub', f'tests.apps.mounts:{interface}'),
+        ]
+    elif mounts:
+        kwargs['mounts'] = [
+            Mount(prefix, f'tests.apps.mounts:{mount_interface}', interface=mount_interface)
+            for prefix, mount_interface in mounts
+        ]
+
+    if proxy_routes:
+        kwargs['proxy_routes'] = proxy_routes
+
+    if listeners:
+        kwargs['listeners'] = listeners
+
+    if server_kwargs:
+        kwargs.update(server_kwargs)
+
     succeeded, spawn_failures = False, 0
     while spawn_failures < 3:
         proc = mp.get_context('spawn').Process(target=_serve, kwargs=kwargs)
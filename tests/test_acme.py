@@ -0,0 +1,54 @@
+from pathlib import Path
+
+import pytest
+
+from granian._granian import ACMEManager
+from granian.constants import ACMEChallenges
+from granian.errors import ConfigurationError
+from granian.server import Server
+
+
+def test_acme_tls_alpn(tmp_path):
+    server = Server('tests.apps.asgi:app', acme_domains=['example.com'], acme_storage=tmp_path, acme_agree_tos=True)
+    assert server.acme[1] == ['example.com']
+    assert server.acme[4] == 'tls-alpn-01'
+    assert server.ssl_ctx[0]
+    assert server.ssl_ctx[1] == server.ssl_ctx[2] == str(tmp_path.resolve() / 'certificate.pem')
+    assert server.ssl_ctx[-1] == str(tmp_path.resolve())
+    assert server.static_path is None
+
+
+def test_acme_http(tmp_path):
+    server = Server(
+        'tests.apps.asgi:app',
+        acme_domains=['example.com'],
+        acme_storage=tmp_path,
+        acme_agree_tos=True,
+        acme_challenge=ACMEChallenges.http01,
+        static_path_mount=[Path.cwd() / 'tests' / 'fixtures'],
+    )
+    assert server.static_path[0][0] == ('/.well-known/acme-challenge', str(tmp_path.resolve() / 'http-01'))
+    assert server.static_path[0][1][0] == '/static'
+
+
+def test_acme_with_ssl_cert(tmp_path):
+    certs_path = Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    with pytest.raises(ConfigurationError):
+        Server(
+            'tests.apps.asgi:app',
+            acme_domains=['example.com'],
+            acme_storage=tmp_path,
+            acme_agree_tos=True,
+            ssl_cert=certs_path / 'cert.pem',
+            ssl_key=certs_path / 'key.pem',
+        )
+
+
+def test_acme_tos_not_agreed(tmp_path):
+    with pytest.raises(ConfigurationError):
+        Server('tests.apps.asgi:app', acme_domains=['example.com'], acme_storage=tmp_path)
+
+
+def test_acme_invalid_challenge(tmp_path):
+    with pytest.raises(ValueError):
+        ACMEManager('https://localhost/dir', ['example.com'], [], str(tmp_path), 'dns-01')
@@ -0,0 +1,57 @@
+import os
+import sys
+
+import httpx
+import pytest
+
+from granian.constants import CPUAffinity
+from granian.errors import ConfigurationError
+from granian.server import Server
+
+
+pytestmark = pytest.mark.skipif(sys.platform != 'linux', reason='CPU affinity is only supported on Linux')
+
+CPUS_AVAILABLE = sorted(os.sched_getaffinity(0)) if hasattr(os, 'sched_getaffinity') else []
+
+
+@pytest.mark.parametrize(
+    ('policy', 'cpu_list', 'expected'),
+    (
+        (CPUAffinity.none, ['0-3'], [([], False), ([], False), ([], False)]),
+        (CPUAffinity.worker, ['0-1'], [([0], False), ([1], False), ([0], False)]),
+        (CPUAffinity.thread, ['0-3'], [([0, 1], True), ([2, 3], True), ([0, 1], True)]),
+        (CPUAffinity.list, ['0,2', '1'], [([0, 2], False), ([1], False), ([0, 2], False)]),
+    ),
+)
+@pytest.mark.skipif(CPUS_AVAILABLE[:4] != [0, 1, 2, 3], reason='Requires CPUs 0-3 to be available')
+def test_affinity_policies(policy, cpu_list, expected):
+    server = Server('tests.apps.asgi:app', workers=3, runtime_threads=2, cpu_affinity=policy, cpu_list=cpu_list)
+    assert server._affinity == expected
+
+
+@pytest.mark.parametrize(
+    ('policy', 'cpu_list'),
+    (
+        (CPUAffinity.worker, ['0-x']),
+        (CPUAffinity.list, []),
+        (CPUAffinity.worker, ['4096']),
+        (CPUAffinity.list, ['0', '0-100000']),
+    ),
+)
+def test_affinity_invalid(policy, cpu_list):
+    with pytest.raises(ConfigurationError):
+        Server('tests.apps.asgi:app', cpu_affinity=policy, cpu_list=cpu_list)
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('policy', [CPUAffinity.worker, CPUAffinity.list])
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_affinity_worker(asgi_server, runtime_mode, policy):
+    cpu = CPUS_AVAILABLE[-1]
+    async with asgi_server(
+        runtime_mode, ws=False, server_kwargs={'cpu_affinity': policy, 'cpu_list': [str(cpu)]}
+    ) as port:
+        res = httpx.get(f'http://localhost:{port}/affinity')
+
+    assert res.status_code == 200
+    assert res.json() == [cpu]
@@ -1,12 +1,25 @@
+import asyncio
+import hashlib
 import json
+import os
 import pathlib
+import shutil
+import signal
 import socket
 import ssl
+import subprocess
+import sys
+import time
 
 import httpx
 import pytest
 import websockets
 
+from granian.constants import SSLProfiles, SSLProtocols
+from granian.errors import ConfigurationError, SSLConfigurationError
+from granian.net import ClientCertRule, SNICertificate
+from granian.server import Server
+
 
 @pytest.mark.asyncio
 @pytest.mark.parametrize('server_tls', ['asgi', 'rsgi', 'wsgi'], indirect=True)
@@ -42,6 +55,48 @@ async def test_http_scope(server_tls, runtime_mode):
     assert data['scheme'] == 'https'
 
 
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server_tls', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_tls_scope(server_tls, runtime_mode):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    client_cert = (certs_path / 'ccert.pem').read_text()
+    context = ssl.create_default_context(cafile=str(certs_path / 'cert.pem'))
+    context.maximum_version = ssl.TLSVersion.TLSv1_3
+    context.load_cert_chain(str(certs_path / 'ccert.pem'), str(certs_path / 'ckey.pem'))
+
+    async with server_tls(
+        runtime_mode,
+        ws=False,
+        server_kwargs={'ssl_ca': certs_path / 'cacert.pem', 'ssl_client_verify': True},
+    ) as port:
+        res = httpx.get(f'https://localhost:{port}/info', verify=context)
+
+    assert res.status_code == 200
+    data = res.json()
+    interface = server_tls.args[0]
+    if interface == 'asgi':
+        tls = data['extensions']['tls']
+        assert tls['tls_version'] == 0x0304
+        assert tls['cipher_suite'] is not None
+        assert tls['client_cert_chain'] == [client_cert]
+        assert tls['client_cert_name'] == 'CN=client,O=Granian'
+    elif interface == 'rsgi':
+        tls = data['tls']
+        assert tls['version'] == 'TLSv1.3'
+        assert tls['cipher'].startswith('TLS_')
+        assert tls['sni'] == 'localhost'
+        assert tls['client_cert_chain'] == [client_cert]
+        assert tls['client_cert_name'] == 'CN=client,O=Granian'
+    else:
+        tls = data['tls']
+        assert tls['SSL_PROTOCOL'] == 'TLSv1.3'
+        assert tls['SSL_TLS_SNI'] == 'localhost'
+        assert tls['SSL_CLIENT_VERIFY'] == 'SUCCESS'
+        assert tls['SSL_CLIENT_CERT'] == client_cert
+        assert tls['SSL_CLIENT_S_DN'] == 'CN=client,O=Granian'
+
+
 @pytest.mark.asyncio
 @pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
 async def test_asgi_ws_scope(asgi_server, runtime_mode):
@@ -80,3 +135,428 @@ async def test_tls_encrypted_key(rsgi_server):
     assert res.status_code == 200
     data = res.json()
     assert data['scheme'] == 'https'
+
+
+def _peer_cert(port, server_hostname='localhost'):
+    context = ssl.create_default_context()
+    context.check_hostname = False
+    context.verify_mode = ssl.CERT_NONE
+
+    with socket.create_connection(('localhost', port)) as sock:
+        with context.wrap_socket(sock, server_hostname=server_hostname) as ssock:
+            return ssock.getpeercert(binary_form=True)
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_tls_reload(rsgi_server, runtime_mode, tmp_path):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    cert, key = tmp_path / 'cert.pem', tmp_path / 'key.pem'
+    shutil.copy(certs_path / 'cert.pem', cert)
+    shutil.copy(certs_path / 'key.pem', key)
+
+    async with rsgi_server(
+        runtime_mode, tls=True, server_kwargs={'ssl_cert': cert, 'ssl_key': key, 'ssl_reload_interval': 1}
+    ) as port:
+        original = _peer_cert(port)
+
+        cert.write_text('invalid')
+        await asyncio.sleep(2.5)
+        assert _peer_cert(port) == original
+
+        shutil.copy(certs_path / 'rcert.pem', cert)
+        shutil.copy(certs_path / 'rkey.pem', key)
+        await asyncio.sleep(2.5)
+        assert _peer_cert(port) != original
+
+
+@pytest.mark.skipif(sys.platform == 'win32', reason='SIGHUP not available on Windows')
+def test_tls_reload_signal_invalid(tmp_path, server_port):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    cert, key = tmp_path / 'cert.pem', tmp_path / 'key.pem'
+    shutil.copy(certs_path / 'cert.pem', cert)
+    shutil.copy(certs_path / 'key.pem', key)
+    proc = subprocess.Popen(
+        [
+            sys.executable,
+            '-m',
+            'granian',
+            '--interface',
+            'rsgi',
+            '--port',
+            str(server_port),
+            '--ssl-certificate',
+            str(cert),
+            '--ssl-keyfile',
+            str(key),
+            '--no-ws',
+            'tests.apps.rsgi:app',
+        ],
+        stdout=subprocess.PIPE,
+        text=True,
+    )
+
+    try:
+        original = None
+        for _ in range(20):
+            time.sleep(0.5)
+            try:
+                original = _peer_cert(server_port)
+                break
+            except OSError:
+                continue
+
+        cert.write_text('invalid')
+        os.kill(proc.pid, signal.SIGHUP)
+        time.sleep(1)
+        #: the main process refuses to respawn workers which would fail to load the certificate
+        assert proc.poll() is None
+        assert _peer_cert(server_port) == original
+    finally:
+        proc.terminate()
+        output, _ = proc.communicate(timeout=10)
+
+    assert original is not None
+    assert 'keeping current workers' in output
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_tls_sni(rsgi_server, runtime_mode):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    default_cert = ssl.PEM_cert_to_DER_cert((certs_path / 'cert.pem').read_text())
+    sni_cert = ssl.PEM_cert_to_DER_cert((certs_path / 'rcert.pem').read_text())
+    sni_certs = [SNICertificate(['sni.local', '*.sni.local'], certs_path / 'rcert.pem', certs_path / 'rkey.pem')]
+
+    async with rsgi_server(runtime_mode, tls=True, server_kwargs={'ssl_sni_certs': sni_certs}) as port:
+        assert _peer_cert(port, 'sni.local') == sni_cert
+        assert _peer_cert(port, 'www.sni.local') == sni_cert
+        assert _peer_cert(port, 'other.local') == default_cert
+        assert _peer_cert(port) == default_cert
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_tls_ocsp_invalid(rsgi_server, runtime_mode, tmp_path):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    ocsp = tmp_path / 'ocsp.der'
+    ocsp.write_bytes(b'not an OCSP response')
+    expected = ssl.PEM_cert_to_DER_cert((certs_path / 'cert.pem').read_text())
+
+    async with rsgi_server(runtime_mode, tls=True, server_kwargs={'ssl_ocsp_response': ocsp}) as port:
+        assert _peer_cert(port) == expected
+
+
+def _stapled_ocsp(port):
+    proc = subprocess.run(
+        ['openssl', 's_client', '-connect', f'127.0.0.1:{port}', '-servername', 'localhost', '-status'],
+        stdin=subprocess.DEVNULL,
+        capture_output=True,
+        text=True,
+        timeout=10,
+    )
+    return 'OCSP Response Status: successful' in proc.stdout
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(shutil.which('openssl') is None, reason='OCSP stapling inspection requires the openssl CLI')
+@pytest.mark.parametrize(('response', 'stapled'), [('ocsp.der', True), ('cocsp.der', False)])
+async def test_tls_ocsp_cert_id(rsgi_server, response, stapled):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+
+    #: `cocsp.der` is a valid response for a different certificate, thus it should never get stapled
+    async with rsgi_server('st', tls=True, server_kwargs={'ssl_ocsp_response': certs_path / response}) as port:
+        assert _stapled_ocsp(port) is stapled
+
+
+def _tls_session_request(port, session=None):
+    context = ssl.create_default_context()
+    context.check_hostname = False
+    context.verify_mode = ssl.CERT_NONE
+
+    with socket.create_connection(('localhost', port)) as sock:
+        with context.wrap_socket(sock, server_hostname='localhost', session=session) as ssock:
+            ssock.sendall(b'GET /info HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n')
+            #: TLS 1.3 tickets are sent after the handshake, so we need to read the response
+            while ssock.recv(4096):
+                pass
+            return ssock.session, ssock.session_reused
+
+
+@pytest.mark.asyncio
+async def test_tls_session_tickets(rsgi_server):
+    async with rsgi_server('st', ws=False, tls=True, server_kwargs={'workers': 2, 'ssl_session_cache': 0}) as port:
+        session, reused = _tls_session_request(port)
+        assert not reused
+        #: with the session cache disabled, resumption relies on ticket keys shared by workers
+        for _ in range(4):
+            _, reused = _tls_session_request(port, session)
+            assert reused
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(sys.platform == 'win32', reason='SSL session ticket keys rotation not available on Windows')
+async def test_tls_session_tickets_rotation(rsgi_server):
+    server_kwargs = {'workers': 2, 'ssl_session_cache': 0, 'ssl_ticket_rotation': 2}
+    async with rsgi_server('st', ws=False, tls=True, server_kwargs=server_kwargs) as port:
+        #: tickets issued with the rotated keys should be accepted by every worker
+        for _ in range(3):
+            session, _ = _tls_session_request(port)
+            for _ in range(4):
+                _, reused = _tls_session_request(port, session)
+                assert reused
+            await asyncio.sleep(2.5)
+
+
+@pytest.mark.asyncio
+async def test_tls_session_tickets_disabled(rsgi_server):
+    async with rsgi_server(
+        'st', ws=False, tls=True, server_kwargs={'ssl_session_cache': 0, 'ssl_tickets': False}
+    ) as port:
+        session, _ = _tls_session_request(port)
+        _, reused = _tls_session_request(port, session)
+        assert not reused
+
+
+def test_tls_ticket_keys_invalid(tmp_path):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    keys = tmp_path / 'ticket.key'
+    keys.write_bytes(b'0' * 40)
+    with pytest.raises(ConfigurationError):
+        Server(
+            'tests.apps.rsgi:app',
+            ssl_cert=certs_path / 'cert.pem',
+            ssl_key=certs_path / 'key.pem',
+            ssl_ticket_keys=keys,
+        )
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_tls_pkcs12(rsgi_server, runtime_mode):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    expected = ssl.PEM_cert_to_DER_cert((certs_path / 'cert.pem').read_text())
+    server_kwargs = {
+        'ssl_cert': None,
+        'ssl_key': None,
+        'ssl_pkcs12': certs_path / 'bundle.p12',
+        'ssl_key_password': 'foobar',
+    }
+
+    async with rsgi_server(runtime_mode, tls=True, server_kwargs=server_kwargs) as port:
+        assert _peer_cert(port) == expected
+
+
+@pytest.mark.asyncio
+async def test_tls_ciphers(rsgi_server):
+    context = ssl.create_default_context()
+    context.check_hostname = False
+    context.verify_mode = ssl.CERT_NONE
+
+    server_kwargs = {'ssl_ciphers': ['TLS_AES_256_GCM_SHA384'], 'ssl_kx_groups': ['secp384r1']}
+    async with rsgi_server('st', ws=False, tls=True, server_kwargs=server_kwargs) as port:
+        with socket.create_connection(('localhost', port)) as sock:
+            with context.wrap_socket(sock, server_hostname='localhost') as ssock:
+                assert ssock.cipher()[0] == 'TLS_AES_256_GCM_SHA384'
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('tls_max_proto', [ssl.TLSVersion.TLSv1_2, ssl.TLSVersion.TLSv1_3])
+async def test_tls_ktls(rsgi_server, tls_max_proto):
+    context = ssl.create_default_context()
+    context.check_hostname = False
+    context.verify_mode = ssl.CERT_NONE
+    context.maximum_version = tls_max_proto
+
+    #: connections should work both with kernel offload and on the userspace fallback
+    async with rsgi_server('st', ws=False, tls=True, tls_proto='tls1.2', server_kwargs={'ssl_ktls': True}) as port:
+        body = b'x' * (1024 * 1024)
+        with httpx.Client(verify=context) as client:
+            for _ in range(2):
+                res = client.post(f'https://localhost:{port}/echo', content=body)
+                assert res.status_code == 200
+                assert res.content == body
+            res = client.get(f'https://localhost:{port}/info')
+
+    #: TLS 1.3 connections get offloaded only when the kernel module is available
+    ktls = tls_max_proto == ssl.TLSVersion.TLSv1_3 and pathlib.Path('/sys/module/tls').exists()
+    assert res.json()['tls']['ktls'] is ktls
+
+
+def _client_cert_fingerprint():
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    der = ssl.PEM_cert_to_DER_cert((certs_path / 'ccert.pem').read_text())
+    return ':'.join(f'{byte:02X}' for byte in hashlib.sha256(der).digest())
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize(
+    'rule,client_cert,path,status',
+    [
+        (ClientCertRule('/info', cn=['client']), True, '/info', 200),
+        (
+            ClientCertRule('/info', cn=['other', 'client'], fingerprints=[_client_cert_fingerprint()]),
+            True,
+            '/info',
+            200,
+        ),
+        (ClientCertRule('/info', cn=['CLIENT']), True, '/info', 200),
+        (ClientCertRule('/info', cn=['other']), True, '/info', 403),
+        (ClientCertRule('/info', cn=['client'], ou=['ops']), True, '/info', 403),
+        (ClientCertRule('/info', fingerprints=['00' * 32]), True, '/info', 403),
+        (ClientCertRule('/info'), False, '/info', 403),
+        (ClientCertRule('/info/'), False, '/info', 403),
+        (ClientCertRule('/inf'), False, '/info', 200),
+        (ClientCertRule('/admin'), False, '/info', 200),
+    ],
+)
+async def test_tls_client_rules(rsgi_server, rule, client_cert, path, status):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    context = ssl.create_default_context(cafile=str(certs_path / 'cert.pem'))
+    if client_cert:
+        context.load_cert_chain(str(certs_path / 'ccert.pem'), str(certs_path / 'ckey.pem'))
+
+    server_kwargs = {'ssl_ca': certs_path / 'cacert.pem', 'ssl_client_rules': [rule]}
+    async with rsgi_server('st', ws=False, tls=True, server_kwargs=server_kwargs) as port:
+        res = httpx.get(f'https://localhost:{port}{path}', verify=context)
+
+    assert res.status_code == status
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('path', ['/%61dmin', '/%61dmin/x', '//admin', '/./admin', '/admin/../admin', '/%2E%2E/admin'])
+async def test_tls_client_rules_path_normalization(rsgi_server, path):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    context = ssl.create_default_context(cafile=str(certs_path / 'cert.pem'))
+
+    server_kwargs = {'ssl_ca': certs_path / 'cacert.pem', 'ssl_client_rules': [ClientCertRule('/admin')]}
+    async with rsgi_server('st', ws=False, tls=True, server_kwargs=server_kwargs) as port:
+        #: send the raw path, as HTTP clients normalise it
+        with socket.create_connection(('localhost', port)) as sock:
+            with context.wrap_socket(sock, server_hostname='localhost') as tls_sock:
+                tls_sock.sendall(f'GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n'.encode())
+                res = tls_sock.recv(1024)
+
+    assert res.split(b' ', 2)[1] == b'403'
+
+
+@pytest.mark.parametrize(
+    'server_kwargs',
+    [
+        {'ssl_client_rules': [ClientCertRule('/admin')]},
+        {'ssl_ca': 'cacert.pem', 'ssl_client_rules': [ClientCertRule('admin')]},
+        {'ssl_ca': 'cacert.pem', 'ssl_client_rules': [ClientCertRule('/admin', fingerprints=['AB:CD'])]},
+    ],
+)
+def test_tls_client_rules_invalid(server_kwargs):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    if 'ssl_ca' in server_kwargs:
+        server_kwargs['ssl_ca'] = certs_path / server_kwargs['ssl_ca']
+    with pytest.raises(ConfigurationError):
+        Server('tests.apps.rsgi:app', ssl_cert=certs_path / 'cert.pem', ssl_key=certs_path / 'key.pem', **server_kwargs)
+
+
+def test_tls_profile():
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    server = Server(
+        'tests.apps.rsgi:app',
+        ssl_cert=certs_path / 'cert.pem',
+        ssl_key=certs_path / 'key.pem',
+        ssl_profile=SSLProfiles.intermediate,
+        ssl_kx_groups=['x25519'],
+    )
+    assert server.ssl_ctx[4] == 'tls1.2'
+    assert 'TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256' in server.ssl_ctx[13]
+    assert server.ssl_ctx[14] == ['X25519']
+
+
+@pytest.mark.parametrize(
+    ('profile', 'proto_min', 'expected'),
+    [
+        (SSLProfiles.intermediate, None, 'tls1.2'),
+        (SSLProfiles.intermediate, SSLProtocols.tls13, 'tls1.3'),
+        (SSLProfiles.modern, None, 'tls1.3'),
+        (SSLProfiles.modern, SSLProtocols.tls12, 'tls1.3'),
+        (None, None, 'tls1.3'),
+    ],
+)
+def test_tls_profile_protocol_min(profile, proto_min, expected):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    server = Server(
+        'tests.apps.rsgi:app',
+        ssl_cert=certs_path / 'cert.pem',
+        ssl_key=certs_path / 'key.pem',
+        ssl_profile=profile,
+        ssl_protocol_min=proto_min,
+    )
+    assert server.ssl_ctx[4] == expected
+
+
+@pytest.mark.parametrize(
+    'server_kwargs',
+    [
+        {'ssl_ciphers': ['TLS_RSA_WITH_AES_128_CBC_SHA']},
+        {'ssl_ciphers': ['TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256']},
+        {'ssl_kx_groups': ['ffdhe2048']},
+    ],
+)
+def test_tls_ciphers_invalid(server_kwargs):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    with pytest.raises(ConfigurationError):
+        Server('tests.apps.rsgi:app', ssl_cert=certs_path / 'cert.pem', ssl_key=certs_path / 'key.pem', **server_kwargs)
+
+
+@pytest.mark.parametrize(
+    'server_kwargs,param',
+    [
+        ({'ssl_cert': 'cert.pem', 'ssl_key': 'rkey.pem'}, 'ssl_certificate'),
+        ({'ssl_cert': 'pcert.pem', 'ssl_key': 'pkey.pem', 'ssl_key_password': 'wrong'}, 'ssl_certificate'),
+        ({'ssl_cert': 'pcert.pem', 'ssl_key': 'pkey.pem'}, 'ssl_certificate'),
+        ({'ssl_cert': 'cert.pem', 'ssl_key': 'key.pem', 'ssl_ca': 'key.pem'}, 'ssl_ca'),
+    ],
+)
+def test_tls_validation(server_kwargs, param):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    kwargs = {key: certs_path / value if key != 'ssl_key_password' else value for key, value in server_kwargs.items()}
+    with pytest.raises(SSLConfigurationError) as exc:
+        Server('tests.apps.rsgi:app', **kwargs)
+    assert exc.value.param == param
+
+
+def test_tls_validation_chain_order(tmp_path):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    chain = tmp_path / 'chain.pem'
+    chain.write_bytes((certs_path / 'ccert.pem').read_bytes() + (certs_path / 'cert.pem').read_bytes())
+    with pytest.raises(SSLConfigurationError) as exc:
+        Server('tests.apps.rsgi:app', ssl_cert=chain, ssl_key=certs_path / 'ckey.pem')
+    assert 'not in order' in exc.value.message
+
+
+def _der_mangle(der, mangle):
+    #: the fixture certificate uses a 2 bytes long form length for the outer sequence
+    assert der[:2] == b'\x30\x82'
+    body = der[4:]
+    if mangle == 'truncated':
+        return der[: len(der) // 2]
+    if mangle == 'inner_truncated':
+        body = body[: len(body) // 2]
+        return b'\x30\x82' + len(body).to_bytes(2, 'big') + body
+    if mangle == 'length_past_end':
+        return b'\x30\x84\x7f\xff\xff\xff' + body
+    if mangle == 'length_too_long':
+        return b'\x30\x85' + len(body).to_bytes(5, 'big') + body
+    if mangle == 'length_not_minimal':
+        return b'\x30\x83' + len(body).to_bytes(3, 'big') + body
+    return der
+
+
+@pytest.mark.parametrize(
+    'mangle', ['truncated', 'inner_truncated', 'length_past_end', 'length_too_long', 'length_not_minimal']
+)
+def test_tls_validation_malformed_der(tmp_path, mangle):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    der = ssl.PEM_cert_to_DER_cert((certs_path / 'cert.pem').read_text())
+    cert = tmp_path / 'cert.pem'
+    cert.write_text(ssl.DER_cert_to_PEM_cert(_der_mangle(der, mangle)))
+    with pytest.raises(SSLConfigurationError):
+        Server('tests.apps.rsgi:app', ssl_cert=cert, ssl_key=certs_path / 'key.pem')
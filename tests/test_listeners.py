@@ -0,0 +1,56 @@
+import socket
+import sys
+from contextlib import closing
+
+import httpx
+import pytest
+
+from granian.errors import ConfigurationError
+from granian.net import Listener
+from granian.server import Server
+
+
+def _free_port():
+    with closing(socket.socket(socket.AF_INET, socket.SOCK_STREAM)) as sock:
+        sock.bind(('localhost', 0))
+        return sock.getsockname()[1]
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server_tls', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_listeners_mixed_tls(server_tls, runtime_mode):
+    plain_port = _free_port()
+
+    async with server_tls(runtime_mode, ws=False, listeners=[Listener(port=plain_port)]) as port:
+        res_tls = httpx.get(f'https://localhost:{port}/info', verify=False)
+        res_plain = httpx.get(f'http://localhost:{plain_port}/info')
+
+    assert res_tls.status_code == 200
+    assert res_tls.json()['scheme'] == 'https'
+    assert res_plain.status_code == 200
+    assert res_plain.json()['scheme'] == 'http'
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(sys.platform == 'win32', reason='UDS not available on Windows')
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_listeners_uds(server, runtime_mode, tmp_path):
+    uds = tmp_path / 'granian.sock'
+
+    async with server(runtime_mode, ws=False, listeners=[Listener(uds=uds)]) as port:
+        res_tcp = httpx.get(f'http://localhost:{port}/info')
+        with httpx.Client(transport=httpx.HTTPTransport(uds=str(uds))) as client:
+            res_uds = client.get('http://localhost/info')
+
+    assert res_tcp.status_code == 200
+    assert res_uds.status_code == 200
+    assert res_uds.json()['path'] == '/info'
+
+
+def test_listeners_tls_without_ssl():
+    server = Server('tests.apps.rsgi:app', listeners=[Listener(port=_free_port(), tls=True)])
+
+    with pytest.raises(ConfigurationError):
+        server.serve()
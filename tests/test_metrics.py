@@ -0,0 +1,159 @@
+import asyncio
+import pathlib
+import shutil
+import socket
+import sys
+from contextlib import closing
+
+import httpx
+import pytest
+import websockets
+import websockets.exceptions
+
+
+pytestmark = pytest.mark.skipif(sys.platform == 'win32', reason='Metrics are not supported on Windows')
+
+
+def _free_port():
+    with closing(socket.socket(socket.AF_INET, socket.SOCK_STREAM)) as sock:
+        sock.bind(('localhost', 0))
+        return sock.getsockname()[1]
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_request_histograms(server, runtime_mode):
+    interface = server.args[0]
+    metrics_port = _free_port()
+    server_kwargs = {
+        'metrics_enabled': True,
+        'metrics_port': metrics_port,
+        'metrics_scrape_interval': 1,
+        'metrics_buckets': [0.5, 0.001, 10],
+    }
+
+    async with server(runtime_mode, ws=False, server_kwargs=server_kwargs) as port:
+        for _ in range(3):
+            res = httpx.get(f'http://localhost:{port}/info')
+            assert res.status_code == 200
+        await asyncio.sleep(1.5)
+        res = httpx.get(f'http://localhost:{metrics_port}/')
+
+    assert res.status_code == 200
+    lines = res.text.splitlines()
+    labels = f'worker="1",interface="{interface}",protocol="HTTP/1.1"'
+    for metric in ['request_duration_seconds', 'request_first_byte_seconds', 'request_queue_seconds']:
+        assert f'# TYPE granian_{metric} histogram' in lines
+        buckets = [line for line in lines if line.startswith(f'granian_{metric}_bucket{{{labels},')]
+        assert [line.split('le="')[1].split('"')[0] for line in buckets] == ['0.001', '0.5', '10', '+Inf']
+        assert buckets[-1].endswith(' 3')
+        assert f'granian_{metric}_count{{{labels}}} 3' in lines
+
+
+@pytest.mark.asyncio
+async def test_tls_cert_expiry(rsgi_server, tmp_path):
+    certs_path = pathlib.Path.cwd() / 'tests' / 'fixtures' / 'tls'
+    cert, key = tmp_path / 'cert.pem', tmp_path / 'key.pem'
+    shutil.copy(certs_path / 'cert.pem', cert)
+    shutil.copy(certs_path / 'key.pem', key)
+    metrics_port = _free_port()
+    server_kwargs = {
+        'metrics_enabled': True,
+        'metrics_port': metrics_port,
+        'ssl_cert': cert,
+        'ssl_key': key,
+        'ssl_reload_interval': 1,
+    }
+
+    def _expiry():
+        res = httpx.get(f'http://localhost:{metrics_port}/')
+        prefix = f'granian_tls_certificate_expiry_timestamp{{cert="{cert.resolve()}"}} '
+        return next(int(line[len(prefix) :]) for line in res.text.splitlines() if line.startswith(prefix))
+
+    async with rsgi_server('st', ws=False, tls=True, server_kwargs=server_kwargs):
+        await asyncio.sleep(1.5)
+        original = _expiry()
+        assert original == _expiry()
+
+        #: the cached expiry gets refreshed when the certificate file changes
+        shutil.copy(certs_path / 'rcert.pem', cert)
+        shutil.copy(certs_path / 'rkey.pem', key)
+        assert _expiry() > original
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+async def test_request_counters(server):
+    metrics_port = _free_port()
+    server_kwargs = {'metrics_enabled': True, 'metrics_port': metrics_port, 'metrics_scrape_interval': 1}
+
+    async with server('st', ws=False, server_kwargs=server_kwargs) as port:
+        for _ in range(2):
+            httpx.get(f'http://localhost:{port}/info')
+        httpx.post(f'http://localhost:{port}/echo', content=b'test')
+        httpx.get(f'http://localhost:{port}/err_app')
+        await asyncio.sleep(1.5)
+        res = httpx.get(f'http://localhost:{metrics_port}/')
+
+    lines = res.text.splitlines()
+    assert 'granian_requests_by_status{worker="1",status="200"} 3' in lines
+    assert 'granian_requests_by_status{worker="1",status="500"} 1' in lines
+    assert 'granian_requests_by_method{worker="1",method="GET"} 3' in lines
+    assert 'granian_requests_by_method{worker="1",method="POST"} 1' in lines
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi'], indirect=True)
+async def test_websocket_metrics(server):
+    metrics_port = _free_port()
+    server_kwargs = {'metrics_enabled': True, 'metrics_port': metrics_port, 'metrics_scrape_interval': 1}
+
+    async with server('st', server_kwargs=server_kwargs) as port:
+        async with websockets.connect(f'ws://localhost:{port}/ws_echo') as ws:
+            await ws.send('foo')
+            await ws.recv()
+            await ws.send(b'test')
+            await ws.recv()
+        with pytest.raises(websockets.exceptions.InvalidStatus):
+            async with websockets.connect(f'ws://localhost:{port}/ws_reject'):
+                pass
+        await asyncio.sleep(1.5)
+        res = httpx.get(f'http://localhost:{metrics_port}/')
+
+    lines = res.text.splitlines()
+    assert 'granian_websocket_connections_active{worker="1"} 0' in lines
+    assert 'granian_websocket_upgrades_accepted{worker="1"} 1' in lines
+    assert 'granian_websocket_upgrades_rejected{worker="1"} 1' in lines
+    for direction in ['received', 'sent']:
+        assert f'granian_websocket_messages_{direction}{{worker="1",type="text"}} 1' in lines
+        assert f'granian_websocket_messages_{direction}{{worker="1",type="binary"}} 1' in lines
+        assert f'granian_websocket_bytes_{direction}{{worker="1",type="text"}} 3' in lines
+        assert f'granian_websocket_bytes_{direction}{{worker="1",type="binary"}} 4' in lines
+    assert 'granian_websocket_close_codes_received{worker="1",code="1000"} 1' in lines
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+async def test_connection_metrics(server):
+    interface = server.args[0]
+    metrics_port = _free_port()
+    server_kwargs = {'metrics_enabled': True, 'metrics_port': metrics_port, 'metrics_scrape_interval': 1}
+
+    async with server('st', ws=False, server_kwargs=server_kwargs) as port:
+        with httpx.Client() as client:
+            for _ in range(3):
+                client.post(f'http://localhost:{port}/echo', content=b'test')
+        await asyncio.sleep(1.5)
+        res = httpx.get(f'http://localhost:{metrics_port}/')
+
+    lines = res.text.splitlines()
+    values = dict(line.rsplit(' ', 1) for line in lines if not line.startswith('#'))
+    assert int(values['granian_bytes_received{worker="1"}']) > 0
+    assert int(values['granian_bytes_sent{worker="1"}']) > 0
+    # the test server readiness check opens a connection with no requests
+    labels = f'worker="1",interface="{interface}"'
+    assert f'granian_connection_duration_seconds_count{{{labels}}} 2' in lines
+    assert f'granian_connection_requests_bucket{{{labels},le="2"}} 1' in lines
+    assert f'granian_connection_requests_bucket{{{labels},le="5"}} 2' in lines
+    assert f'granian_connection_requests_sum{{{labels}}} 3' in lines
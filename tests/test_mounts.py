@@ -0,0 +1,57 @@
+import httpx
+import pytest
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+@pytest.mark.parametrize(
+    'path,root_path',
+    [('/mnt', '/mnt'), ('/mnt/info', '/mnt'), ('/mnt/sub/info', '/mnt/sub'), ('/mnt/subpath', '/mnt')],
+)
+async def test_mount_prefix(server, runtime_mode, path, root_path):
+    async with server(runtime_mode, ws=False, mounts=True) as port:
+        res = httpx.get(f'http://localhost:{port}{path}')
+
+    assert res.status_code == 200
+    data = res.json()
+    assert data['app'] == 'mount'
+    assert data['root_path'] == root_path
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_mount_prefix_rsgi(rsgi_server, runtime_mode):
+    async with rsgi_server(runtime_mode, ws=False, mounts=True) as port:
+        res = httpx.get(f'http://localhost:{port}/mnt/info')
+
+    assert res.status_code == 200
+    data = res.json()
+    assert data['app'] == 'mount'
+    assert data['path'] == '/mnt/info'
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_mount_fallback(server, runtime_mode):
+    async with server(runtime_mode, ws=False, mounts=True) as port:
+        res = httpx.get(f'http://localhost:{port}/info')
+
+    assert res.status_code == 200
+    assert 'app' not in res.json()
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_mount_interfaces(server, runtime_mode):
+    mounts = [('/admin', 'wsgi'), ('/api', 'asgi'), ('/rpc', 'rsgi')]
+    async with server(runtime_mode, ws=False, mounts=mounts) as port:
+        responses = {prefix: httpx.get(f'http://localhost:{port}{prefix}/info') for prefix, _ in mounts}
+
+    for prefix, interface in mounts:
+        assert responses[prefix].status_code == 200
+        data = responses[prefix].json()
+        assert data['app'] == 'mount'
+        assert data['interface'] == interface
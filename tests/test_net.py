@@ -0,0 +1,119 @@
+import os
+import signal
+import socket
+import subprocess
+import sys
+import threading
+import time
+
+import httpx
+import pytest
+
+from granian.net import SocketSpec, TCPSettings
+
+
+@pytest.mark.skipif(sys.platform != 'linux', reason='TCP options inspection requires Linux')
+def test_tcp_settings():
+    settings = TCPSettings(
+        keepalive_idle=30,
+        keepalive_interval=5,
+        keepalive_count=3,
+        defer_accept=2,
+        user_timeout=10_000,
+    )
+    holder = SocketSpec('127.0.0.1', 0, 128, settings.as_opts()).build()
+    sock = socket.socket(fileno=os.dup(holder.get_fd()))
+
+    try:
+        assert sock.getsockopt(socket.SOL_SOCKET, socket.SO_KEEPALIVE) == 1
+        assert sock.getsockopt(socket.IPPROTO_TCP, socket.TCP_KEEPIDLE) == 30
+        assert sock.getsockopt(socket.IPPROTO_TCP, socket.TCP_KEEPINTVL) == 5
+        assert sock.getsockopt(socket.IPPROTO_TCP, socket.TCP_KEEPCNT) == 3
+        assert sock.getsockopt(socket.IPPROTO_TCP, socket.TCP_USER_TIMEOUT) == 10_000
+        assert sock.getsockopt(socket.IPPROTO_TCP, socket.TCP_DEFER_ACCEPT) > 0
+    finally:
+        sock.close()
+
+
+@pytest.mark.skipif(sys.platform == 'win32' or not socket.has_ipv6, reason='IPv6 not available')
+@pytest.mark.parametrize('ipv6_only', [True, False])
+def test_tcp_settings_ipv6_only(ipv6_only):
+    holder = SocketSpec('::1', 0, 128, TCPSettings(ipv6_only=ipv6_only).as_opts()).build()
+    sock = socket.socket(fileno=os.dup(holder.get_fd()))
+
+    try:
+        assert bool(sock.getsockopt(socket.IPPROTO_IPV6, socket.IPV6_V6ONLY)) is ipv6_only
+    finally:
+        sock.close()
+
+
+@pytest.mark.skipif(sys.platform == 'win32', reason='Socket handoff not available on Windows')
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+def test_handoff(tmp_path, server_port, runtime_mode):
+    pid_file = tmp_path / 'granian.pid'
+    proc = subprocess.Popen(
+        [
+            sys.executable,
+            '-m',
+            'granian',
+            '--interface',
+            'asgi',
+            '--port',
+            str(server_port),
+            '--workers',
+            '2',
+            '--runtime-mode',
+            runtime_mode,
+            '--no-ws',
+            '--pid-file',
+            str(pid_file),
+            'tests.apps.asgi:app',
+        ],
+    )
+    new_pid = None
+    done, results = threading.Event(), []
+
+    def _requests():
+        while not done.is_set():
+            try:
+                results.append(httpx.get(f'http://127.0.0.1:{server_port}/info').status_code)
+            except httpx.TransportError as exc:
+                results.append(exc)
+
+    try:
+        for _ in range(20):
+            time.sleep(0.5)
+            try:
+                httpx.get(f'http://127.0.0.1:{server_port}/info')
+                break
+            except httpx.TransportError:
+                continue
+
+        runner = threading.Thread(target=_requests)
+        runner.start()
+        try:
+            time.sleep(0.5)
+            os.kill(proc.pid, signal.SIGUSR2)
+            #: the old main process exits once the new one took over the sockets and its workers drained
+            proc.wait(timeout=30)
+            new_pid = int(pid_file.read_text())
+            time.sleep(0.5)
+        finally:
+            done.set()
+            runner.join()
+    finally:
+        if proc.poll() is None:
+            proc.terminate()
+            proc.wait(timeout=5)
+        if new_pid is not None:
+            os.kill(new_pid, signal.SIGTERM)
+            for _ in range(20):
+                try:
+                    os.kill(new_pid, 0)
+                except ProcessLookupError:
+                    break
+                time.sleep(0.25)
+
+    assert new_pid != proc.pid
+    assert results
+    assert all(result == 200 for result in results)
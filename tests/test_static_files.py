@@ -91,3 +91,26 @@ async def test_static_files_multi(server_static_files, runtime_mode):
     assert res2.status_code == 200
     assert res1.headers.get('content-type') == 'image/png'
     assert res2.headers.get('content-type') == 'application/x-x509-ca-cert'
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server_static_files', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+@pytest.mark.parametrize(
+    'accept,content_type',
+    [
+        ('text/html', 'text/html; charset=utf-8'),
+        ('application/json', 'application/json'),
+        ('text/html;q=0.5, application/json', 'application/json'),
+    ],
+)
+async def test_static_files_error_pages(server_static_files, runtime_mode, accept, content_type):
+    async with server_static_files(runtime_mode, ws=False, error_pages=True) as port:
+        res = httpx.get(f'http://localhost:{port}/static/missing.png', headers={'accept': accept})
+
+    assert res.status_code == 404
+    assert res.headers.get('content-type') == content_type
+    if content_type == 'application/json':
+        assert res.json() == {'error': 'not_found'}
+    else:
+        assert '<h1>Not found</h1>' in res.text
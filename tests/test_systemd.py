@@ -0,0 +1,53 @@
+import socket
+import subprocess
+import sys
+import time
+from contextlib import closing
+
+import httpx
+import pytest
+
+
+@pytest.mark.skipif(sys.platform != 'linux', reason='systemd socket activation is available on Linux only')
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+def test_listen_fds(runtime_mode):
+    with closing(socket.socket(socket.AF_INET, socket.SOCK_STREAM)) as sock:
+        sock.bind(('127.0.0.1', 0))
+        sock.listen()
+        port = sock.getsockname()[1]
+
+        #: the shell keeps its PID through `exec`, so it can set `LISTEN_PID` for granian
+        proc = subprocess.Popen(
+            [
+                'sh',
+                '-c',
+                f'export LISTEN_PID=$$ LISTEN_FDS=1 LISTEN_FDNAMES=http; exec "$0" "$@" 3<&{sock.fileno()}',
+                sys.executable,
+                '-m',
+                'granian',
+                '--interface',
+                'asgi',
+                '--runtime-mode',
+                runtime_mode,
+                '--no-ws',
+                'tests.apps.asgi:app',
+            ],
+            pass_fds=(sock.fileno(),),
+        )
+
+    try:
+        res = None
+        for _ in range(10):
+            time.sleep(0.5)
+            try:
+                res = httpx.get(f'http://127.0.0.1:{port}/info')
+                break
+            except httpx.TransportError:
+                continue
+    finally:
+        proc.terminate()
+        proc.wait(timeout=5)
+
+    assert res is not None
+    assert res.status_code == 200
+    assert res.json()['path'] == '/info'
@@ -1,6 +1,7 @@
 import asyncio
 import multiprocessing as mp
 import os
+import socket
 import stat
 import sys
 from contextlib import asynccontextmanager
@@ -190,3 +191,60 @@ async def test_uds_default_file_permission(asgi_server, runtime_mode):
 async def test_uds_configurable_file_permission(asgi_server, runtime_mode):
     async with asgi_server(runtime_mode, ws=False, uds_permissions=0o666):
         assert stat.S_IMODE(os.stat('granian.sock').st_mode) == 0o666
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(IS_WIN, reason='no UDS on win')
+@pytest.mark.skipif(bool(os.getenv('PGO_RUN')), reason='PGO build')
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_uds_configurable_file_owner(asgi_server, runtime_mode):
+    async with asgi_server(runtime_mode, ws=False, uds_owner=os.getuid(), uds_group=os.getgid()):
+        sock_stat = os.stat('granian.sock')
+        assert sock_stat.st_uid == os.getuid()
+        assert sock_stat.st_gid == os.getgid()
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(IS_WIN, reason='no UDS on win')
+@pytest.mark.skipif(bool(os.getenv('PGO_RUN')), reason='PGO build')
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_uds_stale_file_cleanup(asgi_server, runtime_mode, http_client):
+    Path('granian.sock').unlink(missing_ok=True)
+    stale = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+    stale.bind('granian.sock')
+    stale.close()
+
+    async with asgi_server(runtime_mode, ws=False):
+        res = http_client.get('http://granian/info')
+
+    assert res.status_code == 200
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(sys.platform != 'linux', reason='abstract sockets are available on Linux only')
+@pytest.mark.skipif(bool(os.getenv('PGO_RUN')), reason='PGO build')
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_uds_abstract(asgi_server, runtime_mode):
+    async with asgi_server(runtime_mode, ws=False, uds=Path('@granian-test')):
+        with httpx.Client(transport=httpx.HTTPTransport(uds='\0granian-test')) as client:
+            res = client.get('http://granian/info')
+
+    assert res.status_code == 200
+    assert not Path('@granian-test').exists()
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(IS_WIN, reason='no UDS on win')
+@pytest.mark.skipif(bool(os.getenv('PGO_RUN')), reason='PGO build')
+@pytest.mark.parametrize('interface', ['asgi', 'rsgi', 'wsgi'])
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_uds_peer_cred(interface, runtime_mode, http_client):
+    async with _server(interface, runtime_mode, ws=False):
+        res = http_client.get('http://granian/info')
+
+    assert res.status_code == 200
+    data = res.json()
+    if interface == 'asgi':
+        assert data['extensions']['unix.peer_cred'] == {'pid': os.getpid(), 'uid': os.getuid(), 'gid': os.getgid()}
+    else:
+        assert data['peer_cred'] == [os.getpid(), os.getuid(), os.getgid()]
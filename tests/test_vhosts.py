@@ -0,0 +1,59 @@
+import httpx
+import pytest
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+@pytest.mark.parametrize('host', ['static.local', 'sub.static.local', 'static.local:8000'])
+async def test_vhost_routing(server, runtime_mode, host):
+    async with server(runtime_mode, ws=False, vhosts=True) as port:
+        res = httpx.get(f'http://localhost:{port}/static/media.png', headers={'host': host})
+
+    assert res.status_code == 200
+    assert res.headers.get('content-type') == 'image/png'
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_vhost_fallback(server, runtime_mode):
+    async with server(runtime_mode, ws=False, vhosts=True) as port:
+        res = httpx.get(f'http://localhost:{port}/info')
+        res_static = httpx.get(f'http://localhost:{port}/static/media.png')
+
+    assert res.status_code == 200
+    assert res_static.headers.get('content-type') != 'image/png'
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+@pytest.mark.parametrize('host', ['one.local', 'two.local', 'sub.two.local'])
+async def test_vhost_app(server, runtime_mode, host):
+    vhosts = [(['one.local'], None), (['two.local', '*.two.local'], None)]
+    async with server(runtime_mode, ws=False, vhosts=vhosts) as port:
+        res = httpx.get(f'http://localhost:{port}/info', headers={'host': host})
+
+    assert res.status_code == 200
+    data = res.json()
+    assert data['app'] == 'vhost'
+    assert data['host'] == host
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize('server', ['asgi', 'rsgi', 'wsgi'], indirect=True)
+@pytest.mark.parametrize('runtime_mode', ['mt', 'st'])
+async def test_vhost_interfaces(server, runtime_mode):
+    vhosts = [(['asgi.local'], 'asgi'), (['rsgi.local'], 'rsgi'), (['wsgi.local'], 'wsgi')]
+    async with server(runtime_mode, ws=False, vhosts=vhosts) as port:
+        responses = {
+            interface: httpx.get(f'http://localhost:{port}/info', headers={'host': hosts[0]})
+            for hosts, interface in vhosts
+        }
+
+    for hosts, interface in vhosts:
+        assert responses[interface].status_code == 200
+        data = responses[interface].json()
+        assert data['interface'] == interface
+        assert data['host'] == hosts[0]